@@ -0,0 +1,76 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - Everything that needs the time (how long a game
+//   took, how much of a countdown is left, timestamps in
+//   the event log) asks a Clock, so tests and replays
+//   can use a ManualClock instead of waiting.
+// - Times are durations since the Unix epoch. Only the
+//   differences matter for a game; the epoch just makes
+//   the timestamps in the log meaningful.
+//
+
+use std::cell::Cell;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()
+    }
+}
+
+// A clock that only moves when it is told to
+//
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ManualClock {
+    now: Cell<Duration>,
+}
+
+impl ManualClock {
+    pub fn new(now: Duration) -> ManualClock {
+        ManualClock { now: Cell::new(now) }
+    }
+
+    pub fn set(&self, now: Duration) {
+        self.now.set(now);
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock_moves_when_told() {
+        let clock = ManualClock::new(Duration::from_secs(10));
+        assert_eq!(clock.now(), Duration::from_secs(10));
+
+        clock.advance(Duration::from_millis(1500));
+        assert_eq!(clock.now(), Duration::from_millis(11_500));
+
+        clock.set(Duration::from_secs(3));
+        assert_eq!(clock.now(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn system_clock_is_after_the_epoch() {
+        assert!(SystemClock.now() > Duration::from_secs(1_600_000_000));
+    }
+}
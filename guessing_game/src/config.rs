@@ -0,0 +1,700 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - Config::new takes ownership of an iterator of
+//   arguments (like std::env::args) so it can be
+//   exercised from tests without touching the real
+//   command line.
+//
+
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::replay::ExportFormat;
+use crate::secret::SecretType;
+use crate::solver::StrategyKind;
+
+pub const USAGE: &str = "\
+Usage: guessing_game [OPTIONS]
+       guessing_game scores [--difficulty <D>] [--type <T>] [--min <N>] [--max <N>] [--reset] [--scores-file <PATH>]
+       guessing_game serve --port <PORT> [--difficulty <D>] [--min <N>] [--max <N>] [--seed <N>]
+       guessing_game join <HOST:PORT> [--name <NAME>]
+       guessing_game replay <FILE>
+       guessing_game export [--format <csv|json>] [<FILE>]
+
+Options:
+  --difficulty <easy|normal|hard>  Preset range and attempt limit (default: normal)
+  --min <N>                        Lowest possible secret (overrides the preset, and the
+                                   attempt limit grows or shrinks to suit the range)
+  --max <N>                        Highest possible secret (the same)
+  --type <integer|float|letter|word>
+                                   What kind of secret to guess (default: integer)
+  --tolerance <X>                  How close a float guess must be to win (default: 0.5)
+  --seed <N>                       Seed the random number generator for a reproducible game
+  --hints                          Warn about guesses that can't win and compare with optimal play
+  --reveal                         Print the secret number before playing (debugging)
+  --autoplay <STRATEGY>            Let the computer play (binary, random, linear or golden)
+  --bench <N>                      Play N computer games per strategy and compare them
+  --reverse                        You think of a number and the computer guesses it
+  --time-limit <SECONDS>           Win before the time runs out; the prompt shows what's left
+  --tournament <N>                 Play a best-of-N series and show a summary at the end
+                                   (always line by line)
+  --plain                          Play line by line even in a terminal (the default when
+                                   output is not a terminal)
+  --scores-file <PATH>             Where high scores are kept (default: <data dir>/guessing_game/scores.json)
+  --log-file <PATH>                Where every game is logged (default: <data dir>/guessing_game/games.jsonl)
+  -h, --help                       Print this help
+
+The scores subcommand lists the high score table, optionally filtered
+by difficulty and range, or clears it with --reset.
+
+serve hosts a multiplayer round that other players join with join;
+the first player to guess the secret wins.
+
+replay plays every game in an event log again and checks that the
+answers come out the same; export turns a log (by default the one
+games are written to) into csv or json.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    // Inclusive range the secret is drawn from
+    //
+    pub fn range(self) -> (u32, u32) {
+        match self {
+            Difficulty::Easy => (1, 50),
+            Difficulty::Normal => (1, 100),
+            Difficulty::Hard => (1, 1000),
+        }
+    }
+
+    pub fn max_attempts(self) -> u32 {
+        match self {
+            Difficulty::Easy => 10,
+            Difficulty::Normal => 7,
+            Difficulty::Hard => 10,
+        }
+    }
+
+    // The attempt limit for a range other than the
+    // preset's: enough to always win with perfect play,
+    // plus the same slack the preset gives over its own
+    // range
+    //
+    pub fn attempts_for(self, min: u32, max: u32) -> u32 {
+        let (preset_min, preset_max) = self.range();
+        let slack = self.max_attempts() - perfect_play(preset_min, preset_max);
+        perfect_play(min, max) + slack
+    }
+}
+
+// The most guesses a binary search over min..=max ever
+// needs: ceil(log2(max - min + 2))
+//
+fn perfect_play(min: u32, max: u32) -> u32 {
+    let outcomes = u64::from(max - min) + 2;
+    64 - (outcomes - 1).leading_zeros()
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Difficulty, String> {
+        match s {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            other => Err(format!(
+                "unknown difficulty '{}' (expected easy, normal or hard)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub difficulty: Difficulty,
+    pub secret_type: SecretType,
+    pub tolerance: f64,
+    pub min: u32,
+    pub max: u32,
+    pub max_attempts: u32,
+    pub seed: Option<u64>,
+    pub hints: bool,
+    pub reveal: bool,
+    pub scores_file: Option<PathBuf>,
+    pub autoplay: Option<StrategyKind>,
+    pub bench: Option<u32>,
+    pub reverse: bool,
+    pub plain: bool,
+    pub log_file: Option<PathBuf>,
+    pub time_limit: Option<Duration>,
+    pub tournament: Option<u32>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config::from_difficulty(Difficulty::Normal)
+    }
+}
+
+impl Config {
+    pub fn from_difficulty(difficulty: Difficulty) -> Config {
+        let (min, max) = difficulty.range();
+        Config {
+            difficulty,
+            secret_type: SecretType::Integer,
+            tolerance: 0.5,
+            min,
+            max,
+            max_attempts: difficulty.max_attempts(),
+            seed: None,
+            hints: false,
+            reveal: false,
+            scores_file: None,
+            autoplay: None,
+            bench: None,
+            reverse: false,
+            plain: false,
+            log_file: None,
+            time_limit: None,
+            tournament: None,
+        }
+    }
+
+    // The first item is expected to be the program name,
+    // the same as std::env::args()
+    //
+    pub fn new<I>(mut args: I) -> Result<Config, String>
+    where
+        I: Iterator<Item = String>,
+    {
+        args.next();
+
+        let mut difficulty = Difficulty::Normal;
+        let mut secret_type = SecretType::Integer;
+        let mut tolerance = None;
+        let mut min = None;
+        let mut max = None;
+        let mut seed = None;
+        let mut hints = false;
+        let mut reveal = false;
+        let mut scores_file = None;
+        let mut autoplay = None;
+        let mut bench = None;
+        let mut reverse = false;
+        let mut plain = false;
+        let mut log_file = None;
+        let mut time_limit = None;
+        let mut tournament = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--difficulty" => difficulty = value_for(&arg, args.next())?.parse()?,
+                "--type" => secret_type = value_for(&arg, args.next())?.parse()?,
+                "--tolerance" => tolerance = Some(float_for(&arg, args.next())?),
+                "--min" => min = Some(number_for(&arg, args.next())?),
+                "--max" => max = Some(number_for(&arg, args.next())?),
+                "--seed" => seed = Some(number_for(&arg, args.next())?),
+                "--hints" => hints = true,
+                "--reveal" => reveal = true,
+                "--scores-file" => scores_file = Some(PathBuf::from(value_for(&arg, args.next())?)),
+                "--autoplay" => autoplay = Some(value_for(&arg, args.next())?.parse()?),
+                "--bench" => bench = Some(number_for(&arg, args.next())?),
+                "--reverse" => reverse = true,
+                "--plain" => plain = true,
+                "--log-file" => log_file = Some(PathBuf::from(value_for(&arg, args.next())?)),
+                "--time-limit" => time_limit = Some(Duration::from_secs(positive_for(&arg, args.next())?)),
+                "--tournament" => tournament = Some(positive_for(&arg, args.next())?),
+                other => return Err(format!("unexpected argument '{}'", other)),
+            }
+        }
+
+        let mut config = Config::from_difficulty(difficulty);
+        config.secret_type = secret_type;
+        config.tolerance = tolerance.unwrap_or(config.tolerance);
+        config.min = min.unwrap_or(config.min);
+        config.max = max.unwrap_or(config.max);
+        config.seed = seed;
+        config.hints = hints;
+        config.reveal = reveal;
+        config.scores_file = scores_file;
+        config.autoplay = autoplay;
+        config.bench = bench;
+        config.reverse = reverse;
+        config.plain = plain;
+        config.log_file = log_file;
+        config.time_limit = time_limit;
+        config.tournament = tournament;
+
+        if config.min > config.max {
+            return Err(format!(
+                "--min ({}) must not be greater than --max ({})",
+                config.min, config.max
+            ));
+        }
+
+        // A range of your own gets an attempt limit to
+        // match, so it can always be won
+        //
+        if min.is_some() || max.is_some() {
+            config.max_attempts = difficulty.attempts_for(config.min, config.max);
+        }
+
+        // The computer players and the network game only
+        // know about integers
+        //
+        if config.secret_type != SecretType::Integer && (config.autoplay.is_some() || config.bench.is_some() || config.reverse) {
+            return Err(String::from("--autoplay, --bench and --reverse only work with --type integer"));
+        }
+
+        // Those are the computer playing; timing it or
+        // running a tournament makes no sense
+        //
+        if (config.time_limit.is_some() || config.tournament.is_some())
+            && (config.autoplay.is_some() || config.bench.is_some() || config.reverse)
+        {
+            return Err(String::from(
+                "--time-limit and --tournament can't be combined with --autoplay, --bench or --reverse",
+            ));
+        }
+
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScoresOptions {
+    pub difficulty: Option<Difficulty>,
+    pub secret_type: Option<SecretType>,
+    pub min: Option<u32>,
+    pub max: Option<u32>,
+    pub reset: bool,
+    pub scores_file: Option<PathBuf>,
+}
+
+impl ScoresOptions {
+    // Unlike Config::new, the program name and the
+    // "scores" subcommand have already been consumed
+    //
+    pub fn new<I>(mut args: I) -> Result<ScoresOptions, String>
+    where
+        I: Iterator<Item = String>,
+    {
+        let mut options = ScoresOptions::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--difficulty" => options.difficulty = Some(value_for(&arg, args.next())?.parse()?),
+                "--type" => options.secret_type = Some(value_for(&arg, args.next())?.parse()?),
+                "--min" => options.min = Some(number_for(&arg, args.next())?),
+                "--max" => options.max = Some(number_for(&arg, args.next())?),
+                "--reset" => options.reset = true,
+                "--scores-file" => options.scores_file = Some(PathBuf::from(value_for(&arg, args.next())?)),
+                other => return Err(format!("unexpected argument '{}'", other)),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServeOptions {
+    pub port: u16,
+    pub config: Config,
+}
+
+impl ServeOptions {
+    // --port is ours, everything else describes the game
+    // and is handed on to Config::new
+    //
+    pub fn new<I>(args: I) -> Result<ServeOptions, String>
+    where
+        I: Iterator<Item = String>,
+    {
+        let mut port = None;
+        let mut game_args = vec![String::from("serve")];
+        let mut args = args;
+
+        while let Some(arg) = args.next() {
+            if arg == "--port" {
+                port = Some(number_for(&arg, args.next())?);
+            } else {
+                game_args.push(arg);
+            }
+        }
+
+        let config = Config::new(game_args.into_iter())?;
+        if config.secret_type != SecretType::Integer {
+            return Err(String::from("serve only works with --type integer"));
+        }
+
+        Ok(ServeOptions {
+            port: port.ok_or("serve needs --port")?,
+            config,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JoinOptions {
+    pub addr: String,
+    pub name: Option<String>,
+}
+
+impl JoinOptions {
+    pub fn new<I>(mut args: I) -> Result<JoinOptions, String>
+    where
+        I: Iterator<Item = String>,
+    {
+        let mut addr = None;
+        let mut name = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--name" => name = Some(value_for(&arg, args.next())?),
+                other if other.starts_with("--") => {
+                    return Err(format!("unexpected argument '{}'", other))
+                }
+                _ if addr.is_none() => addr = Some(arg),
+                other => return Err(format!("unexpected argument '{}'", other)),
+            }
+        }
+
+        Ok(JoinOptions {
+            addr: addr.ok_or("join needs a server address such as localhost:7878")?,
+            name,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayOptions {
+    pub file: PathBuf,
+}
+
+impl ReplayOptions {
+    pub fn new<I>(mut args: I) -> Result<ReplayOptions, String>
+    where
+        I: Iterator<Item = String>,
+    {
+        let file = match args.next() {
+            Some(arg) if !arg.starts_with("--") => PathBuf::from(arg),
+            Some(other) => return Err(format!("unexpected argument '{}'", other)),
+            None => return Err(String::from("replay needs the event log to replay")),
+        };
+        if let Some(other) = args.next() {
+            return Err(format!("unexpected argument '{}'", other));
+        }
+
+        Ok(ReplayOptions { file })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    // None means the log games are written to
+    //
+    pub file: Option<PathBuf>,
+}
+
+impl ExportOptions {
+    pub fn new<I>(mut args: I) -> Result<ExportOptions, String>
+    where
+        I: Iterator<Item = String>,
+    {
+        let mut format = ExportFormat::Csv;
+        let mut file = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--format" => format = value_for(&arg, args.next())?.parse()?,
+                other if other.starts_with("--") => {
+                    return Err(format!("unexpected argument '{}'", other))
+                }
+                _ if file.is_none() => file = Some(PathBuf::from(arg)),
+                other => return Err(format!("unexpected argument '{}'", other)),
+            }
+        }
+
+        Ok(ExportOptions { format, file })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Play(Config),
+    Scores(ScoresOptions),
+    Serve(ServeOptions),
+    Join(JoinOptions),
+    Replay(ReplayOptions),
+    Export(ExportOptions),
+}
+
+impl Command {
+    pub fn new<I>(mut args: I) -> Result<Command, String>
+    where
+        I: Iterator<Item = String>,
+    {
+        let program = args.next();
+        let mut args = args.peekable();
+
+        match args.peek().map(String::as_str) {
+            Some("scores") => {
+                args.next();
+                ScoresOptions::new(args).map(Command::Scores)
+            }
+            Some("serve") => {
+                args.next();
+                ServeOptions::new(args).map(Command::Serve)
+            }
+            Some("join") => {
+                args.next();
+                JoinOptions::new(args).map(Command::Join)
+            }
+            Some("replay") => {
+                args.next();
+                ReplayOptions::new(args).map(Command::Replay)
+            }
+            Some("export") => {
+                args.next();
+                ExportOptions::new(args).map(Command::Export)
+            }
+            _ => Config::new(program.into_iter().chain(args)).map(Command::Play),
+        }
+    }
+}
+
+fn value_for(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("{} needs a value", flag))
+}
+
+fn float_for(flag: &str, value: Option<String>) -> Result<f64, String> {
+    let value = value_for(flag, value)?;
+    match value.parse::<f64>() {
+        Ok(x) if x.is_finite() && x >= 0.0 => Ok(x),
+        _ => Err(format!("{} expects a positive number, got '{}'", flag, value)),
+    }
+}
+
+fn positive_for<T: FromStr + PartialEq + Default>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let number = number_for(flag, value)?;
+    if number == T::default() {
+        return Err(format!("{} must be at least 1", flag));
+    }
+    Ok(number)
+}
+
+fn number_for<T: FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value_for(flag, value)?;
+    value
+        .parse()
+        .map_err(|_| format!("{} expects a whole number, got '{}'", flag, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args<'a>(args: &'a [&str]) -> impl Iterator<Item = String> + 'a {
+        std::iter::once("guessing_game")
+            .chain(args.iter().copied())
+            .map(String::from)
+    }
+
+    fn parse(args: &[&str]) -> Result<Config, String> {
+        Config::new(self::args(args))
+    }
+
+    #[test]
+    fn defaults_to_normal_without_reveal() {
+        assert_eq!(parse(&[]), Ok(Config::default()));
+        assert!(!parse(&[]).unwrap().reveal);
+    }
+
+    #[test]
+    fn difficulty_sets_range_and_attempts() {
+        let config = parse(&["--difficulty", "hard"]).unwrap();
+        assert_eq!((config.min, config.max), (1, 1000));
+        assert_eq!(config.max_attempts, 10);
+    }
+
+    #[test]
+    fn bounds_override_the_preset() {
+        let config = parse(&["--difficulty", "easy", "--min", "5", "--max", "9"]).unwrap();
+        assert_eq!((config.min, config.max), (5, 9));
+
+        // Five numbers take at most three guesses, and easy
+        // gives four more than that
+        //
+        assert_eq!(config.max_attempts, 7);
+    }
+
+    #[test]
+    fn a_wider_range_gets_enough_attempts_to_win() {
+        let config = parse(&["--max", "1000000"]).unwrap();
+        assert_eq!(config.max_attempts, 20);
+        assert_eq!(parse(&["--min", "7", "--max", "7"]).unwrap().max_attempts, 1);
+
+        // The presets' own ranges keep their own limits
+        //
+        for &difficulty in &[Difficulty::Easy, Difficulty::Normal, Difficulty::Hard] {
+            let (min, max) = difficulty.range();
+            assert_eq!(difficulty.attempts_for(min, max), difficulty.max_attempts());
+        }
+        assert_eq!(Difficulty::Normal.attempts_for(0, u32::MAX), 33);
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(parse(&["--difficulty", "insane"]).is_err());
+        assert!(parse(&["--min"]).is_err());
+        assert!(parse(&["--max", "ten"]).is_err());
+        assert!(parse(&["--min", "10", "--max", "2"]).is_err());
+        assert!(parse(&["--bogus"]).is_err());
+    }
+
+    #[test]
+    fn seed_is_optional() {
+        assert_eq!(parse(&[]).unwrap().seed, None);
+        assert_eq!(parse(&["--seed", "42"]).unwrap().seed, Some(42));
+        assert!(parse(&["--seed", "-1"]).is_err());
+    }
+
+    #[test]
+    fn plain_mode_is_opt_in() {
+        assert!(!parse(&[]).unwrap().plain);
+        assert!(parse(&["--plain"]).unwrap().plain);
+    }
+
+    #[test]
+    fn time_limit_and_tournament() {
+        let config = parse(&["--time-limit", "30", "--tournament", "5"]).unwrap();
+        assert_eq!(config.time_limit, Some(Duration::from_secs(30)));
+        assert_eq!(config.tournament, Some(5));
+
+        assert_eq!(parse(&[]).unwrap().time_limit, None);
+        assert!(parse(&["--time-limit", "0"]).is_err());
+        assert!(parse(&["--tournament", "0"]).is_err());
+        assert!(parse(&["--tournament", "3", "--autoplay", "binary"]).is_err());
+        assert!(parse(&["--time-limit", "10", "--reverse"]).is_err());
+    }
+
+    #[test]
+    fn autoplay_and_bench() {
+        let config = parse(&["--autoplay", "golden", "--bench", "50"]).unwrap();
+        assert_eq!(config.autoplay, Some(StrategyKind::Golden));
+        assert_eq!(config.bench, Some(50));
+        assert!(parse(&["--autoplay", "psychic"]).is_err());
+    }
+
+    #[test]
+    fn secret_type_and_tolerance() {
+        assert_eq!(parse(&[]).unwrap().secret_type, SecretType::Integer);
+
+        let config = parse(&["--type", "float", "--tolerance", "0.1"]).unwrap();
+        assert_eq!(config.secret_type, SecretType::Float);
+        assert_eq!(config.tolerance, 0.1);
+
+        assert!(parse(&["--type", "colour"]).is_err());
+        assert!(parse(&["--tolerance", "-1"]).is_err());
+        assert!(parse(&["--type", "word", "--autoplay", "binary"]).is_err());
+        assert!(Command::new(args(&["serve", "--port", "1", "--type", "letter"])).is_err());
+    }
+
+    #[test]
+    fn scores_subcommand() {
+        assert_eq!(Command::new(args(&[])), Ok(Command::Play(Config::default())));
+
+        let command = Command::new(args(&["scores", "--difficulty", "easy", "--max", "50", "--reset"]));
+        assert_eq!(
+            command,
+            Ok(Command::Scores(ScoresOptions {
+                difficulty: Some(Difficulty::Easy),
+                max: Some(50),
+                reset: true,
+                ..ScoresOptions::default()
+            }))
+        );
+
+        assert!(Command::new(args(&["scores", "--reveal"])).is_err());
+    }
+
+    #[test]
+    fn serve_and_join_subcommands() {
+        match Command::new(args(&["serve", "--difficulty", "easy", "--port", "7878"])) {
+            Ok(Command::Serve(options)) => {
+                assert_eq!(options.port, 7878);
+                assert_eq!(options.config, Config::from_difficulty(Difficulty::Easy));
+            }
+            other => panic!("expected serve, got {:?}", other),
+        }
+        assert!(Command::new(args(&["serve"])).is_err());
+        assert!(Command::new(args(&["serve", "--port", "99999"])).is_err());
+
+        assert_eq!(
+            Command::new(args(&["join", "localhost:7878", "--name", "ada"])),
+            Ok(Command::Join(JoinOptions {
+                addr: String::from("localhost:7878"),
+                name: Some(String::from("ada")),
+            }))
+        );
+        assert!(Command::new(args(&["join"])).is_err());
+        assert!(Command::new(args(&["join", "a:1", "b:2"])).is_err());
+    }
+
+    #[test]
+    fn replay_and_export_subcommands() {
+        assert_eq!(
+            Command::new(args(&["replay", "games.jsonl"])),
+            Ok(Command::Replay(ReplayOptions {
+                file: PathBuf::from("games.jsonl"),
+            }))
+        );
+        assert!(Command::new(args(&["replay"])).is_err());
+        assert!(Command::new(args(&["replay", "a", "b"])).is_err());
+
+        assert_eq!(
+            Command::new(args(&["export", "--format", "json", "old.jsonl"])),
+            Ok(Command::Export(ExportOptions {
+                format: ExportFormat::Json,
+                file: Some(PathBuf::from("old.jsonl")),
+            }))
+        );
+        assert_eq!(
+            Command::new(args(&["export"])),
+            Ok(Command::Export(ExportOptions {
+                format: ExportFormat::Csv,
+                file: None,
+            }))
+        );
+        assert!(Command::new(args(&["export", "--format", "xml"])).is_err());
+
+        let config = parse(&["--log-file", "games.jsonl"]).unwrap();
+        assert_eq!(config.log_file, Some(PathBuf::from("games.jsonl")));
+    }
+}
@@ -0,0 +1,324 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - Every game played is appended to an event log, by
+//   default <data dir>/guessing_game/games.jsonl, or the
+//   file given with --log-file.
+// - The log is JSON lines: one event per line, written
+//   and flushed as it happens, and never rewritten. A
+//   game is a Start event followed by its guesses (and
+//   restarts) up to an End event. A game that was cut
+//   off just has no End.
+// - The Start event holds the seed, so src/replay.rs
+//   can play the same game again and check that the
+//   engine still gives the same answers.
+//
+
+use std::cmp::Ordering;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, Difficulty};
+use crate::game::Outcome;
+use crate::secret::SecretType;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum Event {
+    Start {
+        time_ms: u64,
+        seed: u64,
+        difficulty: Difficulty,
+        secret_type: SecretType,
+        min: u32,
+        max: u32,
+        max_attempts: u32,
+        tolerance: f64,
+        // Seconds, for games with a countdown
+        //
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        time_limit: Option<u64>,
+    },
+    Guess {
+        time_ms: u64,
+        guess: String,
+        result: Answer,
+    },
+    Restart {
+        time_ms: u64,
+    },
+    End {
+        time_ms: u64,
+        outcome: Ending,
+        attempts: u32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Answer {
+    TooSmall,
+    TooBig,
+    Correct,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ending {
+    Won,
+    Lost,
+    Quit,
+}
+
+impl Answer {
+    // The answer to a guess that compared to the secret
+    // as `ordering`
+    //
+    pub fn from_ordering(ordering: Ordering) -> Answer {
+        match ordering {
+            Ordering::Less => Answer::TooSmall,
+            Ordering::Greater => Answer::TooBig,
+            Ordering::Equal => Answer::Correct,
+        }
+    }
+}
+
+impl Ending {
+    pub fn of(outcome: &Outcome) -> Ending {
+        match outcome {
+            Outcome::Won { .. } => Ending::Won,
+            Outcome::Lost { .. } => Ending::Lost,
+            Outcome::Quit { .. } => Ending::Quit,
+        }
+    }
+}
+
+impl Event {
+    pub fn start(config: &Config, seed: u64, time_ms: u64) -> Event {
+        Event::Start {
+            time_ms,
+            seed,
+            difficulty: config.difficulty,
+            secret_type: config.secret_type,
+            min: config.min,
+            max: config.max,
+            max_attempts: config.max_attempts,
+            tolerance: config.tolerance,
+            time_limit: config.time_limit.map(|limit| limit.as_secs()),
+        }
+    }
+
+    pub fn time_ms(&self) -> u64 {
+        match self {
+            Event::Start { time_ms, .. }
+            | Event::Guess { time_ms, .. }
+            | Event::Restart { time_ms }
+            | Event::End { time_ms, .. } => *time_ms,
+        }
+    }
+
+    // The same event at time 0, for comparing what
+    // happened without caring when
+    //
+    pub fn without_time(&self) -> Event {
+        let mut event = self.clone();
+        match &mut event {
+            Event::Start { time_ms, .. }
+            | Event::Guess { time_ms, .. }
+            | Event::Restart { time_ms }
+            | Event::End { time_ms, .. } => *time_ms = 0,
+        }
+        event
+    }
+}
+
+// Where a game writes its events. A disabled log
+// quietly drops them, so playing never depends on it.
+//
+pub struct EventLog<'a> {
+    writer: Option<&'a mut dyn Write>,
+}
+
+impl<'a> EventLog<'a> {
+    pub fn new(writer: &'a mut dyn Write) -> EventLog<'a> {
+        EventLog { writer: Some(writer) }
+    }
+
+    pub fn disabled() -> EventLog<'a> {
+        EventLog { writer: None }
+    }
+
+    // Writes to the same place as this log, for the next
+    // game of a series
+    //
+    pub fn reborrow(&mut self) -> EventLog<'_> {
+        EventLog {
+            writer: self.writer.as_mut().map(|writer| &mut **writer as &mut dyn Write),
+        }
+    }
+
+    pub fn record(&mut self, event: &Event) -> io::Result<()> {
+        if let Some(writer) = &mut self.writer {
+            serde_json::to_writer(&mut *writer, event)?;
+            writeln!(writer)?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+// Opens the log for appending, creating it (and its
+// directory) the first time
+//
+pub fn open(path: &Path) -> io::Result<File> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+// Blank lines are skipped; a line that isn't an event
+// is an ErrorKind::InvalidData error naming the line
+//
+pub fn read<R: BufRead>(input: R) -> io::Result<Vec<Event>> {
+    let mut events = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event = serde_json::from_str(&line)
+            .map_err(|e| io::Error::new(ErrorKind::InvalidData, format!("line {}: {}", i + 1, e)))?;
+        events.push(event);
+    }
+
+    Ok(events)
+}
+
+pub fn read_file(path: &Path) -> io::Result<Vec<Event>> {
+    let file = File::open(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+    read(BufReader::new(file))
+}
+
+// Splits a log into games, each starting with its Start
+// event. Anything before the first Start is ignored.
+//
+pub fn games(events: &[Event]) -> Vec<&[Event]> {
+    let starts: Vec<usize> = events
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| matches!(e, Event::Start { .. }))
+        .map(|(i, _)| i)
+        .collect();
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(n, &start)| {
+            let end = starts.get(n + 1).copied().unwrap_or(events.len());
+            &events[start..end]
+        })
+        .collect()
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("guessing_game").join("games.jsonl"))
+}
+
+// An explicit --log-file always wins over the data dir
+//
+pub fn resolve_path(log_file: Option<&PathBuf>) -> io::Result<PathBuf> {
+    match log_file {
+        Some(path) => Ok(path.clone()),
+        None => default_path().ok_or_else(|| {
+            io::Error::new(ErrorKind::NotFound, "no data directory for this user; pass --log-file")
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn guess(time_ms: u64, guess: &str, result: Answer) -> Event {
+        Event::Guess {
+            time_ms,
+            guess: String::from(guess),
+            result,
+        }
+    }
+
+    #[test]
+    fn events_are_one_json_object_per_line() {
+        let mut written = Vec::new();
+        let mut log = EventLog::new(&mut written);
+        log.record(&guess(5, "42", Answer::TooSmall)).unwrap();
+        log.record(&Event::Restart { time_ms: 6 }).unwrap();
+
+        let written = String::from_utf8(written).unwrap();
+        assert_eq!(
+            written,
+            "{\"event\":\"guess\",\"time_ms\":5,\"guess\":\"42\",\"result\":\"too_small\"}\n\
+             {\"event\":\"restart\",\"time_ms\":6}\n"
+        );
+    }
+
+    #[test]
+    fn read_gives_back_what_was_recorded() {
+        let start = Event::start(&Config::default(), 99, 7);
+        let end = Event::End {
+            time_ms: 9,
+            outcome: Ending::Won,
+            attempts: 1,
+        };
+
+        let mut written = Vec::new();
+        let mut log = EventLog::new(&mut written);
+        for event in &[start.clone(), guess(8, "50", Answer::Correct), end.clone()] {
+            log.record(event).unwrap();
+        }
+        written.extend_from_slice(b"\n");
+
+        let events = read(Cursor::new(written)).unwrap();
+        assert_eq!(events, vec![start, guess(8, "50", Answer::Correct), end]);
+    }
+
+    #[test]
+    fn bad_lines_are_reported_by_number() {
+        let log = "{\"event\":\"restart\",\"time_ms\":1}\nnot json\n";
+        let err = read(Cursor::new(log)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("line 2:"));
+    }
+
+    #[test]
+    fn split_into_games() {
+        let start = Event::start(&Config::default(), 1, 0);
+        let events = vec![
+            Event::Restart { time_ms: 0 },
+            start.clone(),
+            guess(1, "50", Answer::TooBig),
+            start.clone(),
+            guess(2, "10", Answer::Correct),
+        ];
+
+        let games = games(&events);
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0], &events[1..3]);
+        assert_eq!(games[1], &events[3..]);
+        assert!(super::games(&[]).is_empty());
+    }
+
+    #[test]
+    fn without_time_only_clears_the_time() {
+        let event = guess(123, "7", Answer::TooSmall);
+        assert_eq!(event.without_time(), guess(0, "7", Answer::TooSmall));
+        assert_eq!(event.time_ms(), 123);
+    }
+}
@@ -0,0 +1,553 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - play() is generic over BufRead/Write so the same
+//   loop can be driven by io::stdin()/io::stdout() in
+//   main, or by an in-memory script in the tests.
+// - A seeded StdRng makes a game reproducible: the
+//   same seed and range always give the same secret.
+// - Session holds the rules of a game in progress; play()
+//   is the plain line mode on top of it and src/tui.rs is
+//   the full screen one.
+//
+
+use std::cmp::Ordering;
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::clock::{Clock, SystemClock};
+use crate::config::Config;
+use crate::events::{Answer, Ending, Event, EventLog};
+use crate::input::{self, Input};
+use crate::secret::{Float, FloatRange, Secret, SecretType, Word, WordList};
+use crate::state::{GameState, Warning};
+
+// Secrets are reported as text so that every kind of
+// game has the same outcome type
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Won { attempts: u32, elapsed: Duration },
+    Lost { secret: String },
+    // The player typed quit or the input ended
+    //
+    Quit { secret: String },
+}
+
+pub fn new_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+// The integer secret for config, the same one an
+// integer game with this rng would start with
+//
+pub fn pick_secret<R: Rng>(rng: &mut R, config: &Config) -> u32 {
+    u32::random(rng, &(config.min..=config.max))
+}
+
+// Plays a full game of config.secret_type with secrets
+// drawn from config.seed if there is one
+//
+pub fn run<R, W>(config: &Config, input: &mut R, output: &mut W) -> io::Result<Outcome>
+where
+    R: BufRead,
+    W: Write,
+{
+    run_logged(config, &SystemClock, EventLog::disabled(), input, output)
+}
+
+// Same as run, timed by `clock` and with every event
+// written to `log`
+//
+pub fn run_logged<R, W>(
+    config: &Config,
+    clock: &dyn Clock,
+    log: EventLog,
+    input: &mut R,
+    output: &mut W,
+) -> io::Result<Outcome>
+where
+    R: BufRead,
+    W: Write,
+{
+    let seed = pick_seed(config);
+
+    match config.secret_type {
+        SecretType::Integer => {
+            let domain = config.min..=config.max;
+            play(Session::<u32>::new(config, &domain, seed).with_clock(clock).with_log(log), input, output)
+        }
+        SecretType::Float => {
+            let domain = float_range(config);
+            play(Session::<Float>::new(config, &domain, seed).with_clock(clock).with_log(log), input, output)
+        }
+        SecretType::Letter => {
+            let domain = 'a'..='z';
+            play(Session::<char>::new(config, &domain, seed).with_clock(clock).with_log(log), input, output)
+        }
+        SecretType::Word => {
+            let domain = WordList::bundled();
+            play(Session::<Word>::new(config, &domain, seed).with_clock(clock).with_log(log), input, output)
+        }
+    }
+}
+
+// Unseeded games still get a seed, so that every game
+// in the event log can be replayed
+//
+pub fn pick_seed(config: &Config) -> u64 {
+    config.seed.unwrap_or_else(rand::random)
+}
+
+pub fn float_range(config: &Config) -> FloatRange {
+    FloatRange {
+        min: config.min as f64,
+        max: config.max as f64,
+        tolerance: config.tolerance,
+    }
+}
+
+// The plain line by line game: a prompt, a line of
+// input, and whatever the Session has to say about it
+//
+pub fn play<S, R, W>(mut session: Session<'_, '_, S>, input: &mut R, output: &mut W) -> io::Result<Outcome>
+where
+    S: Secret,
+    R: BufRead,
+    W: Write,
+{
+    let config = session.config();
+    let domain = session.domain();
+    writeln!(output, "{}", S::describe(domain))?;
+    writeln!(
+        output,
+        "Difficulty: {} ({} attempts)",
+        config.difficulty, config.max_attempts
+    )?;
+
+    session.start(output)?;
+
+    loop {
+        match session.time_left() {
+            Some(left) => writeln!(output, "Please input your guess ({}s left).", left.as_secs())?,
+            None => writeln!(output, "Please input your guess.")?,
+        }
+        output.flush()?;
+
+        let mut line = String::new();
+
+        // read_line returns Ok(0) at end of input, which
+        // ends the game the same way "quit" does
+        //
+        let command = if input.read_line(&mut line)? == 0 {
+            Input::Quit
+        } else {
+            match input::parse::<S>(&line, domain) {
+                Ok(command) => command,
+                Err(e) => {
+                    writeln!(output, "{}", e)?;
+                    continue;
+                }
+            }
+        };
+
+        if let Some(outcome) = session.handle(command, output)? {
+            return Ok(outcome);
+        }
+    }
+}
+
+// A game in progress. Every frontend turns what the
+// player typed into an Input and hands it to handle(),
+// which answers on `output` and returns the Outcome
+// once the game is over.
+//
+// Built with new(), optionally with_clock() and
+// with_log(), and then start()ed.
+//
+pub struct Session<'a, 'l, S: Secret> {
+    config: &'a Config,
+    domain: &'a S::Domain,
+    seed: u64,
+    rng: StdRng,
+    clock: &'a dyn Clock,
+    log: EventLog<'l>,
+    state: GameState<S>,
+    started: Duration,
+}
+
+impl<'a, 'l, S: Secret> Session<'a, 'l, S> {
+    // The first secret is the first one drawn from the
+    // seed; each restart draws another one
+    //
+    pub fn new(config: &'a Config, domain: &'a S::Domain, seed: u64) -> Session<'a, 'l, S> {
+        let mut rng = new_rng(Some(seed));
+        let secret = S::random(&mut rng, domain);
+        Session {
+            config,
+            domain,
+            seed,
+            rng,
+            clock: &SystemClock,
+            log: EventLog::disabled(),
+            state: GameState::new(domain.clone(), secret),
+            started: Duration::ZERO,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: &'a dyn Clock) -> Session<'a, 'l, S> {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_log(mut self, log: EventLog<'l>) -> Session<'a, 'l, S> {
+        self.log = log;
+        self
+    }
+
+    // Starts the timer and logs the start of the game
+    //
+    pub fn start<W: Write>(&mut self, output: &mut W) -> io::Result<()> {
+        self.started = self.clock.now();
+        self.log.record(&Event::start(self.config, self.seed, as_ms(self.started)))?;
+        self.reveal(output)
+    }
+
+    pub fn config(&self) -> &'a Config {
+        self.config
+    }
+
+    pub fn domain(&self) -> &'a S::Domain {
+        self.domain
+    }
+
+    pub fn state(&self) -> &GameState<S> {
+        &self.state
+    }
+
+    pub fn attempts_left(&self) -> u32 {
+        self.config.max_attempts - self.state.attempts()
+    }
+
+    // How long the current secret has been played for,
+    // in whole milliseconds so that a replay from the
+    // log times out at exactly the same point
+    //
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_millis(as_ms(self.clock.now()).saturating_sub(as_ms(self.started)))
+    }
+
+    // None unless the game has a time limit
+    //
+    pub fn time_left(&self) -> Option<Duration> {
+        self.config
+            .time_limit
+            .map(|limit| limit.saturating_sub(self.elapsed()))
+    }
+
+    // Ends the game if its time is up. handle() checks
+    // this first; frontends that don't block on input can
+    // call it while they wait.
+    //
+    pub fn check_time<W: Write>(&mut self, output: &mut W) -> io::Result<Option<Outcome>> {
+        if self.time_left() != Some(Duration::ZERO) {
+            return Ok(None);
+        }
+
+        writeln!(output, "Time's up! The secret {} was {}.", S::NOUN, self.state.secret())?;
+        let outcome = Outcome::Lost {
+            secret: self.state.secret().to_string(),
+        };
+        self.end(&outcome)?;
+        Ok(Some(outcome))
+    }
+
+    pub fn handle<W: Write>(&mut self, command: Input<S>, output: &mut W) -> io::Result<Option<Outcome>> {
+        if let Some(outcome) = self.check_time(output)? {
+            return Ok(Some(outcome));
+        }
+
+        let outcome = self.turn(command, output)?;
+        if let Some(outcome) = &outcome {
+            self.end(outcome)?;
+        }
+        Ok(outcome)
+    }
+
+    fn end(&mut self, outcome: &Outcome) -> io::Result<()> {
+        self.log.record(&Event::End {
+            time_ms: as_ms(self.clock.now()),
+            outcome: Ending::of(outcome),
+            attempts: self.state.attempts(),
+        })
+    }
+
+    fn reveal<W: Write>(&self, output: &mut W) -> io::Result<()> {
+        if self.config.reveal {
+            writeln!(output, "The secret {} is: {}", S::NOUN, self.state.secret())?;
+        }
+        Ok(())
+    }
+
+    fn turn<W: Write>(&mut self, command: Input<S>, output: &mut W) -> io::Result<Option<Outcome>> {
+        let config = self.config;
+
+        let guess = match command {
+            Input::Guess(guess) => guess,
+            Input::Quit => {
+                writeln!(output, "Goodbye! The secret {} was {}.", S::NOUN, self.state.secret())?;
+                return Ok(Some(Outcome::Quit {
+                    secret: self.state.secret().to_string(),
+                }));
+            }
+            Input::Hint => {
+                let (low, high) = self.state.feasible();
+                writeln!(output, "Hint: the secret is between {} and {}.", low, high)?;
+                return Ok(None);
+            }
+            Input::History => {
+                print_history(&self.state, output)?;
+                return Ok(None);
+            }
+            Input::Restart => {
+                writeln!(output, "Starting over with a new secret {}.", S::NOUN)?;
+                let secret = S::random(&mut self.rng, self.domain);
+                self.state = GameState::new(self.domain.clone(), secret);
+                self.started = self.clock.now();
+                self.log.record(&Event::Restart {
+                    time_ms: as_ms(self.started),
+                })?;
+                self.reveal(output)?;
+                return Ok(None);
+            }
+        };
+
+        let state = &mut self.state;
+
+        if config.hints {
+            match state.check(&guess) {
+                Some(Warning::Above(n)) => writeln!(output, "Hint: you already know it's above {}.", n)?,
+                Some(Warning::Below(n)) => writeln!(output, "Hint: you already know it's below {}.", n)?,
+                None => {}
+            }
+        }
+
+        writeln!(output, "You guessed: {}", guess)?;
+
+        let exact = guess.exact();
+        let ordering = state.guess(guess);
+        self.log.record(&Event::Guess {
+            time_ms: as_ms(self.clock.now()),
+            guess: exact,
+            result: Answer::from_ordering(ordering),
+        })?;
+
+        match ordering {
+            Ordering::Less => writeln!(output, "{}", S::TOO_SMALL)?,
+            Ordering::Greater => writeln!(output, "{}", S::TOO_BIG)?,
+            Ordering::Equal => {
+                writeln!(output, "You win!")?;
+                if config.hints {
+                    print_analysis(state, output)?;
+                }
+                return Ok(Some(Outcome::Won {
+                    attempts: state.attempts(),
+                    elapsed: self.elapsed(),
+                }));
+            }
+        };
+
+        if state.attempts() == config.max_attempts {
+            writeln!(output, "Out of attempts! The secret {} was {}.", S::NOUN, state.secret())?;
+            if config.hints {
+                print_analysis(state, output)?;
+            }
+            return Ok(Some(Outcome::Lost {
+                secret: state.secret().to_string(),
+            }));
+        }
+
+        if config.hints {
+            let (low, high) = state.feasible();
+            writeln!(output, "Hint: the secret is between {} and {}.", low, high)?;
+        }
+
+        writeln!(output, "{} attempts left.", self.attempts_left())?;
+        Ok(None)
+    }
+}
+
+fn as_ms(time: Duration) -> u64 {
+    time.as_millis() as u64
+}
+
+fn print_history<S: Secret, W: Write>(state: &GameState<S>, output: &mut W) -> io::Result<()> {
+    if state.history().is_empty() {
+        return writeln!(output, "No guesses yet.");
+    }
+
+    let describe = |answer: &str| answer.trim_end_matches('!').to_lowercase();
+    let guesses: Vec<String> = state
+        .history()
+        .iter()
+        .map(|(guess, ordering)| match ordering {
+            Ordering::Less => format!("{} ({})", guess, describe(S::TOO_SMALL)),
+            Ordering::Greater => format!("{} ({})", guess, describe(S::TOO_BIG)),
+            Ordering::Equal => format!("{} (correct)", guess),
+        })
+        .collect();
+    writeln!(output, "Your guesses: {}", guesses.join(", "))
+}
+
+fn print_analysis<S: Secret, W: Write>(state: &GameState<S>, output: &mut W) -> io::Result<()> {
+    writeln!(
+        output,
+        "A binary search would have needed {} guesses; you took {}.",
+        state.optimal_guesses(),
+        state.attempts()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_secret() {
+        let config = Config::default();
+        let a = pick_secret(&mut new_rng(Some(1234)), &config);
+        let b = pick_secret(&mut new_rng(Some(1234)), &config);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn secret_stays_in_range() {
+        let config = Config {
+            min: 10,
+            max: 12,
+            ..Config::default()
+        };
+        let mut rng = new_rng(Some(0));
+        for _ in 0..100 {
+            let secret = pick_secret(&mut rng, &config);
+            assert!((10..=12).contains(&secret));
+        }
+    }
+
+    mod countdown {
+        use super::*;
+        use crate::clock::ManualClock;
+
+        fn timed_config() -> Config {
+            Config {
+                time_limit: Some(Duration::from_secs(10)),
+                ..Config::default()
+            }
+        }
+
+        fn wrong_guess(session: &Session<u32>) -> u32 {
+            let secret = *session.state().secret();
+            if secret == 1 {
+                2
+            } else {
+                secret - 1
+            }
+        }
+
+        #[test]
+        fn time_left_follows_the_clock() {
+            let config = timed_config();
+            let domain = 1..=100;
+            let clock = ManualClock::new(Duration::from_secs(1000));
+            let mut session = Session::<u32>::new(&config, &domain, 3).with_clock(&clock);
+            session.start(&mut io::sink()).unwrap();
+
+            assert_eq!(session.time_left(), Some(Duration::from_secs(10)));
+            clock.advance(Duration::from_millis(2500));
+            assert_eq!(session.time_left(), Some(Duration::from_millis(7500)));
+            clock.advance(Duration::from_secs(60));
+            assert_eq!(session.time_left(), Some(Duration::ZERO));
+
+            let untimed = Config::default();
+            assert_eq!(Session::<u32>::new(&untimed, &domain, 3).time_left(), None);
+        }
+
+        #[test]
+        fn a_late_guess_loses_even_if_right() {
+            let config = timed_config();
+            let domain = 1..=100;
+            let clock = ManualClock::default();
+            let mut session = Session::<u32>::new(&config, &domain, 3).with_clock(&clock);
+            session.start(&mut io::sink()).unwrap();
+            let secret = *session.state().secret();
+
+            clock.advance(Duration::from_secs(4));
+            let wrong = wrong_guess(&session);
+            assert_eq!(session.handle(Input::Guess(wrong), &mut io::sink()).unwrap(), None);
+
+            clock.advance(Duration::from_secs(6));
+            let mut output = Vec::new();
+            let outcome = session.handle(Input::Guess(secret), &mut output).unwrap();
+
+            assert_eq!(outcome, Some(Outcome::Lost { secret: secret.to_string() }));
+            assert_eq!(
+                String::from_utf8(output).unwrap(),
+                format!("Time's up! The secret number was {}.\n", secret)
+            );
+        }
+
+        #[test]
+        fn check_time_only_ends_an_expired_game() {
+            let config = timed_config();
+            let domain = 1..=100;
+            let clock = ManualClock::default();
+            let mut session = Session::<u32>::new(&config, &domain, 3).with_clock(&clock);
+            session.start(&mut io::sink()).unwrap();
+
+            clock.advance(Duration::from_millis(9999));
+            assert_eq!(session.check_time(&mut io::sink()).unwrap(), None);
+            clock.advance(Duration::from_millis(1));
+            assert!(matches!(session.check_time(&mut io::sink()).unwrap(), Some(Outcome::Lost { .. })));
+        }
+
+        #[test]
+        fn wins_are_timed_by_the_clock() {
+            let config = Config::default();
+            let domain = 1..=100;
+            let clock = ManualClock::default();
+            let mut session = Session::<u32>::new(&config, &domain, 3).with_clock(&clock);
+            session.start(&mut io::sink()).unwrap();
+            let secret = *session.state().secret();
+
+            clock.advance(Duration::from_secs(42));
+            let outcome = session.handle(Input::Guess(secret), &mut io::sink()).unwrap();
+            assert_eq!(
+                outcome,
+                Some(Outcome::Won {
+                    attempts: 1,
+                    elapsed: Duration::from_secs(42),
+                })
+            );
+        }
+
+        #[test]
+        fn restart_restarts_the_countdown() {
+            let config = timed_config();
+            let domain = 1..=100;
+            let clock = ManualClock::default();
+            let mut session = Session::<u32>::new(&config, &domain, 3).with_clock(&clock);
+            session.start(&mut io::sink()).unwrap();
+
+            clock.advance(Duration::from_secs(8));
+            session.handle(Input::Restart, &mut io::sink()).unwrap();
+            clock.advance(Duration::from_secs(8));
+            assert_eq!(session.time_left(), Some(Duration::from_secs(2)));
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - Every line the player types becomes either an
+//   Input or a ParseError. Each ParseError knows how to
+//   explain itself (Display), so the game loop just
+//   prints it and asks again.
+// - Commands are matched case-insensitively; anything
+//   else is parsed as a guess by the Secret type.
+//
+
+use std::fmt;
+
+use crate::secret::Secret;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input<S = u32> {
+    Guess(S),
+    Quit,
+    Hint,
+    History,
+    Restart,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    NotANumber(String),
+    Negative(String),
+    Overflow(String),
+    NotALetter(String),
+    UnknownWord(String),
+    // Kept as text so every secret type can use it
+    //
+    OutOfRange { guess: String, min: String, max: String },
+}
+
+impl ParseError {
+    pub fn out_of_range<T: fmt::Display>(guess: &T, min: &T, max: &T) -> ParseError {
+        ParseError::OutOfRange {
+            guess: guess.to_string(),
+            min: min.to_string(),
+            max: max.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(
+                f,
+                "Type a guess, or one of: quit, hint, history, restart."
+            ),
+            ParseError::NotANumber(s) => write!(f, "'{}' isn't a number. Try digits, like 42.", s),
+            ParseError::Negative(s) => write!(f, "{} is negative; the secret never is.", s),
+            ParseError::Overflow(s) => write!(f, "{} is far too big to be the secret.", s),
+            ParseError::NotALetter(s) => write!(f, "'{}' isn't a single letter.", s),
+            ParseError::UnknownWord(s) => write!(f, "'{}' isn't in the word list.", s),
+            ParseError::OutOfRange { guess, min, max } => write!(
+                f,
+                "{} is out of range; the secret is between {} and {}.",
+                guess, min, max
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub fn parse<S: Secret>(line: &str, domain: &S::Domain) -> Result<Input<S>, ParseError> {
+    let line = line.trim();
+
+    match line.to_lowercase().as_str() {
+        "" => return Err(ParseError::Empty),
+        "quit" | "exit" => return Ok(Input::Quit),
+        "hint" => return Ok(Input::Hint),
+        "history" => return Ok(Input::History),
+        "restart" => return Ok(Input::Restart),
+        _ => {}
+    }
+
+    S::parse(line, domain).map(Input::Guess)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_1_100(line: &str) -> Result<Input, ParseError> {
+        parse(line, &(1..=100))
+    }
+
+    fn out_of_range(guess: u32) -> ParseError {
+        ParseError::out_of_range(&guess, &1, &100)
+    }
+
+    #[test]
+    fn guesses() {
+        assert_eq!(parse_1_100("42\n"), Ok(Input::Guess(42)));
+        assert_eq!(parse_1_100("  7 "), Ok(Input::Guess(7)));
+        assert_eq!(parse_1_100("+9"), Ok(Input::Guess(9)));
+        assert_eq!(parse_1_100("100"), Ok(Input::Guess(100)));
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(parse_1_100("quit"), Ok(Input::Quit));
+        assert_eq!(parse_1_100("EXIT"), Ok(Input::Quit));
+        assert_eq!(parse_1_100("Hint"), Ok(Input::Hint));
+        assert_eq!(parse_1_100("history\r\n"), Ok(Input::History));
+        assert_eq!(parse_1_100("restart"), Ok(Input::Restart));
+        assert_eq!(parse::<char>("hint", &('a'..='z')), Ok(Input::Hint));
+    }
+
+    #[test]
+    fn each_kind_of_error() {
+        assert_eq!(parse_1_100(""), Err(ParseError::Empty));
+        assert_eq!(parse_1_100("   \n"), Err(ParseError::Empty));
+        assert_eq!(parse_1_100("fish"), Err(ParseError::NotANumber(String::from("fish"))));
+        assert_eq!(parse_1_100("4.5"), Err(ParseError::NotANumber(String::from("4.5"))));
+        assert_eq!(parse_1_100("-"), Err(ParseError::NotANumber(String::from("-"))));
+        assert_eq!(parse_1_100("-5"), Err(ParseError::Negative(String::from("-5"))));
+        assert_eq!(
+            parse_1_100("99999999999"),
+            Err(ParseError::Overflow(String::from("99999999999")))
+        );
+        assert_eq!(parse_1_100("0"), Err(out_of_range(0)));
+        assert_eq!(parse_1_100("101"), Err(out_of_range(101)));
+    }
+
+    #[test]
+    fn messages_are_specific() {
+        let messages: Vec<String> = ["", "fish", "-5", "99999999999", "500"]
+            .iter()
+            .map(|line| parse_1_100(line).unwrap_err().to_string())
+            .collect();
+
+        assert!(messages[0].contains("quit, hint, history, restart"));
+        assert!(messages[1].contains("isn't a number"));
+        assert!(messages[2].contains("negative"));
+        assert!(messages[3].contains("too big"));
+        assert!(messages[4].contains("between 1 and 100"));
+    }
+}
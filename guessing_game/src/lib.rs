@@ -0,0 +1,25 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - The game lives in a library crate so that it can
+//   be driven by integration tests in tests/, while
+//   src/main.rs stays a thin command line frontend.
+//
+
+pub mod clock;
+pub mod config;
+pub mod events;
+pub mod game;
+pub mod input;
+pub mod net;
+pub mod replay;
+pub mod reverse;
+pub mod scores;
+pub mod secret;
+pub mod solver;
+pub mod state;
+pub mod tournament;
+pub mod tui;
+
+pub use crate::config::{Command, Config, Difficulty};
+pub use crate::game::Outcome;
@@ -1,43 +1,146 @@
-use std::io;
-use std::cmp::Ordering;
-use rand::Rng;
+use std::io::{self, BufReader};
+use std::env;
+use std::fs::File;
+use std::process;
 
-fn print_error() {
-    println!("Don't be stupid");
-}
+use guessing_game::config::{self, Command, Config};
+use guessing_game::clock::SystemClock;
+use guessing_game::events::{self, EventLog};
+use guessing_game::game::{self, Outcome};
+use guessing_game::net;
+use guessing_game::replay;
+use guessing_game::reverse;
+use guessing_game::scores::{self, Score};
+use guessing_game::solver;
+use guessing_game::tournament::{self, Standing};
+use guessing_game::tui;
+
+// Exit status used when the player runs out of attempts,
+// so scripts can tell a loss apart from a usage error (1)
+//
+const EXIT_LOST: i32 = 2;
 
 fn main() {
-    println!("Guess the number!");
-
-    let secret_number = rand::thread_rng().gen_range(1..101);
-
-    println!("The secret number is: {}", secret_number);
-    
-    loop {
-        println!("Please input your guess.");
-        let mut guess = String::new();
-    
-        io::stdin()
-            .read_line(&mut guess)
-            .expect("Failed to read line");
- 
-        let guess: u32 = match guess.trim().parse() {
-            Ok(num) => num,
-            Err(_) => {
-                print_error();
-                continue;
+    if env::args().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", config::USAGE);
+        return;
+    }
+
+    let command = Command::new(env::args()).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {}", err);
+        eprintln!("{}", config::USAGE);
+        process::exit(1);
+    });
+
+    match command {
+        Command::Play(config) => {
+            if let Some(games) = config.bench {
+                let results = solver::bench(&config, games);
+                exit_on_error(solver::print_bench(&config, &results, &mut io::stdout()));
+            } else if let Some(strategy) = config.autoplay {
+                exit_on_error(solver::autoplay(&config, strategy, &mut io::stdout()).map(|_| ()));
+            } else if config.reverse {
+                let stdin = io::stdin();
+                exit_on_error(reverse::play(&config, &mut stdin.lock(), &mut io::stdout()).map(|_| ()));
+            } else {
+                let mut log_file = open_log(&config);
+                let log = match &mut log_file {
+                    Some(file) => EventLog::new(file),
+                    None => EventLog::disabled(),
+                };
+
+                let stdin = io::stdin();
+                if config.tournament.is_some() {
+                    // A series is scored on its own, so its
+                    // games don't go on the high score table
+                    //
+                    match tournament::run(&config, &SystemClock, log, &mut stdin.lock(), &mut io::stdout()) {
+                        Ok(series) if series.standing() == Standing::Lost => process::exit(EXIT_LOST),
+                        Ok(_) => {}
+                        Err(err) => {
+                            eprintln!("Tournament aborted: {}", err);
+                            process::exit(1);
+                        }
+                    }
+                } else if !config.plain && tui::is_available() {
+                    finish(&config, tui::run(&config, &SystemClock, log));
+                } else {
+                    finish(
+                        &config,
+                        game::run_logged(&config, &SystemClock, log, &mut stdin.lock(), &mut io::stdout()),
+                    );
+                }
+            }
+        }
+        Command::Scores(options) => {
+            if let Err(err) = scores::run(&options, &mut io::stdout()) {
+                eprintln!("Could not access high scores: {}", err);
+                process::exit(1);
+            }
+        }
+        Command::Serve(options) => {
+            let result = net::Server::bind(("0.0.0.0", options.port), &options.config).and_then(|server| {
+                println!("Listening on {}", server.local_addr()?);
+                server.with_log(io::stdout()).run()
+            });
+            exit_on_error(result.map(|_| ()));
+        }
+        Command::Join(options) => {
+            let stdin = BufReader::new(io::stdin());
+            exit_on_error(net::join(&options.addr, options.name.as_deref(), stdin, &mut io::stdout()));
+        }
+        Command::Replay(options) => match replay::run(&options, &mut io::stdout()) {
+            Ok(true) => {}
+            Ok(false) => process::exit(1),
+            Err(err) => exit_on_error(Err(err)),
+        },
+        Command::Export(options) => {
+            exit_on_error(replay::run_export(&options, &mut io::stdout()));
+        }
+    }
+}
+
+fn exit_on_error(result: io::Result<()>) {
+    if let Err(err) = result {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    }
+}
+
+// Like the scores file, a log that can't be opened
+// is only reported and the game is played without it
+//
+fn open_log(config: &Config) -> Option<File> {
+    match events::resolve_path(config.log_file.as_ref()).and_then(|path| events::open(&path)) {
+        Ok(file) => Some(file),
+        Err(err) => {
+            eprintln!("Could not open the game log: {}", err);
+            None
+        }
+    }
+}
+
+// Keeps the score of a finished game and exits with the
+// status that matches how it ended
+//
+fn finish(config: &Config, result: io::Result<Outcome>) {
+    match result {
+        Ok(Outcome::Won { attempts, elapsed }) => {
+            // A broken scores file should never spoil a win,
+            // so problems here are only reported
+            //
+            let score = Score::new(config, attempts, elapsed);
+            let recorded = scores::resolve_path(config.scores_file.as_ref())
+                .and_then(|path| scores::record_win(&path, score, &mut io::stdout()));
+            if let Err(err) = recorded {
+                eprintln!("Could not save your score: {}", err);
             }
-        };
-        
-        println!("You guessed: {}", guess);
-
-        match guess.cmp(&secret_number) {
-            Ordering::Less => println!("Too small!"),
-            Ordering::Greater => println!("Too big!"),
-	    Ordering::Equal => {
-                println!("You win!");
-                break;
-	    }
-        };
+        }
+        Ok(Outcome::Lost { .. }) => process::exit(EXIT_LOST),
+        Ok(Outcome::Quit { .. }) => {}
+        Err(err) => {
+            eprintln!("Game aborted: {}", err);
+            process::exit(1);
+        }
     }
 }
@@ -0,0 +1,419 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - `guessing_game serve --port <P>` hosts one round and
+//   `guessing_game join <host:port>` takes part in it.
+//   Everybody guesses the same secret; the first correct
+//   guess wins and ends the round.
+// - The server compares guesses with the same GameState
+//   the single player game uses.
+// - One thread per client. The shared state lives in an
+//   Arc<Mutex<_>> and every client's TcpStream is kept
+//   there too so messages can be broadcast.
+// - What happens in the round (joins, guesses, the win)
+//   goes to the server's log, which is silent unless
+//   with_log gives it somewhere to go. Lines are written
+//   after the round's lock is released, so a slow log
+//   never holds up the other players.
+// - Writes to a player time out after WRITE_TIMEOUT. A
+//   player whose write fails or times out (they stopped
+//   reading, say) is hung up on, so they can't stall the
+//   round for everybody else.
+//
+// Protocol
+//
+// Plain text over TCP, one message per line ("\n"
+// terminated, "\r\n" is accepted). Words are separated
+// by single spaces.
+//
+// Client to server:
+//
+//   NAME <name>        Set the name other players see
+//                      (default: player<id>)
+//   GUESS <n>          Guess a number
+//   QUIT               Leave the round
+//
+// Server to client:
+//
+//   WELCOME <id> <min> <max>   Sent once on connect
+//   TOO_SMALL <n>              Reply to your guess of n
+//   TOO_BIG <n>                Reply to your guess of n
+//   ERROR <message>            Your last line was not understood
+//                              or the guess was out of range
+//   CLOSEST <name>             Broadcast whenever somebody's guess
+//                              becomes the closest one so far
+//   WINNER <name> <secret>     Broadcast when the round is won;
+//                              the server then closes every
+//                              connection
+//
+
+use std::cmp::Ordering;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::game::{new_rng, pick_secret};
+use crate::state::GameState;
+
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundResult {
+    pub winner: String,
+    pub secret: u32,
+    pub guesses: u32,
+}
+
+struct Player {
+    id: usize,
+    name: String,
+    stream: TcpStream,
+}
+
+struct Round {
+    state: GameState,
+    players: Vec<Player>,
+    closest: Option<u32>,
+    winner: Option<String>,
+    notes: Vec<String>,
+}
+
+type Log = Arc<Mutex<Box<dyn Write + Send>>>;
+
+// A log that can't be written to doesn't stop the round
+//
+fn write_log(log: &Log, notes: &[String]) {
+    let mut log = log.lock().unwrap();
+    for note in notes {
+        let _ = writeln!(log, "{}", note);
+    }
+}
+
+impl Round {
+    fn name_of(&self, id: usize) -> String {
+        self.players
+            .iter()
+            .find(|p| p.id == id)
+            .map(|p| p.name.clone())
+            .unwrap_or_else(|| format!("player{}", id))
+    }
+
+    // A player that has gone away, or won't take any more,
+    // is hung up on instead of failing the whole round
+    //
+    fn send(&mut self, id: usize, message: &str) {
+        let delivered = match self.players.iter().find(|p| p.id == id) {
+            Some(player) => writeln!(&player.stream, "{}", message).is_ok(),
+            None => return,
+        };
+        if !delivered {
+            self.hang_up(id);
+        }
+    }
+
+    fn broadcast(&mut self, message: &str) {
+        let gone: Vec<usize> = self
+            .players
+            .iter()
+            .filter(|p| writeln!(&p.stream, "{}", message).is_err())
+            .map(|p| p.id)
+            .collect();
+        for id in gone {
+            self.hang_up(id);
+        }
+    }
+
+    // Shutting the stream down also ends that player's
+    // thread, which is waiting to read from it
+    //
+    fn hang_up(&mut self, id: usize) {
+        if let Some(i) = self.players.iter().position(|p| p.id == id) {
+            let player = self.players.remove(i);
+            let _ = player.stream.shutdown(Shutdown::Both);
+        }
+    }
+
+    fn guess(&mut self, id: usize, guess: u32) {
+        let (min, max) = self.state.range();
+        if guess < min || guess > max {
+            self.send(id, &format!("ERROR guess must be between {} and {}", min, max));
+            return;
+        }
+
+        let name = self.name_of(id);
+        let secret = *self.state.secret();
+        self.notes.push(format!("{} guessed {}", name, guess));
+
+        match self.state.guess(guess) {
+            Ordering::Less => self.send(id, &format!("TOO_SMALL {}", guess)),
+            Ordering::Greater => self.send(id, &format!("TOO_BIG {}", guess)),
+            Ordering::Equal => {
+                self.notes.push(format!("{} wins!", name));
+                self.broadcast(&format!("WINNER {} {}", name, secret));
+                self.winner = Some(name);
+                for player in &self.players {
+                    let _ = player.stream.shutdown(Shutdown::Both);
+                }
+                return;
+            }
+        }
+
+        let distance = guess.abs_diff(secret);
+        if self.closest.is_none_or(|closest| distance < closest) {
+            self.closest = Some(distance);
+            self.broadcast(&format!("CLOSEST {}", name));
+        }
+    }
+}
+
+pub struct Server {
+    listener: TcpListener,
+    min: u32,
+    max: u32,
+    secret: u32,
+    log: Log,
+}
+
+impl Server {
+    // The secret comes from config.seed, the same as
+    // a single player game
+    //
+    pub fn bind<A: ToSocketAddrs>(addr: A, config: &Config) -> io::Result<Server> {
+        Ok(Server {
+            listener: TcpListener::bind(addr)?,
+            min: config.min,
+            max: config.max,
+            secret: pick_secret(&mut new_rng(config.seed), config),
+            log: Arc::new(Mutex::new(Box::new(io::sink()))),
+        })
+    }
+
+    pub fn with_log<W: Write + Send + 'static>(mut self, log: W) -> Server {
+        self.log = Arc::new(Mutex::new(Box::new(log)));
+        self
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    // Accepts players until somebody wins
+    //
+    pub fn run(self) -> io::Result<RoundResult> {
+        let mut wake_addr = self.local_addr()?;
+        if wake_addr.ip().is_unspecified() {
+            wake_addr.set_ip([127, 0, 0, 1].into());
+        }
+
+        let round = Arc::new(Mutex::new(Round {
+            state: GameState::new(self.min..=self.max, self.secret),
+            players: Vec::new(),
+            closest: None,
+            winner: None,
+            notes: Vec::new(),
+        }));
+        let mut handles = Vec::new();
+
+        for (id, stream) in self.listener.incoming().enumerate() {
+            if round.lock().unwrap().winner.is_some() {
+                break;
+            }
+
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    write_log(&self.log, &[format!("Could not accept a player: {}", e)]);
+                    continue;
+                }
+            };
+
+            let id = id + 1;
+            let round = Arc::clone(&round);
+            let log = Arc::clone(&self.log);
+            handles.push(thread::spawn(move || handle_player(id, stream, round, log, wake_addr)));
+        }
+
+        for handle in handles {
+            let _ = handle.join();
+        }
+
+        let round = round.lock().unwrap();
+        Ok(RoundResult {
+            winner: round.winner.clone().unwrap_or_default(),
+            secret: self.secret,
+            guesses: round.state.attempts(),
+        })
+    }
+}
+
+fn handle_player(id: usize, stream: TcpStream, round: Arc<Mutex<Round>>, log: Log, wake_addr: SocketAddr) {
+    let reader = match stream.try_clone() {
+        Ok(reader) => BufReader::new(reader),
+        Err(_) => return,
+    };
+    if stream.set_write_timeout(Some(WRITE_TIMEOUT)).is_err() {
+        return;
+    }
+
+    let joined = {
+        let mut round = round.lock().unwrap();
+        if round.winner.is_some() {
+            return;
+        }
+        let (min, max) = round.state.range();
+        let name = format!("player{}", id);
+        round.players.push(Player {
+            id,
+            name: name.clone(),
+            stream,
+        });
+        round.send(id, &format!("WELCOME {} {} {}", id, min, max));
+        name
+    };
+    write_log(&log, &[format!("{} joined", joined)]);
+
+    for line in reader.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break,
+        };
+
+        let (won, notes) = {
+            let mut round = round.lock().unwrap();
+            if round.winner.is_some() {
+                break;
+            }
+
+            let line = line.trim();
+            let (command, argument) = match line.find(' ') {
+                Some(i) => (&line[..i], line[i + 1..].trim()),
+                None => (line, ""),
+            };
+
+            match command {
+                "NAME" if !argument.is_empty() => {
+                    if let Some(player) = round.players.iter_mut().find(|p| p.id == id) {
+                        let note = format!("{} is now called {}", player.name, argument);
+                        player.name = argument.to_string();
+                        round.notes.push(note);
+                    }
+                }
+                "GUESS" => match argument.parse() {
+                    Ok(guess) => round.guess(id, guess),
+                    Err(_) => round.send(id, &format!("ERROR '{}' is not a number", argument)),
+                },
+                "QUIT" => break,
+                _ => round.send(id, &format!("ERROR unknown command '{}'", line)),
+            }
+
+            (round.winner.is_some(), std::mem::take(&mut round.notes))
+        };
+        write_log(&log, &notes);
+
+        if won {
+            // Unblock the accept loop so the server can finish
+            //
+            let _ = TcpStream::connect(wake_addr);
+            return;
+        }
+    }
+
+    let mut round = round.lock().unwrap();
+    round.players.retain(|p| p.id != id);
+}
+
+// Turns a server message into something friendlier for
+// the terminal. Returns true once the round is over.
+//
+fn show_message<W: Write>(message: &str, output: &mut W) -> io::Result<bool> {
+    let words: Vec<&str> = message.split(' ').collect();
+
+    match words.as_slice() {
+        ["WELCOME", _, min, max] => {
+            writeln!(output, "Joined! Guess the number between {} and {}.", min, max)?
+        }
+        ["TOO_SMALL", n] => writeln!(output, "{} is too small!", n)?,
+        ["TOO_BIG", n] => writeln!(output, "{} is too big!", n)?,
+        ["CLOSEST", ..] => writeln!(output, "{} is closest so far.", words[1..].join(" "))?,
+        ["WINNER", .., secret] => {
+            let name = words[1..words.len() - 1].join(" ");
+            writeln!(output, "{} wins! The secret number was {}.", name, secret)?;
+            return Ok(true);
+        }
+        ["ERROR", ..] => writeln!(output, "Server: {}", words[1..].join(" "))?,
+        _ => writeln!(output, "Server: {}", message)?,
+    }
+
+    Ok(false)
+}
+
+// Connects to a server and plays: every line of `input`
+// is sent as a guess and every server message is written
+// to `output`. Returns once the round is over.
+//
+pub fn join<R, W>(addr: &str, name: Option<&str>, input: R, output: &mut W) -> io::Result<()>
+where
+    R: BufRead + Send + 'static,
+    W: Write,
+{
+    let mut stream = TcpStream::connect(addr)?;
+    if let Some(name) = name {
+        writeln!(stream, "NAME {}", name)?;
+    }
+
+    // Guesses go out from their own thread so that
+    // broadcasts are shown while we wait for the player
+    //
+    let mut sender = stream.try_clone()?;
+    thread::spawn(move || {
+        for line in input.lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(_) => break,
+            };
+            let line = line.trim();
+            let message = if line == "quit" {
+                String::from("QUIT")
+            } else {
+                format!("GUESS {}", line)
+            };
+            if writeln!(sender, "{}", message).is_err() {
+                break;
+            }
+        }
+    });
+
+    for message in BufReader::new(stream).lines() {
+        if show_message(&message?, output)? {
+            return Ok(());
+        }
+    }
+
+    writeln!(output, "The server closed the connection.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_are_made_readable() {
+        let mut output = Vec::new();
+        assert!(!show_message("WELCOME 1 1 100", &mut output).unwrap());
+        assert!(!show_message("TOO_SMALL 40", &mut output).unwrap());
+        assert!(!show_message("CLOSEST Ada Lovelace", &mut output).unwrap());
+        assert!(show_message("WINNER Ada Lovelace 57", &mut output).unwrap());
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(
+            output,
+            "Joined! Guess the number between 1 and 100.\n\
+             40 is too small!\n\
+             Ada Lovelace is closest so far.\n\
+             Ada Lovelace wins! The secret number was 57.\n"
+        );
+    }
+}
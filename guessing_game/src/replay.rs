@@ -0,0 +1,551 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - `replay <file>` plays every game in an event log
+//   again. The Start event gives the seed and settings,
+//   the Guess and Restart events become the input, and
+//   the events the engine records this time have to
+//   match the log, timestamps aside.
+// - `export --format csv|json [<file>]` turns a log into
+//   one row per guess (csv) or one object per game
+//   (json) for analysis elsewhere.
+//
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, BufRead, Cursor, Read, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::clock::ManualClock;
+use crate::config::{Config, Difficulty, ExportOptions, ReplayOptions};
+use crate::events::{self, Answer, Ending, Event, EventLog};
+use crate::game;
+use crate::secret::SecretType;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Match,
+    // The first event that came out differently; None
+    // when one side ran out of events
+    //
+    Mismatch {
+        index: usize,
+        logged: Option<Event>,
+        replayed: Option<Event>,
+    },
+}
+
+// The settings a game was started with
+//
+pub fn config_for(start: &Event) -> Option<Config> {
+    match *start {
+        Event::Start {
+            seed,
+            difficulty,
+            secret_type,
+            min,
+            max,
+            max_attempts,
+            tolerance,
+            time_limit,
+            ..
+        } => Some(Config {
+            difficulty,
+            secret_type,
+            tolerance,
+            min,
+            max,
+            max_attempts,
+            seed: Some(seed),
+            time_limit: time_limit.map(Duration::from_secs),
+            ..Config::default()
+        }),
+        _ => None,
+    }
+}
+
+// Plays one game (as split by events::games) again and
+// compares the two logs
+//
+pub fn replay_game(game: &[Event]) -> io::Result<Verdict> {
+    let config = game.first().and_then(config_for).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "a game has to begin with a start event")
+    })?;
+
+    let clock = ManualClock::new(Duration::from_millis(game[0].time_ms()));
+    let mut script = Script::new(game, &clock);
+    let mut recorded = Vec::new();
+    game::run_logged(&config, &clock, EventLog::new(&mut recorded), &mut script, &mut io::sink())?;
+
+    let logged: Vec<Event> = game.iter().map(Event::without_time).collect();
+    let mut replayed: Vec<Event> = events::read(Cursor::new(recorded))?
+        .iter()
+        .map(Event::without_time)
+        .collect();
+
+    // A game that was cut off ends like a quit when it is
+    // replayed; only what was logged can be checked
+    //
+    if !matches!(logged.last(), Some(Event::End { .. })) {
+        replayed.truncate(logged.len());
+    }
+
+    for index in 0..logged.len().max(replayed.len()) {
+        if logged.get(index) != replayed.get(index) {
+            return Ok(Verdict::Mismatch {
+                index,
+                logged: logged.get(index).cloned(),
+                replayed: replayed.get(index).cloned(),
+            });
+        }
+    }
+    Ok(Verdict::Match)
+}
+
+// Feeds the logged guesses and restarts back in one line
+// at a time, setting the clock to when each one was made.
+// Once they run out the clock is set to when the game
+// ended, so a countdown that ran out runs out again.
+//
+struct Script<'a> {
+    lines: VecDeque<(String, u64)>,
+    end_ms: Option<u64>,
+    clock: &'a ManualClock,
+    line: Vec<u8>,
+    position: usize,
+}
+
+impl<'a> Script<'a> {
+    fn new(game: &[Event], clock: &'a ManualClock) -> Script<'a> {
+        let mut lines = VecDeque::new();
+        let mut end_ms = None;
+
+        for event in game {
+            match event {
+                Event::Guess { time_ms, guess, .. } => lines.push_back((format!("{}\n", guess), *time_ms)),
+                Event::Restart { time_ms } => lines.push_back((String::from("restart\n"), *time_ms)),
+                Event::End { time_ms, .. } => end_ms = Some(*time_ms),
+                Event::Start { .. } => {}
+            }
+        }
+
+        Script {
+            lines,
+            end_ms,
+            clock,
+            line: Vec::new(),
+            position: 0,
+        }
+    }
+}
+
+impl<'a> Read for Script<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<'a> BufRead for Script<'a> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.position == self.line.len() {
+            match self.lines.pop_front() {
+                Some((line, time_ms)) => {
+                    self.clock.set(Duration::from_millis(time_ms));
+                    self.line = line.into_bytes();
+                    self.position = 0;
+                }
+                None => {
+                    if let Some(end_ms) = self.end_ms {
+                        self.clock.set(Duration::from_millis(end_ms));
+                    }
+                }
+            }
+        }
+        Ok(&self.line[self.position..])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.position = (self.position + amount).min(self.line.len());
+    }
+}
+
+// Replays every game in the log and reports on each.
+// Returns true if they all matched.
+//
+pub fn replay<W: Write>(events: &[Event], output: &mut W) -> io::Result<bool> {
+    let games = events::games(events);
+    let mut differ = 0;
+
+    for (n, game) in games.iter().enumerate() {
+        let record = GameRecord::new(n + 1, game);
+        write!(
+            output,
+            "Game {} (seed {}, {} {}-{}): {} guesses, {}. ",
+            record.game,
+            record.seed,
+            record.secret_type,
+            record.min,
+            record.max,
+            record.guesses.len(),
+            record.outcome.map_or("unfinished", ending_name)
+        )?;
+
+        match replay_game(game)? {
+            Verdict::Match => writeln!(output, "OK")?,
+            Verdict::Mismatch {
+                index,
+                logged,
+                replayed,
+            } => {
+                differ += 1;
+                writeln!(output, "MISMATCH at event {}:", index + 1)?;
+                writeln!(output, "  logged:   {}", describe(logged.as_ref()))?;
+                writeln!(output, "  replayed: {}", describe(replayed.as_ref()))?;
+            }
+        }
+    }
+
+    if differ == 0 {
+        writeln!(output, "Replayed {} games: all match.", games.len())?;
+    } else {
+        writeln!(output, "Replayed {} games: {} differ.", games.len(), differ)?;
+    }
+    Ok(differ == 0)
+}
+
+fn describe(event: Option<&Event>) -> String {
+    match event {
+        Some(event) => serde_json::to_string(event).unwrap_or_default(),
+        None => String::from("(nothing)"),
+    }
+}
+
+fn ending_name(ending: Ending) -> &'static str {
+    match ending {
+        Ending::Won => "won",
+        Ending::Lost => "lost",
+        Ending::Quit => "quit",
+    }
+}
+
+fn answer_name(answer: Answer) -> &'static str {
+    match answer {
+        Answer::TooSmall => "too_small",
+        Answer::TooBig => "too_big",
+        Answer::Correct => "correct",
+    }
+}
+
+// The `replay` subcommand
+//
+pub fn run<W: Write>(options: &ReplayOptions, output: &mut W) -> io::Result<bool> {
+    let events = events::read_file(&options.file)?;
+    replay(&events, output)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ExportFormat, String> {
+        match s {
+            "csv" => Ok(ExportFormat::Csv),
+            "json" => Ok(ExportFormat::Json),
+            other => Err(format!("unknown export format '{}' (expected csv or json)", other)),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GuessRecord {
+    // Restarting starts a new round of the same game
+    //
+    pub round: u32,
+    pub attempt: u32,
+    pub time_ms: u64,
+    pub guess: String,
+    pub result: Answer,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GameRecord {
+    pub game: usize,
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub secret_type: SecretType,
+    pub min: u32,
+    pub max: u32,
+    pub started_ms: u64,
+    // None for a game that was cut off
+    //
+    pub outcome: Option<Ending>,
+    pub guesses: Vec<GuessRecord>,
+}
+
+impl GameRecord {
+    // `game` is one game as split by events::games
+    //
+    pub fn new(number: usize, game: &[Event]) -> GameRecord {
+        let config = game.first().and_then(config_for).unwrap_or_default();
+        let mut record = GameRecord {
+            game: number,
+            seed: config.seed.unwrap_or_default(),
+            difficulty: config.difficulty,
+            secret_type: config.secret_type,
+            min: config.min,
+            max: config.max,
+            started_ms: game.first().map_or(0, Event::time_ms),
+            outcome: None,
+            guesses: Vec::new(),
+        };
+
+        let (mut round, mut attempt) = (1, 0);
+        for event in game {
+            match event {
+                Event::Guess { time_ms, guess, result } => {
+                    attempt += 1;
+                    record.guesses.push(GuessRecord {
+                        round,
+                        attempt,
+                        time_ms: *time_ms,
+                        guess: guess.clone(),
+                        result: *result,
+                    });
+                }
+                Event::Restart { .. } => {
+                    round += 1;
+                    attempt = 0;
+                }
+                Event::End { outcome, .. } => record.outcome = Some(*outcome),
+                Event::Start { .. } => {}
+            }
+        }
+        record
+    }
+}
+
+pub fn records(events: &[Event]) -> Vec<GameRecord> {
+    events::games(events)
+        .iter()
+        .enumerate()
+        .map(|(n, game)| GameRecord::new(n + 1, game))
+        .collect()
+}
+
+pub fn export<W: Write>(events: &[Event], format: ExportFormat, output: &mut W) -> io::Result<()> {
+    let records = records(events);
+
+    match format {
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut *output, &records)?;
+            writeln!(output)
+        }
+        ExportFormat::Csv => {
+            writeln!(output, "game,seed,type,difficulty,min,max,round,attempt,time_ms,guess,result,outcome")?;
+            for game in &records {
+                for guess in &game.guesses {
+                    writeln!(
+                        output,
+                        "{},{},{},{},{},{},{},{},{},{},{},{}",
+                        game.game,
+                        game.seed,
+                        game.secret_type,
+                        game.difficulty,
+                        game.min,
+                        game.max,
+                        guess.round,
+                        guess.attempt,
+                        guess.time_ms,
+                        csv_field(&guess.guess),
+                        answer_name(guess.result),
+                        game.outcome.map_or("unfinished", ending_name)
+                    )?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+// Quotes a field if it would otherwise break the row
+//
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// The `export` subcommand
+//
+pub fn run_export<W: Write>(options: &ExportOptions, output: &mut W) -> io::Result<()> {
+    let path = events::resolve_path(options.file.as_ref())?;
+    let events = events::read_file(&path)?;
+    export(&events, options.format, output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::SystemClock;
+    use crate::game::{Outcome, Session};
+    use crate::input::Input;
+
+    fn guess(guess: &str, result: Answer) -> Event {
+        Event::Guess {
+            time_ms: 2,
+            guess: String::from(guess),
+            result,
+        }
+    }
+
+    // A logged game of `lines` with seed 42
+    //
+    fn logged_game(lines: &str) -> Vec<Event> {
+        let config = Config {
+            seed: Some(42),
+            ..Config::default()
+        };
+        let mut recorded = Vec::new();
+        game::run_logged(&config, &SystemClock, EventLog::new(&mut recorded), &mut Cursor::new(lines), &mut io::sink())
+            .unwrap();
+        events::read(Cursor::new(recorded)).unwrap()
+    }
+
+    #[test]
+    fn a_logged_game_replays_the_same() {
+        let events = logged_game("50\nrestart\n25\n");
+        assert_eq!(replay_game(&events).unwrap(), Verdict::Match);
+    }
+
+    #[test]
+    fn a_changed_answer_is_a_mismatch() {
+        let mut events = logged_game("50\n");
+        let result = match &mut events[1] {
+            Event::Guess { result, .. } => result,
+            other => panic!("expected a guess, got {:?}", other),
+        };
+        *result = if *result == Answer::TooSmall { Answer::TooBig } else { Answer::TooSmall };
+
+        match replay_game(&events).unwrap() {
+            Verdict::Mismatch { index, .. } => assert_eq!(index, 1),
+            Verdict::Match => panic!("a changed answer went unnoticed"),
+        }
+    }
+
+    #[test]
+    fn unfinished_games_check_what_was_logged() {
+        let mut events = logged_game("50\n");
+        events.pop();
+        assert_eq!(replay_game(&events).unwrap(), Verdict::Match);
+    }
+
+    #[test]
+    fn a_countdown_runs_out_again() {
+        let config = Config {
+            seed: Some(42),
+            time_limit: Some(Duration::from_secs(10)),
+            ..Config::default()
+        };
+        let domain = 1..=100;
+        let clock = ManualClock::new(Duration::from_secs(5000));
+        let mut recorded = Vec::new();
+
+        {
+            let mut session = Session::<u32>::new(&config, &domain, 42)
+                .with_clock(&clock)
+                .with_log(EventLog::new(&mut recorded));
+            session.start(&mut io::sink()).unwrap();
+            let secret = *session.state().secret();
+            let wrong = if secret == 1 { 2 } else { 1 };
+
+            clock.advance(Duration::from_millis(9000));
+            session.handle(Input::Guess(wrong), &mut io::sink()).unwrap();
+            clock.advance(Duration::from_millis(1500));
+            let outcome = session.handle(Input::Guess(secret), &mut io::sink()).unwrap();
+            assert!(matches!(outcome, Some(Outcome::Lost { .. })));
+        }
+
+        let events = events::read(Cursor::new(recorded)).unwrap();
+        assert!(matches!(events[0], Event::Start { time_limit: Some(10), .. }));
+        assert_eq!(replay_game(&events).unwrap(), Verdict::Match);
+    }
+
+    #[test]
+    fn records_count_rounds_and_attempts() {
+        let events = vec![
+            Event::start(&Config::default(), 3, 0),
+            guess("50", Answer::TooBig),
+            Event::Restart { time_ms: 3 },
+            guess("20", Answer::TooSmall),
+            guess("30", Answer::Correct),
+            Event::End {
+                time_ms: 4,
+                outcome: Ending::Won,
+                attempts: 2,
+            },
+        ];
+
+        let records = records(&events);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].seed, 3);
+        assert_eq!(records[0].outcome, Some(Ending::Won));
+        let rounds: Vec<(u32, u32)> = records[0].guesses.iter().map(|g| (g.round, g.attempt)).collect();
+        assert_eq!(rounds, vec![(1, 1), (2, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn csv_has_a_row_per_guess() {
+        let events = vec![Event::start(&Config::default(), 3, 0), guess("50", Answer::TooBig)];
+        let mut output = Vec::new();
+        export(&events, ExportFormat::Csv, &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1], "1,3,integer,normal,1,100,1,1,2,50,too_big,unfinished");
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+
+    #[test]
+    fn json_has_an_object_per_game() {
+        let events = logged_game("50\nquit\n");
+        let mut output = Vec::new();
+        export(&events, ExportFormat::Json, &mut output).unwrap();
+
+        let games: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(games[0]["seed"], 42);
+        assert_eq!(games[0]["outcome"], "quit");
+        assert_eq!(games[0]["guesses"][0]["guess"], "50");
+    }
+
+    #[test]
+    fn format_names() {
+        assert_eq!("csv".parse(), Ok(ExportFormat::Csv));
+        assert_eq!(ExportFormat::Json.to_string(), "json");
+        assert!("xml".parse::<ExportFormat>().is_err());
+    }
+}
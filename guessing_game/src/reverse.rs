@@ -0,0 +1,241 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - Reverse mode swaps the roles: the player thinks of
+//   a number and the program guesses it with a binary
+//   search. The player is the Oracle.
+// - The answers are about the player's number, so
+//   "higher" means the guess was too small, which is
+//   Ordering::Less from the guess's point of view.
+//
+
+use std::cmp::Ordering;
+use std::io::{self, BufRead, ErrorKind, Write};
+
+use crate::config::Config;
+use crate::solver::{self, BinarySearch, Guesser, Oracle, SolveError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Found { number: u32, guesses: u32 },
+    // The answers ruled out every number
+    //
+    Inconsistent { guesses: u32 },
+    // The input ended before the number was found, which
+    // ends the game the same way quitting the normal game
+    // does
+    //
+    GaveUp { guesses: u32 },
+}
+
+// Reads "higher", "lower" or "correct" (or h/l/c) and
+// turns them into the Ordering of the guess
+//
+pub fn parse_answer(answer: &str) -> Option<Ordering> {
+    match answer.trim().to_lowercase().as_str() {
+        "higher" | "h" => Some(Ordering::Less),
+        "lower" | "l" => Some(Ordering::Greater),
+        "correct" | "c" => Some(Ordering::Equal),
+        _ => None,
+    }
+}
+
+// At the end of input, answer() fails with UnexpectedEof
+// so that solve() stops; play() turns that into GaveUp
+//
+pub struct HumanOracle<'a, R, W> {
+    input: &'a mut R,
+    output: &'a mut W,
+    answered: u32,
+}
+
+impl<'a, R: BufRead, W: Write> HumanOracle<'a, R, W> {
+    pub fn new(input: &'a mut R, output: &'a mut W) -> HumanOracle<'a, R, W> {
+        HumanOracle {
+            input,
+            output,
+            answered: 0,
+        }
+    }
+
+    pub fn answered(&self) -> u32 {
+        self.answered
+    }
+}
+
+impl<'a, R: BufRead, W: Write> Oracle for HumanOracle<'a, R, W> {
+    fn answer(&mut self, guess: u32) -> io::Result<Ordering> {
+        loop {
+            writeln!(self.output, "Is it {}? (higher/lower/correct)", guess)?;
+            self.output.flush()?;
+
+            let mut answer = String::new();
+            if self.input.read_line(&mut answer)? == 0 {
+                return Err(io::Error::new(ErrorKind::UnexpectedEof, "input ended before the game did"));
+            }
+
+            match parse_answer(&answer) {
+                Some(ordering) => {
+                    self.answered += 1;
+                    return Ok(ordering);
+                }
+                None => writeln!(self.output, "Please answer higher, lower or correct (h/l/c).")?,
+            }
+        }
+    }
+}
+
+pub fn play<R, W>(config: &Config, input: &mut R, output: &mut W) -> io::Result<Outcome>
+where
+    R: BufRead,
+    W: Write,
+{
+    writeln!(
+        output,
+        "Think of a number between {} and {} and I'll guess it.",
+        config.min, config.max
+    )?;
+
+    let mut guesser = Guesser::new(config.min, config.max, Box::new(BinarySearch));
+    let mut oracle = HumanOracle::new(input, output);
+    let result = solver::solve(&mut guesser, &mut oracle);
+    let answered = oracle.answered();
+
+    match result {
+        Ok(transcript) => {
+            let (number, _) = transcript[transcript.len() - 1];
+            let guesses = transcript.len() as u32;
+            writeln!(output, "Got it! Your number is {} ({} guesses).", number, guesses)?;
+            Ok(Outcome::Found { number, guesses })
+        }
+        Err(SolveError::Inconsistent(transcript)) => {
+            writeln!(
+                output,
+                "Those answers contradict each other: no number between {} and {} fits them all.",
+                config.min, config.max
+            )?;
+            Ok(Outcome::Inconsistent {
+                guesses: transcript.len() as u32,
+            })
+        }
+        Err(SolveError::Io(e)) if e.kind() == ErrorKind::UnexpectedEof => {
+            writeln!(output)?;
+            writeln!(output, "Goodbye! I never found your number.")?;
+            Ok(Outcome::GaveUp { guesses: answered })
+        }
+        Err(SolveError::Io(e)) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn play_script(config: &Config, script: &str) -> (io::Result<Outcome>, String) {
+        let mut input = Cursor::new(script.to_string());
+        let mut output = Vec::new();
+        let outcome = play(config, &mut input, &mut output);
+        (outcome, String::from_utf8(output).unwrap())
+    }
+
+    // Answers every question truthfully for `number`
+    //
+    fn honest_script(config: &Config, number: u32) -> String {
+        let mut guesser = Guesser::new(config.min, config.max, Box::new(BinarySearch));
+        let mut script = String::new();
+        while let Some(guess) = guesser.next_guess() {
+            let answer = guess.cmp(&number);
+            script.push_str(match answer {
+                Ordering::Less => "higher\n",
+                Ordering::Greater => "lower\n",
+                Ordering::Equal => "correct\n",
+            });
+            guesser.update(guess, answer);
+            if answer == Ordering::Equal {
+                break;
+            }
+        }
+        script
+    }
+
+    #[test]
+    fn parses_answers_and_shorthands() {
+        assert_eq!(parse_answer("higher\n"), Some(Ordering::Less));
+        assert_eq!(parse_answer(" H "), Some(Ordering::Less));
+        assert_eq!(parse_answer("lower"), Some(Ordering::Greater));
+        assert_eq!(parse_answer("l"), Some(Ordering::Greater));
+        assert_eq!(parse_answer("Correct"), Some(Ordering::Equal));
+        assert_eq!(parse_answer("c"), Some(Ordering::Equal));
+        assert_eq!(parse_answer("42"), None);
+        assert_eq!(parse_answer(""), None);
+    }
+
+    #[test]
+    fn finds_every_number_with_honest_answers() {
+        let config = Config::default();
+        for number in config.min..=config.max {
+            let (outcome, _) = play_script(&config, &honest_script(&config, number));
+            match outcome.unwrap() {
+                Outcome::Found { number: found, guesses } => {
+                    assert_eq!(found, number);
+                    assert!(guesses <= 7);
+                }
+                other => panic!("expected to find {}, got {:?}", number, other),
+            }
+        }
+    }
+
+    #[test]
+    fn shorthand_transcript() {
+        let config = Config::default();
+        let (outcome, transcript) = play_script(&config, "h\nl\nc\n");
+
+        assert_eq!(outcome.unwrap(), Outcome::Found { number: 62, guesses: 3 });
+        assert!(transcript.contains("Is it 50?"));
+        assert!(transcript.contains("Is it 75?"));
+        assert!(transcript.contains("Got it! Your number is 62 (3 guesses)."));
+    }
+
+    #[test]
+    fn detects_lying() {
+        let config = Config {
+            min: 1,
+            max: 3,
+            ..Config::default()
+        };
+        // 2? higher. 3? lower. Nothing is left.
+        //
+        let (outcome, transcript) = play_script(&config, "higher\nlower\n");
+
+        assert_eq!(outcome.unwrap(), Outcome::Inconsistent { guesses: 2 });
+        assert!(transcript.contains("contradict"));
+    }
+
+    #[test]
+    fn lying_at_the_bottom_of_the_range() {
+        let config = Config {
+            min: 0,
+            max: 0,
+            ..Config::default()
+        };
+        let (outcome, _) = play_script(&config, "lower\n");
+        assert_eq!(outcome.unwrap(), Outcome::Inconsistent { guesses: 1 });
+    }
+
+    #[test]
+    fn asks_again_after_nonsense() {
+        let config = Config::default();
+        let (outcome, transcript) = play_script(&config, "maybe\n\nc\n");
+
+        assert_eq!(outcome.unwrap(), Outcome::Found { number: 50, guesses: 1 });
+        assert_eq!(transcript.matches("Please answer").count(), 2);
+    }
+
+    #[test]
+    fn end_of_input_gives_up() {
+        let (outcome, transcript) = play_script(&Config::default(), "higher\n");
+        assert_eq!(outcome.unwrap(), Outcome::GaveUp { guesses: 1 });
+        assert!(transcript.ends_with("Goodbye! I never found your number.\n"));
+    }
+}
@@ -0,0 +1,379 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - Scores are stored as a JSON array in
+//   <data dir>/guessing_game/scores.json unless a
+//   different file is given with --scores-file.
+// - A missing file is just an empty table. A file
+//   that can't be parsed is moved aside to
+//   scores.json.bak (with a warning) so one bad write
+//   never stops anybody from playing.
+//
+
+use std::fs;
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Config, Difficulty, ScoresOptions};
+use crate::secret::SecretType;
+
+pub const LEADERBOARD_SIZE: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Score {
+    pub difficulty: Difficulty,
+    // Tables written before there were other game types
+    // only hold integer games
+    //
+    #[serde(default)]
+    pub secret_type: SecretType,
+    pub min: u32,
+    pub max: u32,
+    pub guesses: u32,
+    pub elapsed_ms: u64,
+}
+
+impl Score {
+    pub fn new(config: &Config, guesses: u32, elapsed: Duration) -> Score {
+        Score {
+            difficulty: config.difficulty,
+            secret_type: config.secret_type,
+            min: config.min,
+            max: config.max,
+            guesses,
+            elapsed_ms: elapsed.as_millis() as u64,
+        }
+    }
+}
+
+// Which scores to show. None means "any".
+//
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    pub difficulty: Option<Difficulty>,
+    pub secret_type: Option<SecretType>,
+    pub min: Option<u32>,
+    pub max: Option<u32>,
+}
+
+impl Filter {
+    // Only games played with the same difficulty and range
+    // are comparable, so that is what a leaderboard shows
+    //
+    pub fn same_game(score: &Score) -> Filter {
+        Filter {
+            difficulty: Some(score.difficulty),
+            secret_type: Some(score.secret_type),
+            min: Some(score.min),
+            max: Some(score.max),
+        }
+    }
+
+    pub fn matches(&self, score: &Score) -> bool {
+        self.difficulty.is_none_or(|d| d == score.difficulty)
+            && self.secret_type.is_none_or(|t| t == score.secret_type)
+            && self.min.is_none_or(|min| min == score.min)
+            && self.max.is_none_or(|max| max == score.max)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScoreTable {
+    scores: Vec<Score>,
+}
+
+impl ScoreTable {
+    // Missing files load as an empty table, anything that
+    // isn't valid JSON is an ErrorKind::InvalidData error
+    //
+    pub fn load(path: &Path) -> io::Result<ScoreTable> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(ScoreTable::default()),
+            Err(e) => return Err(e),
+        };
+
+        if contents.trim().is_empty() {
+            return Ok(ScoreTable::default());
+        }
+
+        let scores = serde_json::from_str(&contents)
+            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+        Ok(ScoreTable { scores })
+    }
+
+    // Like load(), but a corrupt file is reported on `warnings`,
+    // moved out of the way and replaced by an empty table
+    //
+    pub fn load_or_recover<W: Write>(path: &Path, warnings: &mut W) -> io::Result<ScoreTable> {
+        match ScoreTable::load(path) {
+            Err(ref e) if e.kind() == ErrorKind::InvalidData => {
+                let backup = backup_path(path);
+                writeln!(
+                    warnings,
+                    "Warning: could not read scores from {} ({}); moved it to {} and starting a new table.",
+                    path.display(),
+                    e,
+                    backup.display()
+                )?;
+                fs::rename(path, &backup)?;
+                Ok(ScoreTable::default())
+            }
+            other => other,
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(&self.scores)?;
+        fs::write(path, json)
+    }
+
+    pub fn add(&mut self, score: Score) {
+        self.scores.push(score);
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    // Best first: fewest guesses, then fastest
+    //
+    pub fn top(&self, filter: &Filter, n: usize) -> Vec<&Score> {
+        let mut scores: Vec<&Score> = self.scores.iter().filter(|s| filter.matches(s)).collect();
+        scores.sort_by_key(|s| (s.guesses, s.elapsed_ms));
+        scores.truncate(n);
+        scores
+    }
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    PathBuf::from(backup)
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("guessing_game").join("scores.json"))
+}
+
+// An explicit --scores-file always wins over the data dir
+//
+pub fn resolve_path(scores_file: Option<&PathBuf>) -> io::Result<PathBuf> {
+    match scores_file {
+        Some(path) => Ok(path.clone()),
+        None => default_path().ok_or_else(|| {
+            io::Error::new(
+                ErrorKind::NotFound,
+                "no data directory for this user; pass --scores-file",
+            )
+        }),
+    }
+}
+
+pub fn print_table<W: Write>(scores: &[&Score], output: &mut W) -> io::Result<()> {
+    if scores.is_empty() {
+        return writeln!(output, "No scores yet.");
+    }
+
+    writeln!(output, "Rank  Difficulty  Type     Range        Guesses  Time")?;
+    for (i, score) in scores.iter().enumerate() {
+        writeln!(
+            output,
+            "{:>4}  {:<10}  {:<7}  {:<11}  {:>7}  {:.1}s",
+            i + 1,
+            score.difficulty.to_string(),
+            score.secret_type.to_string(),
+            format!("{}-{}", score.min, score.max),
+            score.guesses,
+            score.elapsed_ms as f64 / 1000.0
+        )?;
+    }
+    Ok(())
+}
+
+// Saves a win and prints the leaderboard for that kind of game
+//
+pub fn record_win<W: Write>(path: &Path, score: Score, output: &mut W) -> io::Result<()> {
+    let filter = Filter::same_game(&score);
+
+    let mut table = ScoreTable::load_or_recover(path, output)?;
+    table.add(score);
+    table.save(path)?;
+
+    writeln!(output, "High scores:")?;
+    print_table(&table.top(&filter, LEADERBOARD_SIZE), output)
+}
+
+// The `scores` subcommand
+//
+pub fn run<W: Write>(options: &ScoresOptions, output: &mut W) -> io::Result<()> {
+    let path = resolve_path(options.scores_file.as_ref())?;
+
+    if options.reset {
+        ScoreTable::default().save(&path)?;
+        return writeln!(output, "High scores cleared ({}).", path.display());
+    }
+
+    let filter = Filter {
+        difficulty: options.difficulty,
+        secret_type: options.secret_type,
+        min: options.min,
+        max: options.max,
+    };
+    let table = ScoreTable::load_or_recover(&path, output)?;
+    print_table(&table.top(&filter, LEADERBOARD_SIZE), output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::process;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let path = env::temp_dir().join(format!("guessing_game-scores-{}-{}.json", process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn score(difficulty: Difficulty, guesses: u32, elapsed_ms: u64) -> Score {
+        let (min, max) = difficulty.range();
+        Score {
+            difficulty,
+            secret_type: SecretType::Integer,
+            min,
+            max,
+            guesses,
+            elapsed_ms,
+        }
+    }
+
+    #[test]
+    fn missing_file_is_empty() {
+        let table = ScoreTable::load(&temp_path("missing")).unwrap();
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let path = temp_path("round-trip");
+        let mut table = ScoreTable::default();
+        table.add(score(Difficulty::Easy, 4, 1500));
+        table.add(score(Difficulty::Hard, 9, 30000));
+        table.save(&path).unwrap();
+
+        assert_eq!(ScoreTable::load(&path).unwrap(), table);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn corrupt_file_is_moved_aside() {
+        let path = temp_path("corrupt");
+        fs::write(&path, "{ this is not json").unwrap();
+
+        assert_eq!(ScoreTable::load(&path).unwrap_err().kind(), ErrorKind::InvalidData);
+
+        let mut warnings = Vec::new();
+        let table = ScoreTable::load_or_recover(&path, &mut warnings).unwrap();
+        assert!(table.is_empty());
+        assert!(String::from_utf8(warnings).unwrap().starts_with("Warning"));
+
+        let backup = backup_path(&path);
+        assert!(!path.exists());
+        assert!(backup.exists());
+        fs::remove_file(backup).unwrap();
+    }
+
+    #[test]
+    fn top_sorts_filters_and_truncates() {
+        let mut table = ScoreTable::default();
+        for guesses in (1..=12).rev() {
+            table.add(score(Difficulty::Normal, guesses, 1000));
+        }
+        table.add(score(Difficulty::Normal, 3, 500));
+        table.add(score(Difficulty::Easy, 1, 100));
+
+        let filter = Filter {
+            difficulty: Some(Difficulty::Normal),
+            ..Filter::default()
+        };
+        let top = table.top(&filter, LEADERBOARD_SIZE);
+
+        assert_eq!(top.len(), LEADERBOARD_SIZE);
+        assert_eq!(top[0].guesses, 1);
+        assert_eq!((top[2].guesses, top[2].elapsed_ms), (3, 500));
+        assert!(top.iter().all(|s| s.difficulty == Difficulty::Normal));
+    }
+
+    #[test]
+    fn filter_by_range() {
+        let mut table = ScoreTable::default();
+        table.add(score(Difficulty::Normal, 5, 0));
+        table.add(Score { min: 1, max: 10, ..score(Difficulty::Normal, 2, 0) });
+
+        let filter = Filter { max: Some(10), ..Filter::default() };
+        let top = table.top(&filter, LEADERBOARD_SIZE);
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].guesses, 2);
+    }
+
+    #[test]
+    fn old_tables_load_as_integer_games() {
+        let path = temp_path("old");
+        fs::write(
+            &path,
+            r#"[{"difficulty":"easy","min":1,"max":50,"guesses":4,"elapsed_ms":900}]"#,
+        )
+        .unwrap();
+
+        let table = ScoreTable::load(&path).unwrap();
+        let filter = Filter {
+            secret_type: Some(SecretType::Integer),
+            ..Filter::default()
+        };
+        assert_eq!(table.top(&filter, LEADERBOARD_SIZE).len(), 1);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn record_win_appends_and_prints() {
+        let path = temp_path("record");
+        let mut output = Vec::new();
+        record_win(&path, score(Difficulty::Easy, 6, 2000), &mut output).unwrap();
+        record_win(&path, score(Difficulty::Easy, 3, 4000), &mut output).unwrap();
+
+        assert_eq!(ScoreTable::load(&path).unwrap().len(), 2);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("High scores:"));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn subcommand_lists_and_resets() {
+        let path = temp_path("subcommand");
+        record_win(&path, score(Difficulty::Hard, 8, 2000), &mut Vec::new()).unwrap();
+
+        let mut options = ScoresOptions {
+            scores_file: Some(path.clone()),
+            ..ScoresOptions::default()
+        };
+        let mut output = Vec::new();
+        run(&options, &mut output).unwrap();
+        assert!(String::from_utf8(output).unwrap().contains("hard"));
+
+        options.reset = true;
+        run(&options, &mut Vec::new()).unwrap();
+        assert!(ScoreTable::load(&path).unwrap().is_empty());
+        fs::remove_file(&path).unwrap();
+    }
+}
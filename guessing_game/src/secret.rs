@@ -0,0 +1,608 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - The game is generic over the type of the secret.
+//   A Secret knows how to pick itself at random, how
+//   to parse a guess and how a guess compares to it.
+// - Everything a type needs for that (a range, a
+//   tolerance, a word list) is its Domain, so the
+//   trait works with plain values like u32 and char.
+// - The game types are:
+//     * integers in a range (u32)
+//     * floats in a range, where a guess within the
+//       tolerance counts as correct (Float)
+//     * single letters a-z (char)
+//     * words from the bundled src/words.txt, in
+//       alphabetical order (Word)
+//
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::input::ParseError;
+use crate::state;
+
+pub trait Secret: Clone + fmt::Debug + fmt::Display + Sized {
+    type Domain: Clone + fmt::Debug;
+
+    const NOUN: &'static str = "number";
+    const TOO_SMALL: &'static str = "Too small!";
+    const TOO_BIG: &'static str = "Too big!";
+
+    fn random<R: Rng + ?Sized>(rng: &mut R, domain: &Self::Domain) -> Self;
+
+    fn parse(input: &str, domain: &Self::Domain) -> Result<Self, ParseError>;
+
+    // How this guess compares to the secret; Equal means
+    // the guess wins
+    //
+    fn compare(&self, secret: &Self, domain: &Self::Domain) -> Ordering;
+
+    // The smallest and largest possible secret
+    //
+    fn bounds(domain: &Self::Domain) -> (Self, Self);
+
+    // The smallest and largest secret still possible when
+    // the secret is known to be above one guess and below
+    // another
+    //
+    fn narrow(above: Option<&Self>, below: Option<&Self>, domain: &Self::Domain) -> (Self, Self);
+
+    // How many guesses a binary search needs to find the
+    // secret
+    //
+    fn optimal_guesses(secret: &Self, domain: &Self::Domain) -> u32;
+
+    // Where this value sits between the bounds, from 0.0
+    // for the smallest to 1.0 for the largest
+    //
+    fn fraction(&self, domain: &Self::Domain) -> f64;
+
+    // Written so that parse() gives back exactly this
+    // value, where Display might round it
+    //
+    fn exact(&self) -> String {
+        self.to_string()
+    }
+
+    fn describe(domain: &Self::Domain) -> String {
+        let (low, high) = Self::bounds(domain);
+        format!("Guess the {} between {} and {}!", Self::NOUN, low, high)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretType {
+    #[default]
+    Integer,
+    Float,
+    Letter,
+    Word,
+}
+
+impl FromStr for SecretType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<SecretType, String> {
+        match s {
+            "integer" => Ok(SecretType::Integer),
+            "float" => Ok(SecretType::Float),
+            "letter" => Ok(SecretType::Letter),
+            "word" => Ok(SecretType::Word),
+            other => Err(format!(
+                "unknown game type '{}' (expected integer, float, letter or word)",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SecretType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            SecretType::Integer => "integer",
+            SecretType::Float => "float",
+            SecretType::Letter => "letter",
+            SecretType::Word => "word",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Integers
+//
+
+impl Secret for u32 {
+    type Domain = RangeInclusive<u32>;
+
+    fn random<R: Rng + ?Sized>(rng: &mut R, domain: &RangeInclusive<u32>) -> u32 {
+        rng.gen_range(domain.clone())
+    }
+
+    fn parse(input: &str, domain: &RangeInclusive<u32>) -> Result<u32, ParseError> {
+        let guess: u32 = input.parse().map_err(|e: std::num::ParseIntError| match e.kind() {
+            std::num::IntErrorKind::PosOverflow => ParseError::Overflow(input.to_string()),
+            _ if is_negative_number(input) => ParseError::Negative(input.to_string()),
+            _ => ParseError::NotANumber(input.to_string()),
+        })?;
+
+        if !domain.contains(&guess) {
+            return Err(ParseError::out_of_range(&guess, domain.start(), domain.end()));
+        }
+        Ok(guess)
+    }
+
+    fn compare(&self, secret: &u32, _domain: &RangeInclusive<u32>) -> Ordering {
+        self.cmp(secret)
+    }
+
+    fn bounds(domain: &RangeInclusive<u32>) -> (u32, u32) {
+        (*domain.start(), *domain.end())
+    }
+
+    fn narrow(above: Option<&u32>, below: Option<&u32>, domain: &RangeInclusive<u32>) -> (u32, u32) {
+        let low = above.map_or(*domain.start(), |n| n.saturating_add(1));
+        let high = below.map_or(*domain.end(), |n| n.saturating_sub(1));
+        (low, high)
+    }
+
+    fn optimal_guesses(secret: &u32, domain: &RangeInclusive<u32>) -> u32 {
+        state::optimal_guesses(*domain.start(), *domain.end(), *secret)
+    }
+
+    fn fraction(&self, domain: &RangeInclusive<u32>) -> f64 {
+        fraction_between(*self as f64, *domain.start() as f64, *domain.end() as f64)
+    }
+}
+
+fn fraction_between(value: f64, low: f64, high: f64) -> f64 {
+    if high <= low {
+        return 0.0;
+    }
+    ((value - low) / (high - low)).clamp(0.0, 1.0)
+}
+
+fn is_negative_number(s: &str) -> bool {
+    match s.strip_prefix('-') {
+        Some(digits) => !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()),
+        None => false,
+    }
+}
+
+// Floats
+//
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FloatRange {
+    pub min: f64,
+    pub max: f64,
+    pub tolerance: f64,
+}
+
+// A newtype, so that the secret is shown rounded to
+// two places instead of with every digit of the f64
+//
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Float(pub f64);
+
+impl fmt::Display for Float {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.2}", self.0)
+    }
+}
+
+impl Secret for Float {
+    type Domain = FloatRange;
+
+    fn random<R: Rng + ?Sized>(rng: &mut R, domain: &FloatRange) -> Float {
+        Float(rng.gen_range(domain.min..=domain.max))
+    }
+
+    fn parse(input: &str, domain: &FloatRange) -> Result<Float, ParseError> {
+        let guess: f64 = match input.parse() {
+            Ok(guess) if f64::is_finite(guess) => guess,
+            _ => return Err(ParseError::NotANumber(input.to_string())),
+        };
+
+        if guess < domain.min || guess > domain.max {
+            return Err(ParseError::out_of_range(
+                &Float(guess),
+                &Float(domain.min),
+                &Float(domain.max),
+            ));
+        }
+        Ok(Float(guess))
+    }
+
+    fn compare(&self, secret: &Float, domain: &FloatRange) -> Ordering {
+        if (self.0 - secret.0).abs() <= domain.tolerance {
+            Ordering::Equal
+        } else if self.0 < secret.0 {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        }
+    }
+
+    fn bounds(domain: &FloatRange) -> (Float, Float) {
+        (Float(domain.min), Float(domain.max))
+    }
+
+    // A guess that was too small was more than the
+    // tolerance below the secret
+    //
+    fn narrow(above: Option<&Float>, below: Option<&Float>, domain: &FloatRange) -> (Float, Float) {
+        let low = above.map_or(domain.min, |f| (f.0 + domain.tolerance).min(domain.max));
+        let high = below.map_or(domain.max, |f| (f.0 - domain.tolerance).max(domain.min));
+        (Float(low), Float(high))
+    }
+
+    fn optimal_guesses(secret: &Float, domain: &FloatRange) -> u32 {
+        let (mut low, mut high) = (domain.min, domain.max);
+        let mut guesses = 1;
+
+        // Bisection halves the interval every time, so
+        // this ends long before the cap unless the
+        // tolerance is zero
+        //
+        while guesses < 64 {
+            let middle = low + (high - low) / 2.0;
+            match Float(middle).compare(secret, domain) {
+                Ordering::Less => low = middle,
+                Ordering::Greater => high = middle,
+                Ordering::Equal => break,
+            }
+            guesses += 1;
+        }
+        guesses
+    }
+
+    fn exact(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn fraction(&self, domain: &FloatRange) -> f64 {
+        fraction_between(self.0, domain.min, domain.max)
+    }
+
+    fn describe(domain: &FloatRange) -> String {
+        format!(
+            "Guess the number between {} and {}, to within {}!",
+            Float(domain.min),
+            Float(domain.max),
+            domain.tolerance
+        )
+    }
+}
+
+// Letters
+//
+
+impl Secret for char {
+    type Domain = RangeInclusive<char>;
+
+    const NOUN: &'static str = "letter";
+    const TOO_SMALL: &'static str = "Too early in the alphabet!";
+    const TOO_BIG: &'static str = "Too late in the alphabet!";
+
+    fn random<R: Rng + ?Sized>(rng: &mut R, domain: &RangeInclusive<char>) -> char {
+        rng.gen_range(domain.clone())
+    }
+
+    fn parse(input: &str, domain: &RangeInclusive<char>) -> Result<char, ParseError> {
+        let mut chars = input.chars();
+        let guess = match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphabetic() => c.to_ascii_lowercase(),
+            _ => return Err(ParseError::NotALetter(input.to_string())),
+        };
+
+        if !domain.contains(&guess) {
+            return Err(ParseError::out_of_range(&guess, domain.start(), domain.end()));
+        }
+        Ok(guess)
+    }
+
+    fn compare(&self, secret: &char, _domain: &RangeInclusive<char>) -> Ordering {
+        self.cmp(secret)
+    }
+
+    fn bounds(domain: &RangeInclusive<char>) -> (char, char) {
+        (*domain.start(), *domain.end())
+    }
+
+    fn narrow(above: Option<&char>, below: Option<&char>, domain: &RangeInclusive<char>) -> (char, char) {
+        let step = |c: char, by: i32| char::from_u32((c as i32 + by) as u32).unwrap_or(c);
+        let low = above.map_or(*domain.start(), |&c| step(c, 1));
+        let high = below.map_or(*domain.end(), |&c| step(c, -1));
+        (low, high)
+    }
+
+    fn optimal_guesses(secret: &char, domain: &RangeInclusive<char>) -> u32 {
+        state::optimal_guesses(*domain.start() as u32, *domain.end() as u32, *secret as u32)
+    }
+
+    fn fraction(&self, domain: &RangeInclusive<char>) -> f64 {
+        fraction_between(*self as u32 as f64, *domain.start() as u32 as f64, *domain.end() as u32 as f64)
+    }
+}
+
+// Words
+//
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordList {
+    words: Vec<&'static str>,
+}
+
+impl WordList {
+    pub fn bundled() -> WordList {
+        WordList::new(include_str!("words.txt").lines())
+    }
+
+    // Keeps the list sorted and free of duplicates so the
+    // index order is the alphabetical order
+    //
+    pub fn new<I: IntoIterator<Item = &'static str>>(words: I) -> WordList {
+        let mut words: Vec<&'static str> = words
+            .into_iter()
+            .map(str::trim)
+            .filter(|w| !w.is_empty())
+            .collect();
+        words.sort_unstable();
+        words.dedup();
+        WordList { words }
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    fn position(&self, word: &Word) -> Result<usize, usize> {
+        self.words.binary_search(&word.0.as_str())
+    }
+
+    fn word(&self, index: usize) -> Word {
+        Word(self.words[index].to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Word(pub String);
+
+impl fmt::Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Secret for Word {
+    type Domain = WordList;
+
+    const NOUN: &'static str = "word";
+    const TOO_SMALL: &'static str = "Too early in the alphabet!";
+    const TOO_BIG: &'static str = "Too late in the alphabet!";
+
+    fn random<R: Rng + ?Sized>(rng: &mut R, domain: &WordList) -> Word {
+        domain.word(rng.gen_range(0..domain.len()))
+    }
+
+    fn parse(input: &str, domain: &WordList) -> Result<Word, ParseError> {
+        let guess = Word(input.to_lowercase());
+        match domain.position(&guess) {
+            Ok(_) => Ok(guess),
+            Err(_) => Err(ParseError::UnknownWord(input.to_string())),
+        }
+    }
+
+    fn compare(&self, secret: &Word, _domain: &WordList) -> Ordering {
+        self.cmp(secret)
+    }
+
+    fn bounds(domain: &WordList) -> (Word, Word) {
+        (domain.word(0), domain.word(domain.len() - 1))
+    }
+
+    // Guesses are always in the list, so the neighbours of
+    // a guess are the next and previous words in it
+    //
+    fn narrow(above: Option<&Word>, below: Option<&Word>, domain: &WordList) -> (Word, Word) {
+        let low = above.map_or(0, |w| match domain.position(w) {
+            Ok(i) => (i + 1).min(domain.len() - 1),
+            Err(i) => i.min(domain.len() - 1),
+        });
+        let high = below.map_or(domain.len() - 1, |w| match domain.position(w) {
+            Ok(i) | Err(i) => i.saturating_sub(1),
+        });
+        (domain.word(low), domain.word(high))
+    }
+
+    fn optimal_guesses(secret: &Word, domain: &WordList) -> u32 {
+        let index = domain.position(secret).unwrap_or(0) as u32;
+        state::optimal_guesses(0, domain.len() as u32 - 1, index)
+    }
+
+    fn fraction(&self, domain: &WordList) -> f64 {
+        let index = match domain.position(self) {
+            Ok(i) | Err(i) => i,
+        };
+        fraction_between(index as f64, 0.0, domain.len() as f64 - 1.0)
+    }
+
+    fn describe(domain: &WordList) -> String {
+        let (first, last) = Word::bounds(domain);
+        format!(
+            "Guess the word! It's one of {} words from '{}' to '{}'.",
+            domain.len(),
+            first,
+            last
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::new_rng;
+
+    #[test]
+    fn secret_type_names() {
+        for name in ["integer", "float", "letter", "word"].iter() {
+            let secret_type: SecretType = name.parse().unwrap();
+            assert_eq!(secret_type.to_string(), *name);
+        }
+        assert!("colour".parse::<SecretType>().is_err());
+    }
+
+    mod integer {
+        use super::*;
+
+        #[test]
+        fn random_parse_compare() {
+            let domain = 1..=100;
+            let mut rng = new_rng(Some(3));
+            for _ in 0..100 {
+                assert!(domain.contains(&u32::random(&mut rng, &domain)));
+            }
+
+            assert_eq!(u32::parse("42", &domain), Ok(42));
+            assert_eq!(u32::parse("-4", &domain), Err(ParseError::Negative(String::from("-4"))));
+            assert!(matches!(u32::parse("101", &domain), Err(ParseError::OutOfRange { .. })));
+            assert_eq!(10.compare(&20, &domain), Ordering::Less);
+            assert_eq!(20.compare(&20, &domain), Ordering::Equal);
+        }
+
+        #[test]
+        fn narrow_steps_past_the_guesses() {
+            let domain = 1..=100;
+            assert_eq!(u32::narrow(None, None, &domain), (1, 100));
+            assert_eq!(u32::narrow(Some(&42), Some(&75), &domain), (43, 74));
+        }
+
+        #[test]
+        fn fraction_runs_from_bottom_to_top() {
+            assert_eq!(1.fraction(&(1..=101)), 0.0);
+            assert_eq!(51.fraction(&(1..=101)), 0.5);
+            assert_eq!(101.fraction(&(1..=101)), 1.0);
+            assert_eq!(7.fraction(&(7..=7)), 0.0);
+        }
+    }
+
+    mod float {
+        use super::*;
+
+        const DOMAIN: FloatRange = FloatRange {
+            min: 0.0,
+            max: 10.0,
+            tolerance: 0.25,
+        };
+
+        #[test]
+        fn close_enough_is_equal() {
+            let secret = Float(5.0);
+            assert_eq!(Float(5.2).compare(&secret, &DOMAIN), Ordering::Equal);
+            assert_eq!(Float(4.75).compare(&secret, &DOMAIN), Ordering::Equal);
+            assert_eq!(Float(4.7).compare(&secret, &DOMAIN), Ordering::Less);
+            assert_eq!(Float(5.3).compare(&secret, &DOMAIN), Ordering::Greater);
+        }
+
+        #[test]
+        fn parse_and_random() {
+            assert_eq!(Float::parse("3.5", &DOMAIN), Ok(Float(3.5)));
+            assert_eq!(Float::parse("7", &DOMAIN), Ok(Float(7.0)));
+            assert!(matches!(Float::parse("NaN", &DOMAIN), Err(ParseError::NotANumber(_))));
+            assert!(matches!(Float::parse("inf", &DOMAIN), Err(ParseError::NotANumber(_))));
+            assert!(matches!(Float::parse("10.5", &DOMAIN), Err(ParseError::OutOfRange { .. })));
+
+            let mut rng = new_rng(Some(3));
+            for _ in 0..100 {
+                let Float(f) = Float::random(&mut rng, &DOMAIN);
+                assert!((0.0..=10.0).contains(&f));
+            }
+        }
+
+        #[test]
+        fn narrow_and_optimal_play() {
+            assert_eq!(
+                Float::narrow(Some(&Float(2.0)), Some(&Float(8.0)), &DOMAIN),
+                (Float(2.25), Float(7.75))
+            );
+            assert_eq!(Float::optimal_guesses(&Float(5.0), &DOMAIN), 1);
+            assert!(Float::optimal_guesses(&Float(0.1), &DOMAIN) <= 6);
+            assert_eq!(Float(1.0 / 3.0).to_string(), "0.33");
+            assert_eq!(Float::parse(&Float(1.0 / 3.0).exact(), &FloatRange { max: 1.0, ..DOMAIN }), Ok(Float(1.0 / 3.0)));
+        }
+    }
+
+    mod letter {
+        use super::*;
+
+        #[test]
+        fn parse_compare_narrow() {
+            let domain = 'a'..='z';
+            assert_eq!(char::parse("Q", &domain), Ok('q'));
+            assert_eq!(char::parse("ab", &domain), Err(ParseError::NotALetter(String::from("ab"))));
+            assert_eq!(char::parse("7", &domain), Err(ParseError::NotALetter(String::from("7"))));
+            assert_eq!('c'.compare(&'q', &domain), Ordering::Less);
+            assert_eq!(char::narrow(Some(&'c'), Some(&'q'), &domain), ('d', 'p'));
+            assert_eq!(char::optimal_guesses(&'m', &domain), 1);
+            assert_eq!('z'.fraction(&domain), 1.0);
+        }
+
+        #[test]
+        fn random_letters_stay_in_the_alphabet() {
+            let mut rng = new_rng(Some(8));
+            for _ in 0..100 {
+                assert!(char::random(&mut rng, &('a'..='z')).is_ascii_lowercase());
+            }
+        }
+    }
+
+    mod word {
+        use super::*;
+
+        fn small_list() -> WordList {
+            WordList::new(vec!["pear", "apple", "fig", "kiwi", "apple", ""])
+        }
+
+        #[test]
+        fn list_is_sorted_and_deduplicated() {
+            let list = small_list();
+            assert_eq!(list.len(), 4);
+            assert_eq!(Word::bounds(&list), (Word(String::from("apple")), Word(String::from("pear"))));
+            assert!(WordList::bundled().len() > 100);
+        }
+
+        #[test]
+        fn parse_and_compare_alphabetically() {
+            let list = small_list();
+            assert_eq!(Word::parse("Kiwi", &list), Ok(Word(String::from("kiwi"))));
+            assert_eq!(Word::parse("plum", &list), Err(ParseError::UnknownWord(String::from("plum"))));
+
+            let fig = Word(String::from("fig"));
+            let kiwi = Word(String::from("kiwi"));
+            assert_eq!(fig.compare(&kiwi, &list), Ordering::Less);
+            assert_eq!(Word(String::from("apple")).fraction(&list), 0.0);
+            assert_eq!(Word::narrow(Some(&Word(String::from("apple"))), Some(&Word(String::from("pear"))), &list), (fig, kiwi));
+        }
+
+        #[test]
+        fn random_words_come_from_the_list() {
+            let list = WordList::bundled();
+            let mut rng = new_rng(Some(5));
+            for _ in 0..50 {
+                let word = Word::random(&mut rng, &list);
+                assert_eq!(Word::parse(&word.0, &list), Ok(word));
+            }
+        }
+    }
+}
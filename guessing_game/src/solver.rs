@@ -0,0 +1,514 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - The computer player is split in two halves:
+//     * an Oracle knows the secret and answers each
+//       guess with an Ordering (guess.cmp(&secret)),
+//     * a Guesser knows only the answers so far and
+//       asks its Strategy for the next guess inside
+//       the interval the answers still allow.
+// - Either half can be swapped: GameState is the
+//   oracle for autoplay, and a person can be the
+//   oracle when the program does the guessing.
+//
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::config::Config;
+use crate::game::{new_rng, pick_secret};
+use crate::state::GameState;
+
+pub trait Oracle {
+    // Compares a guess with the secret, the same way
+    // guess.cmp(&secret) does. Interactive oracles can
+    // fail to answer, hence the io::Result.
+    //
+    fn answer(&mut self, guess: u32) -> io::Result<Ordering>;
+}
+
+impl Oracle for GameState {
+    fn answer(&mut self, guess: u32) -> io::Result<Ordering> {
+        Ok(self.guess(guess))
+    }
+}
+
+pub trait Strategy {
+    fn name(&self) -> &'static str;
+
+    // Picks a guess in low..=high (never empty)
+    //
+    fn next_guess(&mut self, low: u32, high: u32) -> u32;
+}
+
+pub struct BinarySearch;
+
+impl Strategy for BinarySearch {
+    fn name(&self) -> &'static str {
+        "binary"
+    }
+
+    fn next_guess(&mut self, low: u32, high: u32) -> u32 {
+        low + (high - low) / 2
+    }
+}
+
+pub struct RandomGuess {
+    rng: StdRng,
+}
+
+impl RandomGuess {
+    pub fn new(rng: StdRng) -> RandomGuess {
+        RandomGuess { rng }
+    }
+}
+
+impl Strategy for RandomGuess {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn next_guess(&mut self, low: u32, high: u32) -> u32 {
+        self.rng.gen_range(low..=high)
+    }
+}
+
+pub struct LinearScan;
+
+impl Strategy for LinearScan {
+    fn name(&self) -> &'static str {
+        "linear"
+    }
+
+    fn next_guess(&mut self, low: u32, _high: u32) -> u32 {
+        low
+    }
+}
+
+// Splits the interval at the golden ratio instead of
+// in half
+//
+pub struct GoldenSection;
+
+impl Strategy for GoldenSection {
+    fn name(&self) -> &'static str {
+        "golden"
+    }
+
+    fn next_guess(&mut self, low: u32, high: u32) -> u32 {
+        const RATIO: f64 = 0.381_966_011_250_105;
+        let offset = ((high - low) as f64 * RATIO).round() as u32;
+        low + offset
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrategyKind {
+    Binary,
+    Random,
+    Linear,
+    Golden,
+}
+
+impl StrategyKind {
+    pub const ALL: [StrategyKind; 4] = [
+        StrategyKind::Binary,
+        StrategyKind::Random,
+        StrategyKind::Linear,
+        StrategyKind::Golden,
+    ];
+
+    // The random strategy draws from the seeded rng so
+    // autoplay games are reproducible with --seed
+    //
+    pub fn build(self, seed: Option<u64>) -> Box<dyn Strategy> {
+        match self {
+            StrategyKind::Binary => Box::new(BinarySearch),
+            StrategyKind::Random => Box::new(RandomGuess::new(new_rng(seed))),
+            StrategyKind::Linear => Box::new(LinearScan),
+            StrategyKind::Golden => Box::new(GoldenSection),
+        }
+    }
+}
+
+impl fmt::Display for StrategyKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            StrategyKind::Binary => "binary",
+            StrategyKind::Random => "random",
+            StrategyKind::Linear => "linear",
+            StrategyKind::Golden => "golden",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for StrategyKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<StrategyKind, String> {
+        match s {
+            "binary" => Ok(StrategyKind::Binary),
+            "random" => Ok(StrategyKind::Random),
+            "linear" => Ok(StrategyKind::Linear),
+            "golden" => Ok(StrategyKind::Golden),
+            other => Err(format!(
+                "unknown strategy '{}' (expected binary, random, linear or golden)",
+                other
+            )),
+        }
+    }
+}
+
+// Keeps track of which numbers the answers still allow
+// and asks the strategy to choose among them
+//
+pub struct Guesser<S: Strategy + ?Sized> {
+    low: u32,
+    high: u32,
+    exhausted: bool,
+    strategy: Box<S>,
+}
+
+impl<S: Strategy + ?Sized> Guesser<S> {
+    pub fn new(min: u32, max: u32, strategy: Box<S>) -> Guesser<S> {
+        Guesser {
+            low: min,
+            high: max,
+            exhausted: min > max,
+            strategy,
+        }
+    }
+
+    pub fn strategy_name(&self) -> &'static str {
+        self.strategy.name()
+    }
+
+    // None once the answers rule out every number
+    //
+    pub fn interval(&self) -> Option<(u32, u32)> {
+        if self.exhausted {
+            None
+        } else {
+            Some((self.low, self.high))
+        }
+    }
+
+    pub fn next_guess(&mut self) -> Option<u32> {
+        let (low, high) = self.interval()?;
+        let guess = self.strategy.next_guess(low, high);
+        assert!(
+            low <= guess && guess <= high,
+            "{} strategy guessed {} outside {}..={}",
+            self.strategy.name(),
+            guess,
+            low,
+            high
+        );
+        Some(guess)
+    }
+
+    pub fn update(&mut self, guess: u32, answer: Ordering) {
+        match answer {
+            Ordering::Less => match guess.checked_add(1) {
+                Some(low) => self.low = self.low.max(low),
+                None => self.exhausted = true,
+            },
+            Ordering::Greater => match guess.checked_sub(1) {
+                Some(high) => self.high = self.high.min(high),
+                None => self.exhausted = true,
+            },
+            Ordering::Equal => {
+                self.low = guess;
+                self.high = guess;
+            }
+        }
+
+        if self.low > self.high {
+            self.exhausted = true;
+        }
+    }
+}
+
+pub type Transcript = Vec<(u32, Ordering)>;
+
+#[derive(Debug)]
+pub enum SolveError {
+    // The answers contradict each other: no number
+    // is consistent with all of them
+    //
+    Inconsistent(Transcript),
+    Io(io::Error),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::Inconsistent(_) => write!(f, "the answers contradict each other"),
+            SolveError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<io::Error> for SolveError {
+    fn from(e: io::Error) -> SolveError {
+        SolveError::Io(e)
+    }
+}
+
+// Guesses until the oracle says Equal
+//
+pub fn solve<S, O>(guesser: &mut Guesser<S>, oracle: &mut O) -> Result<Transcript, SolveError>
+where
+    S: Strategy + ?Sized,
+    O: Oracle + ?Sized,
+{
+    let mut transcript = Vec::new();
+
+    loop {
+        let guess = match guesser.next_guess() {
+            Some(guess) => guess,
+            None => return Err(SolveError::Inconsistent(transcript)),
+        };
+
+        let answer = oracle.answer(guess)?;
+        guesser.update(guess, answer);
+        transcript.push((guess, answer));
+
+        if answer == Ordering::Equal {
+            return Ok(transcript);
+        }
+    }
+}
+
+pub fn autoplay<W: Write>(config: &Config, kind: StrategyKind, output: &mut W) -> io::Result<Transcript> {
+    let mut rng = new_rng(config.seed);
+    let secret = pick_secret(&mut rng, config);
+    let mut oracle = GameState::new(config.min..=config.max, secret);
+    // The strategy's seed comes after the secret, so a random
+    // strategy doesn't just repeat the draw that picked it
+    //
+    let mut guesser = Guesser::new(config.min, config.max, kind.build(Some(rng.gen())));
+
+    writeln!(output, "Guess the number between {} and {}!", config.min, config.max)?;
+    writeln!(output, "The computer plays using the {} strategy.", guesser.strategy_name())?;
+
+    let transcript = match solve(&mut guesser, &mut oracle) {
+        Ok(transcript) => transcript,
+        Err(SolveError::Io(e)) => return Err(e),
+        Err(SolveError::Inconsistent(_)) => unreachable!("the game state never lies"),
+    };
+
+    for (guess, answer) in &transcript {
+        let reply = match answer {
+            Ordering::Less => "Too small!",
+            Ordering::Greater => "Too big!",
+            Ordering::Equal => "You win!",
+        };
+        writeln!(output, "Computer guessed: {} ... {}", guess, reply)?;
+    }
+    let noun = if transcript.len() == 1 { "guess" } else { "guesses" };
+    writeln!(output, "Found {} in {} {}.", secret, transcript.len(), noun)?;
+
+    Ok(transcript)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchResult {
+    pub strategy: StrategyKind,
+    pub games: u32,
+    pub mean: f64,
+    pub max: usize,
+}
+
+// Plays `games` games with every strategy. Each strategy
+// sees the same sequence of secrets, even without a seed.
+//
+pub fn bench(config: &Config, games: u32) -> Vec<BenchResult> {
+    bench_games(config, games)
+        .into_iter()
+        .map(|(kind, played)| {
+            let total: usize = played.iter().map(|&(_, guesses)| guesses).sum();
+            BenchResult {
+                strategy: kind,
+                games,
+                mean: if games == 0 { 0.0 } else { total as f64 / games as f64 },
+                max: played.iter().map(|&(_, guesses)| guesses).max().unwrap_or(0),
+            }
+        })
+        .collect()
+}
+
+// The secret and the number of guesses for every game each
+// strategy played. Without a seed one is drawn here, once,
+// so that every strategy's secrets come from the same rng.
+//
+fn bench_games(config: &Config, games: u32) -> Vec<(StrategyKind, Vec<(u32, usize)>)> {
+    let seed = config.seed.unwrap_or_else(rand::random);
+    StrategyKind::ALL
+        .iter()
+        .map(|&kind| {
+            let mut secrets = new_rng(Some(seed));
+            let mut strategy_seed = new_rng(Some(seed));
+            let played = (0..games)
+                .map(|_| {
+                    let secret = pick_secret(&mut secrets, config);
+                    let mut oracle = GameState::new(config.min..=config.max, secret);
+                    let mut guesser = Guesser::new(config.min, config.max, kind.build(Some(strategy_seed.gen())));
+                    let guesses = match solve(&mut guesser, &mut oracle) {
+                        Ok(transcript) => transcript.len(),
+                        Err(e) => unreachable!("the game state never lies or fails: {}", e),
+                    };
+                    (secret, guesses)
+                })
+                .collect();
+            (kind, played)
+        })
+        .collect()
+}
+
+pub fn print_bench<W: Write>(config: &Config, results: &[BenchResult], output: &mut W) -> io::Result<()> {
+    writeln!(output, "Range {}-{}", config.min, config.max)?;
+    writeln!(output, "Strategy  Games    Mean  Max")?;
+    for result in results {
+        writeln!(
+            output,
+            "{:<8}  {:>5}  {:>6.2}  {:>3}",
+            result.strategy.to_string(),
+            result.games,
+            result.mean,
+            result.max
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An oracle that replays fixed answers, whatever
+    // the guess
+    //
+    struct Scripted(Vec<Ordering>);
+
+    impl Oracle for Scripted {
+        fn answer(&mut self, _guess: u32) -> io::Result<Ordering> {
+            Ok(self.0.remove(0))
+        }
+    }
+
+    fn guesses_for(kind: StrategyKind, min: u32, max: u32, secret: u32) -> usize {
+        let mut guesser = Guesser::new(min, max, kind.build(Some(7)));
+        let mut oracle = GameState::new(min..=max, secret);
+        solve(&mut guesser, &mut oracle).unwrap().len()
+    }
+
+    #[test]
+    fn every_strategy_finds_every_secret() {
+        for &kind in StrategyKind::ALL.iter() {
+            for secret in 1..=100 {
+                let mut guesser = Guesser::new(1, 100, kind.build(Some(secret as u64)));
+                let mut oracle = GameState::new(1..=100, secret);
+                let transcript = solve(&mut guesser, &mut oracle).unwrap();
+                assert_eq!(transcript.last(), Some(&(secret, Ordering::Equal)));
+            }
+        }
+    }
+
+    #[test]
+    fn binary_search_is_optimal() {
+        for secret in 1..=100 {
+            let guesses = guesses_for(StrategyKind::Binary, 1, 100, secret);
+            assert_eq!(guesses as u32, crate::state::optimal_guesses(1, 100, secret));
+        }
+    }
+
+    #[test]
+    fn linear_scan_counts_up() {
+        assert_eq!(guesses_for(StrategyKind::Linear, 1, 100, 1), 1);
+        assert_eq!(guesses_for(StrategyKind::Linear, 1, 100, 37), 37);
+    }
+
+    #[test]
+    fn works_at_the_edges_of_u32() {
+        for &kind in [StrategyKind::Binary, StrategyKind::Golden, StrategyKind::Random].iter() {
+            assert!(guesses_for(kind, 0, u32::MAX, 0) <= 64);
+            assert!(guesses_for(kind, 0, u32::MAX, u32::MAX) <= 64);
+        }
+    }
+
+    #[test]
+    fn contradicting_answers_are_detected() {
+        let mut guesser = Guesser::new(1, 10, Box::new(BinarySearch));
+        // 5 is too small, 8 too big, 6 too small and 7 too small
+        // leaves nothing
+        //
+        let mut oracle = Scripted(vec![Ordering::Less, Ordering::Greater, Ordering::Less, Ordering::Less]);
+
+        match solve(&mut guesser, &mut oracle) {
+            Err(SolveError::Inconsistent(transcript)) => assert_eq!(transcript.len(), 4),
+            other => panic!("expected inconsistency, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn autoplay_is_reproducible() {
+        let config = Config {
+            seed: Some(99),
+            ..Config::default()
+        };
+        let mut first = Vec::new();
+        let mut second = Vec::new();
+        autoplay(&config, StrategyKind::Random, &mut first).unwrap();
+        autoplay(&config, StrategyKind::Random, &mut second).unwrap();
+        assert_eq!(first, second);
+
+        // ...without the random strategy guessing the secret
+        // straight away, as it would if it shared its seed
+        //
+        for &seed in &[1, 2, 3, 7, 42, 99, 123] {
+            let config = Config {
+                seed: Some(seed),
+                ..Config::default()
+            };
+            let transcript = autoplay(&config, StrategyKind::Random, &mut Vec::new()).unwrap();
+            assert!(transcript.len() > 1, "seed {} found the secret first time", seed);
+        }
+    }
+
+    #[test]
+    fn bench_reports_every_strategy() {
+        let config = Config {
+            seed: Some(1),
+            ..Config::default()
+        };
+        let results = bench(&config, 200);
+
+        assert_eq!(results.len(), StrategyKind::ALL.len());
+        let binary = &results[0];
+        assert_eq!(binary.strategy, StrategyKind::Binary);
+        assert!(binary.max <= 7);
+        assert!(results.iter().all(|r| r.mean >= 1.0 && r.games == 200));
+
+        let linear = &results[2];
+        assert!(linear.mean > binary.mean);
+    }
+
+    #[test]
+    fn unseeded_strategies_see_the_same_secrets() {
+        let played = bench_games(&Config::default(), 50);
+        let secrets: Vec<Vec<u32>> = played
+            .iter()
+            .map(|(_, games)| games.iter().map(|&(secret, _)| secret).collect())
+            .collect();
+
+        assert_eq!(secrets.len(), StrategyKind::ALL.len());
+        assert!(secrets.iter().all(|s| *s == secrets[0]));
+    }
+}
@@ -0,0 +1,240 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - GameState is the guess/compare logic without any
+//   I/O, so every frontend (and the tests) can share it.
+//   It works for any Secret type; u32 is the default.
+// - Each answer narrows the feasible interval: after
+//   "Too small!" for 42 we know the secret is above 42,
+//   so guessing 40 next can't possibly win.
+//
+
+use std::cmp::Ordering;
+
+use crate::secret::Secret;
+
+// Why a guess can't be the secret, given earlier answers
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Warning<S = u32> {
+    // The secret is known to be above this guess
+    //
+    Above(S),
+    // The secret is known to be below this guess
+    //
+    Below(S),
+}
+
+#[derive(Debug, Clone)]
+pub struct GameState<S: Secret = u32> {
+    domain: S::Domain,
+    secret: S,
+    known_above: Option<S>,
+    known_below: Option<S>,
+    history: Vec<(S, Ordering)>,
+}
+
+impl<S: Secret> GameState<S> {
+    pub fn new(domain: S::Domain, secret: S) -> GameState<S> {
+        GameState {
+            domain,
+            secret,
+            known_above: None,
+            known_below: None,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn secret(&self) -> &S {
+        &self.secret
+    }
+
+    pub fn domain(&self) -> &S::Domain {
+        &self.domain
+    }
+
+    pub fn range(&self) -> (S, S) {
+        S::bounds(&self.domain)
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.history.len() as u32
+    }
+
+    pub fn history(&self) -> &[(S, Ordering)] {
+        &self.history
+    }
+
+    pub fn is_won(&self) -> bool {
+        matches!(self.history.last(), Some((_, Ordering::Equal)))
+    }
+
+    // Inclusive bounds the secret must lie in, given
+    // every answer so far
+    //
+    pub fn feasible(&self) -> (S, S) {
+        S::narrow(self.known_above.as_ref(), self.known_below.as_ref(), &self.domain)
+    }
+
+    // Checks a guess against what is already known,
+    // without recording it. A guess can still win if it
+    // would win against some secret in feasible(), which
+    // for floats means anywhere within the tolerance of it.
+    //
+    pub fn check(&self, guess: &S) -> Option<Warning<S>> {
+        let (low, high) = self.feasible();
+        if let Some(above) = &self.known_above {
+            if guess.compare(&low, &self.domain) == Ordering::Less {
+                return Some(Warning::Above(above.clone()));
+            }
+        }
+        if let Some(below) = &self.known_below {
+            if guess.compare(&high, &self.domain) == Ordering::Greater {
+                return Some(Warning::Below(below.clone()));
+            }
+        }
+        None
+    }
+
+    // Records a guess and returns how it compares to
+    // the secret
+    //
+    pub fn guess(&mut self, guess: S) -> Ordering {
+        let ordering = guess.compare(&self.secret, &self.domain);
+
+        match ordering {
+            Ordering::Less => {
+                let closer = match &self.known_above {
+                    Some(above) => guess.compare(above, &self.domain) == Ordering::Greater,
+                    None => true,
+                };
+                if closer {
+                    self.known_above = Some(guess.clone());
+                }
+            }
+            Ordering::Greater => {
+                let closer = match &self.known_below {
+                    Some(below) => guess.compare(below, &self.domain) == Ordering::Less,
+                    None => true,
+                };
+                if closer {
+                    self.known_below = Some(guess.clone());
+                }
+            }
+            Ordering::Equal => {}
+        }
+
+        self.history.push((guess, ordering));
+        ordering
+    }
+
+    pub fn optimal_guesses(&self) -> u32 {
+        S::optimal_guesses(&self.secret, &self.domain)
+    }
+}
+
+// How many guesses a binary search over min..=max
+// takes to find the secret
+//
+pub fn optimal_guesses(min: u32, max: u32, secret: u32) -> u32 {
+    debug_assert!(min <= secret && secret <= max);
+
+    let (mut low, mut high) = (min, max);
+    let mut guesses = 1;
+
+    loop {
+        let middle = low + (high - low) / 2;
+        match middle.cmp(&secret) {
+            Ordering::Less => low = middle + 1,
+            Ordering::Greater => high = middle - 1,
+            Ordering::Equal => return guesses,
+        }
+        guesses += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secret::{Float, FloatRange};
+
+    #[test]
+    fn answers_narrow_the_interval() {
+        let mut state = GameState::new(1..=100, 60);
+        assert_eq!(state.feasible(), (1, 100));
+
+        assert_eq!(state.guess(42), Ordering::Less);
+        assert_eq!(state.feasible(), (43, 100));
+
+        assert_eq!(state.guess(75), Ordering::Greater);
+        assert_eq!(state.feasible(), (43, 74));
+
+        // A worse guess doesn't widen what we already know
+        //
+        assert_eq!(state.guess(10), Ordering::Less);
+        assert_eq!(state.feasible(), (43, 74));
+
+        assert_eq!(state.guess(60), Ordering::Equal);
+        assert!(state.is_won());
+        assert_eq!(state.attempts(), 4);
+    }
+
+    #[test]
+    fn warns_about_guesses_outside_the_interval() {
+        let mut state = GameState::new(1..=100, 60);
+        assert_eq!(state.check(&5), None);
+
+        state.guess(42);
+        state.guess(75);
+
+        assert_eq!(state.check(&42), Some(Warning::Above(42)));
+        assert_eq!(state.check(&7), Some(Warning::Above(42)));
+        assert_eq!(state.check(&80), Some(Warning::Below(75)));
+        assert_eq!(state.check(&43), None);
+        assert_eq!(state.check(&74), None);
+    }
+
+    #[test]
+    fn float_warnings_allow_for_the_tolerance() {
+        let domain = FloatRange {
+            min: 0.0,
+            max: 10.0,
+            tolerance: 0.25,
+        };
+        let mut state = GameState::new(domain, Float(2.3));
+        assert_eq!(state.guess(Float(2.0)), Ordering::Less);
+
+        // The secret is above 2.25, so 2.1 still wins if
+        // it's no more than 2.35
+        //
+        assert_eq!(state.check(&Float(2.1)), None);
+        assert_eq!(state.check(&Float(1.9)), Some(Warning::Above(Float(2.0))));
+
+        assert_eq!(state.guess(Float(3.0)), Ordering::Greater);
+        assert_eq!(state.check(&Float(2.9)), None);
+        assert_eq!(state.check(&Float(3.1)), Some(Warning::Below(Float(3.0))));
+    }
+
+    #[test]
+    fn interval_at_the_edges_of_u32() {
+        let mut state = GameState::new(0..=u32::MAX, u32::MAX);
+        state.guess(0);
+        assert_eq!(state.feasible(), (1, u32::MAX));
+
+        let mut state = GameState::new(0..=u32::MAX, 0);
+        state.guess(u32::MAX);
+        assert_eq!(state.feasible(), (0, u32::MAX - 1));
+    }
+
+    #[test]
+    fn optimal_play_is_logarithmic() {
+        assert_eq!(optimal_guesses(1, 100, 50), 1);
+        assert_eq!(optimal_guesses(1, 100, 25), 2);
+        assert_eq!(optimal_guesses(5, 5, 5), 1);
+        assert_eq!(optimal_guesses(0, u32::MAX, 0), 32);
+
+        for secret in 1..=100 {
+            assert!(optimal_guesses(1, 100, secret) <= 7);
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - `--tournament <N>` plays a best-of-N series: winning
+//   a majority of the N games takes it, so the series
+//   stops as soon as the result is certain.
+// - Every game is an ordinary game with its own seed
+//   (the series seed plus the game's number), so each
+//   one is logged and can be replayed on its own.
+// - A win scores one point plus one for every attempt
+//   left over. A loss scores nothing, and quitting
+//   abandons the whole series.
+//
+
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+use crate::clock::Clock;
+use crate::config::Config;
+use crate::events::EventLog;
+use crate::game::{self, Outcome};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+    pub outcome: Outcome,
+    pub time: Duration,
+    pub points: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Standing {
+    Undecided,
+    Won,
+    Lost,
+    Abandoned,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tournament {
+    games: u32,
+    rounds: Vec<Round>,
+}
+
+impl Tournament {
+    pub fn new(games: u32) -> Tournament {
+        Tournament {
+            games,
+            rounds: Vec::new(),
+        }
+    }
+
+    pub fn rounds(&self) -> &[Round] {
+        &self.rounds
+    }
+
+    pub fn add(&mut self, round: Round) {
+        self.rounds.push(round);
+    }
+
+    // Wins needed to take the series
+    //
+    pub fn needed(&self) -> u32 {
+        self.games / 2 + 1
+    }
+
+    pub fn wins(&self) -> u32 {
+        self.count(|outcome| matches!(outcome, Outcome::Won { .. }))
+    }
+
+    pub fn losses(&self) -> u32 {
+        self.count(|outcome| matches!(outcome, Outcome::Lost { .. }))
+    }
+
+    pub fn points(&self) -> u32 {
+        self.rounds.iter().map(|round| round.points).sum()
+    }
+
+    pub fn standing(&self) -> Standing {
+        if self.count(|outcome| matches!(outcome, Outcome::Quit { .. })) > 0 {
+            Standing::Abandoned
+        } else if self.wins() >= self.needed() {
+            Standing::Won
+        } else if self.losses() > self.games - self.needed() {
+            Standing::Lost
+        } else {
+            Standing::Undecided
+        }
+    }
+
+    fn count<F: Fn(&Outcome) -> bool>(&self, f: F) -> u32 {
+        self.rounds.iter().filter(|round| f(&round.outcome)).count() as u32
+    }
+}
+
+pub fn points(config: &Config, outcome: &Outcome) -> u32 {
+    match outcome {
+        Outcome::Won { attempts, .. } => config.max_attempts - attempts + 1,
+        _ => 0,
+    }
+}
+
+pub fn run<R, W>(
+    config: &Config,
+    clock: &dyn Clock,
+    mut log: EventLog,
+    input: &mut R,
+    output: &mut W,
+) -> io::Result<Tournament>
+where
+    R: BufRead,
+    W: Write,
+{
+    let games = config.tournament.unwrap_or(1);
+    let seed = game::pick_seed(config);
+    let mut tournament = Tournament::new(games);
+
+    while tournament.standing() == Standing::Undecided {
+        let number = tournament.rounds().len() as u64 + 1;
+        writeln!(
+            output,
+            "Game {} of a best of {} ({} wins needed).",
+            number,
+            games,
+            tournament.needed()
+        )?;
+
+        let game_config = Config {
+            seed: Some(seed.wrapping_add(number - 1)),
+            ..config.clone()
+        };
+        let started = clock.now();
+        let outcome = game::run_logged(&game_config, clock, log.reborrow(), input, output)?;
+
+        tournament.add(Round {
+            points: points(config, &outcome),
+            time: clock.now().saturating_sub(started),
+            outcome,
+        });
+        writeln!(
+            output,
+            "So far: {} won, {} lost, {} points.",
+            tournament.wins(),
+            tournament.losses(),
+            tournament.points()
+        )?;
+    }
+
+    print_summary(&tournament, output)?;
+    Ok(tournament)
+}
+
+pub fn print_summary<W: Write>(tournament: &Tournament, output: &mut W) -> io::Result<()> {
+    writeln!(output, "Game  Result  Guesses  Time     Points")?;
+    for (i, round) in tournament.rounds().iter().enumerate() {
+        let (result, guesses) = match &round.outcome {
+            Outcome::Won { attempts, .. } => ("won", attempts.to_string()),
+            Outcome::Lost { .. } => ("lost", String::from("-")),
+            Outcome::Quit { .. } => ("quit", String::from("-")),
+        };
+        writeln!(
+            output,
+            "{:>4}  {:<6}  {:>7}  {:>6.1}s  {:>6}",
+            i + 1,
+            result,
+            guesses,
+            round.time.as_secs_f64(),
+            round.points
+        )?;
+    }
+
+    writeln!(
+        output,
+        "Total: {} won, {} lost, {} points.",
+        tournament.wins(),
+        tournament.losses(),
+        tournament.points()
+    )?;
+
+    match tournament.standing() {
+        Standing::Won => writeln!(output, "You win the tournament!"),
+        Standing::Lost => writeln!(output, "You lose the tournament."),
+        Standing::Abandoned => writeln!(output, "Tournament abandoned."),
+        Standing::Undecided => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::game::Session;
+    use std::io::Cursor;
+
+    fn won(attempts: u32) -> Round {
+        let outcome = Outcome::Won {
+            attempts,
+            elapsed: Duration::from_secs(1),
+        };
+        Round {
+            points: points(&Config::default(), &outcome),
+            time: Duration::from_secs(1),
+            outcome,
+        }
+    }
+
+    fn lost() -> Round {
+        Round {
+            outcome: Outcome::Lost {
+                secret: String::from("7"),
+            },
+            time: Duration::from_secs(2),
+            points: 0,
+        }
+    }
+
+    #[test]
+    fn majority_decides_the_series() {
+        let mut tournament = Tournament::new(5);
+        assert_eq!(tournament.needed(), 3);
+
+        tournament.add(won(3));
+        tournament.add(lost());
+        tournament.add(won(7));
+        assert_eq!(tournament.standing(), Standing::Undecided);
+        tournament.add(won(1));
+        assert_eq!(tournament.standing(), Standing::Won);
+        assert_eq!(tournament.points(), 5 + 1 + 7);
+    }
+
+    #[test]
+    fn too_many_losses_end_it_early() {
+        let mut tournament = Tournament::new(3);
+        tournament.add(lost());
+        assert_eq!(tournament.standing(), Standing::Undecided);
+        tournament.add(lost());
+        assert_eq!(tournament.standing(), Standing::Lost);
+
+        let mut even = Tournament::new(4);
+        even.add(won(1));
+        even.add(won(1));
+        even.add(lost());
+        assert_eq!(even.standing(), Standing::Undecided);
+        even.add(lost());
+        assert_eq!(even.standing(), Standing::Lost);
+    }
+
+    #[test]
+    fn scripted_series_with_summary() {
+        let config = Config {
+            seed: Some(11),
+            tournament: Some(3),
+            ..Config::default()
+        };
+        let domain = config.min..=config.max;
+        let secret = |n: u64| *Session::<u32>::new(&config, &domain, 11 + n).state().secret();
+
+        // Game 1 in one guess, game 2 with a wrong guess first
+        //
+        let wrong = if secret(1) == 1 { 2 } else { 1 };
+        let script = format!("{}\n{}\n{}\n", secret(0), wrong, secret(1));
+        let clock = ManualClock::default();
+        let mut output = Vec::new();
+        let tournament = run(&config, &clock, EventLog::disabled(), &mut Cursor::new(script), &mut output).unwrap();
+
+        assert_eq!(tournament.standing(), Standing::Won);
+        assert_eq!(tournament.rounds().len(), 2);
+        assert_eq!(tournament.points(), 7 + 6);
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Game 1 of a best of 3 (2 wins needed)."));
+        assert!(output.contains("So far: 1 won, 0 lost, 7 points."));
+        assert!(output.contains("   2  won           2     0.0s       6"));
+        assert!(output.ends_with("Total: 2 won, 0 lost, 13 points.\nYou win the tournament!\n"));
+    }
+
+    #[test]
+    fn quitting_abandons_the_series() {
+        let config = Config {
+            tournament: Some(5),
+            ..Config::default()
+        };
+        let mut output = Vec::new();
+        let tournament = run(&config, &ManualClock::default(), EventLog::disabled(), &mut Cursor::new("quit\n"), &mut output).unwrap();
+
+        assert_eq!(tournament.standing(), Standing::Abandoned);
+        assert!(String::from_utf8(output).unwrap().ends_with("Tournament abandoned.\n"));
+    }
+}
@@ -0,0 +1,462 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - A full screen frontend for the same Session the
+//   plain mode uses: an input box, the guesses so far
+//   with ↑/↓ markers, and a bar showing how much of the
+//   range the secret can still be in.
+// - main only starts it when stdin and stdout are both
+//   terminals. Pipes, redirects and the tests get the
+//   plain line mode from game.rs instead.
+// - Screen::render builds the screen as plain lines of
+//   text, so the layout can be tested without a terminal;
+//   draw() is the only part that talks to crossterm.
+//
+
+use std::cmp::Ordering;
+use std::io::{self, IsTerminal, Write};
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::Print;
+use crossterm::terminal::{self, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, execute, queue};
+
+use crate::clock::Clock;
+use crate::config::Config;
+use crate::events::EventLog;
+use crate::game::{self, Outcome, Session};
+use crate::input::{self, Input};
+use crate::secret::{Float, Secret, SecretType, Word, WordList};
+
+// Rows used by everything except the list of guesses
+//
+const FIXED_ROWS: usize = 11;
+
+// How often a countdown is redrawn
+//
+const TICK: Duration = Duration::from_millis(250);
+
+pub fn is_available() -> bool {
+    io::stdin().is_terminal() && io::stdout().is_terminal()
+}
+
+// Same as game::run_logged, but on the whole terminal
+//
+pub fn run(config: &Config, clock: &dyn Clock, log: EventLog) -> io::Result<Outcome> {
+    let seed = game::pick_seed(config);
+
+    match config.secret_type {
+        SecretType::Integer => {
+            let domain = config.min..=config.max;
+            play(Session::<u32>::new(config, &domain, seed).with_clock(clock).with_log(log))
+        }
+        SecretType::Float => {
+            let domain = game::float_range(config);
+            play(Session::<Float>::new(config, &domain, seed).with_clock(clock).with_log(log))
+        }
+        SecretType::Letter => {
+            let domain = 'a'..='z';
+            play(Session::<char>::new(config, &domain, seed).with_clock(clock).with_log(log))
+        }
+        SecretType::Word => {
+            let domain = WordList::bundled();
+            play(Session::<Word>::new(config, &domain, seed).with_clock(clock).with_log(log))
+        }
+    }
+}
+
+// Puts the terminal back when it's dropped: however the
+// game ends, even by panicking, the shell isn't left in
+// raw mode on the alternate screen
+//
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter(stdout: &mut io::Stdout) -> io::Result<RawTerminal> {
+        terminal::enable_raw_mode()?;
+        let guard = RawTerminal;
+        execute!(stdout, EnterAlternateScreen, cursor::Hide)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn play<S: Secret>(session: Session<S>) -> io::Result<Outcome> {
+    let mut stdout = io::stdout();
+
+    let terminal = RawTerminal::enter(&mut stdout)?;
+    let result = event_loop(session, &mut stdout);
+    drop(terminal);
+
+    // The alternate screen is gone now, so repeat how the
+    // game ended where the player can still see it
+    //
+    let (outcome, messages) = result?;
+    for message in messages {
+        println!("{}", message);
+    }
+    Ok(outcome)
+}
+
+fn event_loop<S: Secret>(mut session: Session<S>, stdout: &mut io::Stdout) -> io::Result<(Outcome, Vec<String>)> {
+    let config = session.config();
+    let domain = session.domain();
+    let title = format!(
+        "{}  Difficulty: {} ({} attempts)",
+        S::describe(domain),
+        config.difficulty,
+        config.max_attempts
+    );
+    let mut screen = Screen::new(title);
+
+    let mut said = Vec::new();
+    session.start(&mut said)?;
+    screen.show(&said);
+
+    let outcome = loop {
+        draw(stdout, &screen.render(&session, size()?))?;
+
+        // With a countdown the screen is redrawn every so
+        // often even if nothing is typed, so the time left
+        // keeps moving and the game ends on time
+        //
+        if session.time_left().is_some() && !event::poll(TICK)? {
+            let mut said = Vec::new();
+            if let Some(outcome) = session.check_time(&mut said)? {
+                screen.show(&said);
+                break outcome;
+            }
+            continue;
+        }
+
+        let key = match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            _ => continue,
+        };
+
+        let command = match screen.key(key, session.state().attempts() as usize) {
+            Some(Action::Submit(line)) => match input::parse::<S>(&line, domain) {
+                Ok(command) => command,
+                Err(e) => {
+                    screen.show(e.to_string().as_bytes());
+                    continue;
+                }
+            },
+            Some(Action::Quit) => Input::Quit,
+            None => continue,
+        };
+
+        let mut said = Vec::new();
+        let outcome = session.handle(command, &mut said)?;
+        screen.show(&said);
+        if let Some(outcome) = outcome {
+            break outcome;
+        }
+    };
+
+    screen.input = String::from("(press any key to leave)");
+    draw(stdout, &screen.render(&session, size()?))?;
+    loop {
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                break;
+            }
+        }
+    }
+
+    Ok((outcome, screen.messages))
+}
+
+fn size() -> io::Result<(usize, usize)> {
+    let (width, height) = terminal::size()?;
+    Ok((width as usize, height as usize))
+}
+
+fn draw<W: Write>(output: &mut W, lines: &[String]) -> io::Result<()> {
+    queue!(output, terminal::Clear(ClearType::All))?;
+    for (row, line) in lines.iter().enumerate() {
+        queue!(output, cursor::MoveTo(0, row as u16), Print(line))?;
+    }
+    output.flush()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Submit(String),
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Screen {
+    title: String,
+    input: String,
+    // Whatever the game said about the last thing typed
+    //
+    messages: Vec<String>,
+    // How many guesses the list is scrolled back from
+    // the newest one
+    //
+    scroll: usize,
+}
+
+impl Screen {
+    pub fn new(title: String) -> Screen {
+        Screen {
+            title,
+            input: String::new(),
+            messages: Vec::new(),
+            scroll: 0,
+        }
+    }
+
+    // Replaces the messages with the lines of `said`, as
+    // written by Session::handle
+    //
+    pub fn show(&mut self, said: &[u8]) {
+        self.messages = String::from_utf8_lossy(said).lines().map(String::from).collect();
+        self.scroll = 0;
+    }
+
+    // Edits the input box. Returns what the player asked
+    // for once they press Enter or give up.
+    //
+    pub fn key(&mut self, key: KeyEvent, guesses: usize) -> Option<Action> {
+        match key.code {
+            KeyCode::Char('c') | KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                Some(Action::Quit)
+            }
+            KeyCode::Esc => Some(Action::Quit),
+            KeyCode::Enter => Some(Action::Submit(std::mem::take(&mut self.input))),
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                None
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                None
+            }
+            KeyCode::Up => {
+                self.scroll = (self.scroll + 1).min(guesses.saturating_sub(1));
+                None
+            }
+            KeyCode::Down => {
+                self.scroll = self.scroll.saturating_sub(1);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    pub fn render<S: Secret>(&self, session: &Session<S>, size: (usize, usize)) -> Vec<String> {
+        let (width, height) = size;
+        let state = session.state();
+        let left = match session.time_left() {
+            Some(time) => format!("{} attempts and {}s left", session.attempts_left(), time.as_secs()),
+            None => format!("{} attempts left", session.attempts_left()),
+        };
+        let (low, high) = state.feasible();
+
+        let mut lines = vec![
+            self.title.clone(),
+            String::new(),
+            format!("The secret is between {} and {}.", low, high),
+            interval_bar(low.fraction(state.domain()), high.fraction(state.domain()), width),
+            String::new(),
+            format!("Guesses ({}, ↑ go higher, ↓ go lower):", left),
+        ];
+
+        // Newest guess at the bottom, scrolled back by
+        // self.scroll
+        //
+        let rows = height.saturating_sub(FIXED_ROWS).max(1);
+        let history = state.history();
+        let end = history.len() - self.scroll.min(history.len());
+        let start = end.saturating_sub(rows);
+        for (i, (guess, ordering)) in history.iter().enumerate().take(end).skip(start) {
+            let marker = match ordering {
+                Ordering::Less => "↑",
+                Ordering::Greater => "↓",
+                Ordering::Equal => "✓",
+            };
+            lines.push(format!("{:>4}. {:<12} {}", i + 1, guess.to_string(), marker));
+        }
+        if history.is_empty() {
+            lines.push(String::from("      none yet"));
+        }
+        while lines.len() < 6 + rows {
+            lines.push(String::new());
+        }
+
+        // The last two things the game said
+        //
+        lines.push(String::new());
+        let shown = self.messages.len().saturating_sub(2);
+        for i in 0..2 {
+            lines.push(self.messages.get(shown + i).cloned().unwrap_or_default());
+        }
+        lines.push(String::from("Enter: guess  Esc: quit  ↑/↓: scroll  Also: hint, history, restart"));
+        lines.push(format!("> {}", self.input));
+
+        lines
+            .into_iter()
+            .map(|line| line.chars().take(width).collect())
+            .collect()
+    }
+}
+
+// `width` cells between brackets; the cells from `low`
+// to `high` (fractions of the whole range) are filled.
+// At least one cell stays filled so the secret never
+// seems to have vanished.
+//
+pub fn interval_bar(low: f64, high: f64, width: usize) -> String {
+    let cells = width.saturating_sub(2);
+    let start = ((low * cells as f64).floor() as usize).min(cells.saturating_sub(1));
+    let end = ((high * cells as f64).ceil() as usize).clamp(start + 1, cells.max(1));
+
+    let bar: String = (0..cells)
+        .map(|i| if i >= start && i < end { '█' } else { '░' })
+        .collect();
+    format!("[{}]", bar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn type_line(screen: &mut Screen, line: &str) -> Option<Action> {
+        for c in line.chars() {
+            assert_eq!(screen.key(key(KeyCode::Char(c)), 0), None);
+        }
+        screen.key(key(KeyCode::Enter), 0)
+    }
+
+    #[test]
+    fn bar_narrows_with_the_interval() {
+        assert_eq!(interval_bar(0.0, 1.0, 12), "[██████████]");
+        assert_eq!(interval_bar(0.5, 1.0, 12), "[░░░░░█████]");
+        assert_eq!(interval_bar(0.0, 0.3, 12), "[███░░░░░░░]");
+        assert_eq!(interval_bar(0.42, 0.42, 12), "[░░░░█░░░░░]");
+        assert_eq!(interval_bar(1.0, 1.0, 12), "[░░░░░░░░░█]");
+    }
+
+    #[test]
+    fn typing_and_submitting() {
+        let mut screen = Screen::new(String::new());
+        assert_eq!(type_line(&mut screen, "42"), Some(Action::Submit(String::from("42"))));
+        assert_eq!(screen.input, "");
+
+        screen.key(key(KeyCode::Char('7')), 0);
+        screen.key(key(KeyCode::Char('8')), 0);
+        screen.key(key(KeyCode::Backspace), 0);
+        assert_eq!(screen.input, "7");
+
+        assert_eq!(screen.key(key(KeyCode::Esc), 0), Some(Action::Quit));
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(screen.key(ctrl_c, 0), Some(Action::Quit));
+    }
+
+    fn guess<S: Secret>(session: &mut Session<S>, guess: S) {
+        session.handle(Input::Guess(guess), &mut io::sink()).unwrap();
+    }
+
+    #[test]
+    fn render_shows_markers_interval_and_messages() {
+        let config = Config::default();
+        let domain = 1..=100;
+        let mut session = Session::<u32>::new(&config, &domain, 42);
+        session.start(&mut io::sink()).unwrap();
+        let secret = *session.state().secret();
+        assert!(secret > 1 && secret < 100, "pick a seed with an interior secret");
+
+        guess(&mut session, secret - 1);
+        guess(&mut session, secret + 1);
+        guess(&mut session, secret);
+
+        let mut screen = Screen::new(String::from("Guess the number between 1 and 100!"));
+        screen.show(format!("You guessed: {}\nYou win!\n", secret).as_bytes());
+        let lines = screen.render(&session, (80, 20));
+
+        assert_eq!(lines.len(), 20);
+        assert_eq!(lines[0], "Guess the number between 1 and 100!");
+        assert_eq!(lines[2], format!("The secret is between {} and {}.", secret, secret));
+        assert!(lines[3].contains('░') && lines[3].contains('█'));
+        assert!(lines[5].starts_with("Guesses (4 attempts left"));
+        assert_eq!(lines[6], format!("   1. {:<12} ↑", secret - 1));
+        assert_eq!(lines[7], format!("   2. {:<12} ↓", secret + 1));
+        assert_eq!(lines[8], format!("   3. {:<12} ✓", secret));
+        assert_eq!(lines[16], format!("You guessed: {}", secret));
+        assert_eq!(lines[17], "You win!");
+        assert_eq!(lines[19], "> ");
+    }
+
+    #[test]
+    fn countdown_is_shown() {
+        let config = Config {
+            time_limit: Some(Duration::from_secs(30)),
+            ..Config::default()
+        };
+        let domain = 1..=100;
+        let clock = ManualClock::default();
+        let mut session = Session::<u32>::new(&config, &domain, 42).with_clock(&clock);
+        session.start(&mut io::sink()).unwrap();
+        clock.advance(Duration::from_millis(12_300));
+
+        let lines = Screen::new(String::new()).render(&session, (80, 20));
+        assert!(lines[5].starts_with("Guesses (7 attempts and 17s left"), "got {}", lines[5]);
+    }
+
+    #[test]
+    fn long_histories_scroll() {
+        let config = Config {
+            max_attempts: 30,
+            ..Config::default()
+        };
+        let domain = 50..=1000;
+        let mut session = Session::<u32>::new(&config, &domain, 42);
+        for n in 1..=20 {
+            guess(&mut session, n);
+        }
+
+        // 15 rows leave room for 4 guesses
+        //
+        let mut screen = Screen::new(String::new());
+        let newest = screen.render(&session, (40, 15));
+        assert!(newest[6].starts_with("  17."));
+        assert!(newest[9].starts_with("  20."));
+
+        screen.key(key(KeyCode::Up), 20);
+        screen.key(key(KeyCode::Up), 20);
+        let older = screen.render(&session, (40, 15));
+        assert!(older[6].starts_with("  15."));
+        assert!(older[9].starts_with("  18."));
+
+        for _ in 0..50 {
+            screen.key(key(KeyCode::Up), 20);
+        }
+        assert!(screen.render(&session, (40, 15))[6].starts_with("   1."));
+    }
+
+    #[test]
+    fn lines_fit_the_width() {
+        let config = Config::default();
+        let domain = 1..=100;
+        let session = Session::<u32>::new(&config, &domain, 5);
+        let screen = Screen::new("x".repeat(200));
+        for line in screen.render(&session, (30, 12)) {
+            assert!(line.chars().count() <= 30);
+        }
+    }
+}
@@ -0,0 +1,449 @@
+use std::env;
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::PathBuf;
+use std::process::{self, Command, Stdio};
+use std::time::Duration;
+
+use guessing_game::game::{self, Outcome};
+use guessing_game::secret::{Float, FloatRange, Secret, SecretType, Word, WordList};
+use guessing_game::Config;
+
+const SEED: u64 = 42;
+
+fn seeded_config() -> Config {
+    Config {
+        seed: Some(SEED),
+        ..Config::default()
+    }
+}
+
+// The binary must never write to the real scores file
+// or game log
+//
+fn scores_file(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("guessing_game-test-{}-{}.json", process::id(), name));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn log_file(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("guessing_game-test-{}-{}.jsonl", process::id(), name));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn secret_for(config: &Config) -> u32 {
+    game::pick_secret(&mut game::new_rng(config.seed), config)
+}
+
+// Plays one game from a script of input lines and
+// returns the outcome plus everything that was printed
+//
+fn play_script(config: &Config, lines: &[String]) -> (Outcome, String) {
+    let mut input = Cursor::new(lines.join("\n") + "\n");
+    let mut output = Vec::new();
+    let outcome = game::run(config, &mut input, &mut output).expect("game failed");
+    (outcome, String::from_utf8(output).unwrap())
+}
+
+fn responses(transcript: &str) -> Vec<&str> {
+    transcript
+        .lines()
+        .filter(|line| matches!(*line, "Too small!" | "Too big!" | "You win!"))
+        .collect()
+}
+
+#[test]
+fn scripted_win() {
+    let mut config = seeded_config();
+    config.min = 1;
+    config.max = 100;
+    let secret = secret_for(&config);
+    assert!(secret > 1 && secret < 100, "pick a seed with an interior secret");
+
+    let script = vec![
+        (secret - 1).to_string(),
+        (secret + 1).to_string(),
+        secret.to_string(),
+    ];
+    let (outcome, transcript) = play_script(&config, &script);
+
+    assert!(matches!(outcome, Outcome::Won { attempts: 3, .. }));
+    assert_eq!(responses(&transcript), vec!["Too small!", "Too big!", "You win!"]);
+    assert!(!transcript.contains("secret number is"));
+}
+
+#[test]
+fn invalid_input_does_not_cost_an_attempt() {
+    let config = seeded_config();
+    let secret = secret_for(&config);
+
+    let script = vec![
+        String::from("fish"),
+        String::new(),
+        String::from("-3"),
+        String::from("123456789012"),
+        String::from("1000"),
+        secret.to_string(),
+    ];
+    let (outcome, transcript) = play_script(&config, &script);
+
+    assert!(matches!(outcome, Outcome::Won { attempts: 1, .. }));
+    assert_eq!(responses(&transcript), vec!["You win!"]);
+    assert!(transcript.contains("'fish' isn't a number."));
+    assert!(transcript.contains("Type a guess, or one of: quit, hint, history, restart."));
+    assert!(transcript.contains("-3 is negative"));
+    assert!(transcript.contains("123456789012 is far too big"));
+    assert!(transcript.contains("1000 is out of range; the secret is between 1 and 100."));
+}
+
+#[test]
+fn commands_do_not_cost_attempts() {
+    let config = seeded_config();
+    let secret = secret_for(&config);
+
+    let script = vec![
+        String::from("history"),
+        (secret - 1).to_string(),
+        String::from("hint"),
+        String::from("history"),
+        secret.to_string(),
+    ];
+    let (outcome, transcript) = play_script(&config, &script);
+
+    assert!(matches!(outcome, Outcome::Won { attempts: 2, .. }));
+    assert!(transcript.contains("No guesses yet."));
+    assert!(transcript.contains(&format!("Hint: the secret is between {} and 100.", secret)));
+    assert!(transcript.contains(&format!("Your guesses: {} (too small)", secret - 1)));
+}
+
+#[test]
+fn quit_reveals_the_secret() {
+    let config = seeded_config();
+    let secret = secret_for(&config);
+
+    let (outcome, transcript) = play_script(&config, &[String::from("quit")]);
+    assert_eq!(outcome, Outcome::Quit { secret: secret.to_string() });
+    assert!(transcript.ends_with(&format!("Goodbye! The secret number was {}.\n", secret)));
+}
+
+#[test]
+fn restart_picks_the_next_secret() {
+    let config = Config {
+        reveal: true,
+        ..seeded_config()
+    };
+    let mut rng = game::new_rng(config.seed);
+    let first = game::pick_secret(&mut rng, &config);
+    let second = game::pick_secret(&mut rng, &config);
+
+    let wrong = if first == config.min { first + 1 } else { first - 1 };
+    let script = vec![wrong.to_string(), String::from("restart"), second.to_string()];
+    let (outcome, transcript) = play_script(&config, &script);
+
+    // The guess before the restart doesn't count
+    //
+    assert!(matches!(outcome, Outcome::Won { attempts: 1, .. }));
+    assert!(transcript.contains("Starting over with a new secret number."));
+    assert!(transcript.contains(&format!("The secret number is: {}", second)));
+}
+
+#[test]
+fn scripted_loss_reveals_secret() {
+    let mut config = seeded_config();
+    config.max_attempts = 2;
+    let secret = secret_for(&config);
+    let wrong = if secret == config.min { secret + 1 } else { secret - 1 };
+
+    let script = vec![wrong.to_string(), wrong.to_string()];
+    let (outcome, transcript) = play_script(&config, &script);
+
+    assert_eq!(outcome, Outcome::Lost { secret: secret.to_string() });
+    assert!(transcript.contains(&format!("The secret number was {}.", secret)));
+}
+
+#[test]
+fn reveal_prints_the_secret_up_front() {
+    let mut config = seeded_config();
+    config.reveal = true;
+    let secret = secret_for(&config);
+
+    let (_, transcript) = play_script(&config, &[secret.to_string()]);
+    assert!(transcript.contains(&format!("The secret number is: {}", secret)));
+}
+
+#[test]
+fn hints_track_the_interval_and_compare_with_binary_search() {
+    let config = Config {
+        min: 1,
+        max: 100,
+        hints: true,
+        ..seeded_config()
+    };
+    let secret = secret_for(&config);
+
+    let script = vec![
+        (secret - 1).to_string(),
+        (secret - 1).to_string(),
+        secret.to_string(),
+    ];
+    let (outcome, transcript) = play_script(&config, &script);
+
+    assert!(matches!(outcome, Outcome::Won { attempts: 3, .. }));
+    assert!(transcript.contains(&format!("Hint: the secret is between {} and 100.", secret)));
+    assert!(transcript.contains(&format!("Hint: you already know it's above {}.", secret - 1)));
+    assert!(transcript.contains(&format!(
+        "A binary search would have needed {} guesses; you took 3.",
+        guessing_game::state::optimal_guesses(1, 100, secret)
+    )));
+}
+
+#[test]
+fn no_hints_by_default() {
+    let config = seeded_config();
+    let secret = secret_for(&config);
+
+    let (_, transcript) = play_script(&config, &[secret.to_string()]);
+    assert!(!transcript.contains("Hint"));
+    assert!(!transcript.contains("binary search"));
+}
+
+#[test]
+fn end_of_input_ends_the_game() {
+    let config = seeded_config();
+    let secret = secret_for(&config);
+    let mut input = Cursor::new("");
+    let mut output = Vec::new();
+
+    let outcome = game::run(&config, &mut input, &mut output).unwrap();
+    assert_eq!(outcome, Outcome::Quit { secret: secret.to_string() });
+}
+
+#[test]
+fn float_game_accepts_a_guess_within_the_tolerance() {
+    let config = Config {
+        secret_type: SecretType::Float,
+        tolerance: 0.5,
+        ..seeded_config()
+    };
+    let domain = FloatRange {
+        min: 1.0,
+        max: 100.0,
+        tolerance: 0.5,
+    };
+    let secret = Float::random(&mut game::new_rng(config.seed), &domain);
+
+    let script = vec![
+        format!("{}", secret.0 - 2.0),
+        format!("{}", secret.0 + 0.25),
+    ];
+    let (outcome, transcript) = play_script(&config, &script);
+
+    assert!(matches!(outcome, Outcome::Won { attempts: 2, .. }));
+    assert!(transcript.starts_with("Guess the number between 1.00 and 100.00, to within 0.5!"));
+    assert_eq!(responses(&transcript), vec!["Too small!", "You win!"]);
+}
+
+#[test]
+fn letter_game_talks_about_the_alphabet() {
+    let config = Config {
+        secret_type: SecretType::Letter,
+        ..seeded_config()
+    };
+    let secret = char::random(&mut game::new_rng(config.seed), &('a'..='z'));
+    let wrong = if secret == 'a' { 'b' } else { 'a' };
+
+    let script = vec![String::from("ab"), wrong.to_string(), secret.to_ascii_uppercase().to_string()];
+    let (outcome, transcript) = play_script(&config, &script);
+
+    assert!(matches!(outcome, Outcome::Won { attempts: 2, .. }));
+    assert!(transcript.contains("'ab' isn't a single letter."));
+    assert!(transcript.contains("Too early in the alphabet!") || transcript.contains("Too late in the alphabet!"));
+}
+
+#[test]
+fn word_game_quits_with_the_secret_word() {
+    let config = Config {
+        secret_type: SecretType::Word,
+        ..seeded_config()
+    };
+    let secret = Word::random(&mut game::new_rng(config.seed), &WordList::bundled());
+
+    let script = vec![String::from("xyzzy"), String::from("quit")];
+    let (outcome, transcript) = play_script(&config, &script);
+
+    assert_eq!(outcome, Outcome::Quit { secret: secret.to_string() });
+    assert!(transcript.contains("'xyzzy' isn't in the word list."));
+    assert!(transcript.contains(&format!("Goodbye! The secret word was {}.", secret)));
+}
+
+#[test]
+fn timed_games_show_the_time_left() {
+    let config = Config {
+        time_limit: Some(Duration::from_secs(45)),
+        ..seeded_config()
+    };
+    let secret = secret_for(&config);
+
+    let (outcome, transcript) = play_script(&config, &[secret.to_string()]);
+    assert!(matches!(outcome, Outcome::Won { attempts: 1, .. }));
+    assert!(transcript.contains("Please input your guess (4"));
+    assert!(!transcript.contains("Please input your guess."));
+}
+
+#[test]
+fn binary_plays_a_tournament() {
+    let config = seeded_config();
+    let log = log_file("tournament");
+
+    // Quitting the first game abandons the series
+    //
+    let mut child = Command::new(env!("CARGO_BIN_EXE_guessing_game"))
+        .args(["--seed", &SEED.to_string(), "--tournament", "3"])
+        .arg("--log-file")
+        .arg(&log)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    writeln!(child.stdin.take().unwrap(), "quit").unwrap();
+
+    let result = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    assert!(result.status.success());
+    assert!(stdout.contains("Game 1 of a best of 3 (2 wins needed)."));
+    assert!(stdout.contains(&format!("Goodbye! The secret number was {}.", secret_for(&config))));
+    assert!(stdout.ends_with("Tournament abandoned.\n"));
+
+    fs::remove_file(&log).unwrap();
+}
+
+#[test]
+fn binary_replays_a_seeded_game_and_keeps_score() {
+    let config = seeded_config();
+    let secret = secret_for(&config);
+    let scores = scores_file("win");
+    let log = log_file("win");
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_guessing_game"))
+        .args(["--seed", &SEED.to_string()])
+        .arg("--scores-file")
+        .arg(&scores)
+        .arg("--log-file")
+        .arg(&log)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    writeln!(child.stdin.take().unwrap(), "{}", secret).unwrap();
+
+    let result = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    assert!(result.status.success());
+    assert!(stdout.contains("You win!"));
+    assert!(stdout.contains("High scores:"));
+
+    let listing = Command::new(env!("CARGO_BIN_EXE_guessing_game"))
+        .args(["scores", "--difficulty", "normal", "--scores-file"])
+        .arg(&scores)
+        .output()
+        .unwrap();
+    let listing = String::from_utf8_lossy(&listing.stdout);
+    assert!(listing.contains("normal"), "listing was: {}", listing);
+    assert_eq!(listing.lines().count(), 2);
+
+    fs::remove_file(&scores).unwrap();
+    fs::remove_file(&log).unwrap();
+}
+
+#[test]
+fn binary_exits_with_distinct_status_on_loss() {
+    let mut config = seeded_config();
+    config.min = 1;
+    config.max = 1000;
+    config.max_attempts = config.difficulty.attempts_for(config.min, config.max);
+    let secret = secret_for(&config);
+    let wrong = if secret == 1 { 2 } else { 1 };
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_guessing_game"))
+        .args(["--seed", &SEED.to_string(), "--max", "1000"])
+        .arg("--scores-file")
+        .arg(scores_file("loss"))
+        .arg("--log-file")
+        .arg(log_file("loss"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    {
+        let mut stdin = child.stdin.take().unwrap();
+        for _ in 0..config.max_attempts {
+            writeln!(stdin, "{}", wrong).unwrap();
+        }
+    }
+
+    let result = child.wait_with_output().unwrap();
+    assert_eq!(result.status.code(), Some(2));
+}
+
+#[test]
+fn binary_logs_games_that_replay_and_export() {
+    let config = seeded_config();
+    let secret = secret_for(&config);
+    let wrong = if secret == config.min { secret + 1 } else { secret - 1 };
+    let log = log_file("replay");
+
+    // Two games appended to the same log
+    //
+    for script in &[format!("{}\n{}\n", wrong, secret), String::from("quit\n")] {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_guessing_game"))
+            .args(["--seed", &SEED.to_string()])
+            .arg("--scores-file")
+            .arg(scores_file("replay"))
+            .arg("--log-file")
+            .arg(&log)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+        write!(child.stdin.take().unwrap(), "{}", script).unwrap();
+        child.wait().unwrap();
+    }
+
+    let replay = Command::new(env!("CARGO_BIN_EXE_guessing_game"))
+        .arg("replay")
+        .arg(&log)
+        .output()
+        .unwrap();
+    let report = String::from_utf8_lossy(&replay.stdout);
+    assert!(replay.status.success(), "replay said: {}", report);
+    assert!(report.contains("2 guesses, won. OK"));
+    assert!(report.contains("0 guesses, quit. OK"));
+    assert!(report.ends_with("Replayed 2 games: all match.\n"));
+
+    let export = Command::new(env!("CARGO_BIN_EXE_guessing_game"))
+        .args(["export", "--format", "csv"])
+        .arg(&log)
+        .output()
+        .unwrap();
+    let csv = String::from_utf8_lossy(&export.stdout);
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows.len(), 3);
+    assert!(rows[2].ends_with(&format!(",{},correct,won", secret)));
+
+    // Tampering with the log is caught
+    //
+    let tampered = fs::read_to_string(&log).unwrap().replace("\"correct\"", "\"too_big\"");
+    fs::write(&log, tampered).unwrap();
+    let replay = Command::new(env!("CARGO_BIN_EXE_guessing_game"))
+        .arg("replay")
+        .arg(&log)
+        .output()
+        .unwrap();
+    assert_eq!(replay.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&replay.stdout).contains("MISMATCH at event 3"));
+
+    fs::remove_file(&log).unwrap();
+    let _ = fs::remove_file(scores_file("replay"));
+}
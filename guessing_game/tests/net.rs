@@ -0,0 +1,223 @@
+use std::io::{BufRead, BufReader, Cursor, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use guessing_game::game;
+use guessing_game::net::{self, RoundResult, Server};
+use guessing_game::Config;
+
+fn config() -> Config {
+    Config {
+        seed: Some(7),
+        ..Config::default()
+    }
+}
+
+fn secret() -> u32 {
+    let config = config();
+    game::pick_secret(&mut game::new_rng(config.seed), &config)
+}
+
+fn start_server() -> (SocketAddr, JoinHandle<RoundResult>) {
+    let server = Server::bind("127.0.0.1:0", &config()).unwrap();
+    let addr = server.local_addr().unwrap();
+    (addr, thread::spawn(move || server.run().unwrap()))
+}
+
+struct Client {
+    stream: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+impl Client {
+    fn connect(addr: SocketAddr) -> Client {
+        let stream = TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let reader = BufReader::new(stream.try_clone().unwrap());
+        Client { stream, reader }
+    }
+
+    fn send(&mut self, line: &str) {
+        writeln!(self.stream, "{}", line).unwrap();
+    }
+
+    fn receive(&mut self) -> String {
+        let mut line = String::new();
+        self.reader.read_line(&mut line).unwrap();
+        line.trim_end().to_string()
+    }
+}
+
+#[test]
+fn first_correct_guess_wins() {
+    let secret = secret();
+    let (addr, server) = start_server();
+
+    let mut alice = Client::connect(addr);
+    assert_eq!(alice.receive(), "WELCOME 1 1 100");
+    alice.send("NAME alice");
+    alice.send(&format!("GUESS {}", secret - 1));
+    assert_eq!(alice.receive(), format!("TOO_SMALL {}", secret - 1));
+    assert_eq!(alice.receive(), "CLOSEST alice");
+
+    let mut bob = Client::connect(addr);
+    assert_eq!(bob.receive(), "WELCOME 2 1 100");
+    bob.send("NAME bob");
+    bob.send(&format!("GUESS {}", secret));
+
+    let winner = format!("WINNER bob {}", secret);
+    assert_eq!(bob.receive(), winner);
+    assert_eq!(alice.receive(), winner);
+
+    // The server hangs up once the round is over
+    //
+    assert_eq!(alice.receive(), "");
+
+    let result = server.join().unwrap();
+    assert_eq!(
+        result,
+        RoundResult {
+            winner: String::from("bob"),
+            secret,
+            guesses: 2,
+        }
+    );
+}
+
+#[test]
+fn closest_is_only_broadcast_when_it_changes() {
+    let secret = secret();
+    let (addr, server) = start_server();
+
+    let mut alice = Client::connect(addr);
+    alice.receive();
+    let mut bob = Client::connect(addr);
+    bob.receive();
+    bob.send("NAME bob");
+
+    alice.send(&format!("GUESS {}", secret + 2));
+    assert_eq!(alice.receive(), format!("TOO_BIG {}", secret + 2));
+    assert_eq!(alice.receive(), "CLOSEST player1");
+    assert_eq!(bob.receive(), "CLOSEST player1");
+
+    // Further away: no broadcast, so the next thing bob
+    // hears is his own answer
+    //
+    bob.send(&format!("GUESS {}", secret - 5));
+    assert_eq!(bob.receive(), format!("TOO_SMALL {}", secret - 5));
+
+    bob.send(&format!("GUESS {}", secret - 1));
+    assert_eq!(bob.receive(), format!("TOO_SMALL {}", secret - 1));
+    assert_eq!(bob.receive(), "CLOSEST bob");
+    assert_eq!(alice.receive(), "CLOSEST bob");
+
+    alice.send(&format!("GUESS {}", secret));
+    assert_eq!(server.join().unwrap().winner, "player1");
+}
+
+#[test]
+fn bad_lines_get_errors() {
+    let secret = secret();
+    let (addr, server) = start_server();
+
+    let mut client = Client::connect(addr);
+    client.receive();
+
+    client.send("GUESS fish");
+    assert_eq!(client.receive(), "ERROR 'fish' is not a number");
+    client.send("GUESS 1000");
+    assert_eq!(client.receive(), "ERROR guess must be between 1 and 100");
+    client.send("DANCE");
+    assert_eq!(client.receive(), "ERROR unknown command 'DANCE'");
+
+    client.send(&format!("GUESS {}", secret));
+    assert_eq!(client.receive(), format!("WINNER player1 {}", secret));
+    assert_eq!(server.join().unwrap().guesses, 1);
+}
+
+// A log the test can read back once the server is done
+//
+#[derive(Clone, Default)]
+struct SharedLog(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedLog {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn the_round_goes_to_the_log() {
+    let secret = secret();
+    let log = SharedLog::default();
+    let server = Server::bind("127.0.0.1:0", &config()).unwrap().with_log(log.clone());
+    let addr = server.local_addr().unwrap();
+    let server = thread::spawn(move || server.run().unwrap());
+
+    let mut dana = Client::connect(addr);
+    dana.receive();
+    dana.send("NAME dana");
+    dana.send(&format!("GUESS {}", secret));
+    dana.receive();
+    server.join().unwrap();
+
+    let log = String::from_utf8(log.0.lock().unwrap().clone()).unwrap();
+    assert_eq!(
+        log,
+        format!(
+            "player1 joined\nplayer1 is now called dana\ndana guessed {}\ndana wins!\n",
+            secret
+        )
+    );
+}
+
+#[test]
+fn join_plays_a_scripted_round() {
+    let secret = secret();
+    let (addr, server) = start_server();
+
+    let script = Cursor::new(format!("{}\n{}\n", secret + 1, secret));
+    let mut output = Vec::new();
+    net::join(&addr.to_string(), Some("carol"), script, &mut output).unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.starts_with("Joined! Guess the number between 1 and 100.\n"));
+    assert!(output.contains(&format!("{} is too big!", secret + 1)));
+    assert!(output.contains("carol is closest so far."));
+    assert!(output.ends_with(&format!("carol wins! The secret number was {}.\n", secret)));
+
+    assert_eq!(server.join().unwrap().winner, "carol");
+}
+
+#[test]
+fn a_player_who_stops_reading_is_hung_up_on() {
+    let secret = secret();
+    let (addr, server) = start_server();
+
+    let mut lurker = Client::connect(addr);
+    lurker.receive();
+    let mut erin = Client::connect(addr);
+    erin.receive();
+
+    // Every CLOSEST carries erin's (very long) name, and the
+    // lurker never reads them, so their socket fills up
+    //
+    let name = "e".repeat(400_000);
+    erin.send(&format!("NAME {}", name));
+    let guesses: Vec<u32> = if secret > 50 { (1..secret).collect() } else { (secret + 1..=100).rev().collect() };
+    for guess in guesses {
+        erin.send(&format!("GUESS {}", guess));
+        erin.receive();
+        assert_eq!(erin.receive().len(), "CLOSEST ".len() + name.len());
+    }
+
+    erin.send(&format!("GUESS {}", secret));
+    assert_eq!(erin.receive(), format!("WINNER {} {}", name, secret));
+    assert_eq!(server.join().unwrap().winner, name);
+}
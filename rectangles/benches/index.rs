@@ -0,0 +1,78 @@
+// 100k random rectangles in a 100000 x 100000 area,
+// queried through the quadtree and through the brute
+// force reference
+//
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use rectangles::{BruteForce, Point, QuadTree, Rectangle, SpatialIndex};
+
+const COUNT: usize = 100_000;
+const AREA: i32 = 100_000;
+
+fn random_rects(rng: &mut StdRng, count: usize) -> Vec<Rectangle> {
+    (0..count)
+        .map(|_| {
+            Rectangle::at(
+                rng.gen_range(0..AREA),
+                rng.gen_range(0..AREA),
+                rng.gen_range(1..500),
+                rng.gen_range(1..500),
+            )
+        })
+        .collect()
+}
+
+fn random_points(rng: &mut StdRng, count: usize) -> Vec<Point> {
+    (0..count)
+        .map(|_| Point::new(rng.gen_range(0..AREA), rng.gen_range(0..AREA)))
+        .collect()
+}
+
+fn filled<I: SpatialIndex>(mut index: I, rects: &[Rectangle]) -> I {
+    for &r in rects {
+        index.insert(r);
+    }
+    index
+}
+
+fn bench_index(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(17);
+    let rects = random_rects(&mut rng, COUNT);
+    let points = random_points(&mut rng, 100);
+    let ranges: Vec<Rectangle> = points.iter().map(|p| Rectangle::at(p.x, p.y, 2000, 2000)).collect();
+
+    let tree = filled(QuadTree::new(), &rects);
+    let brute = filled(BruteForce::new(), &rects);
+
+    let mut group = c.benchmark_group("index_100k");
+    group.sample_size(10);
+    group.bench_function("quadtree_build", |b| b.iter(|| filled(QuadTree::new(), black_box(&rects))));
+
+    group.bench_function("quadtree_point", |b| {
+        b.iter(|| points.iter().map(|&p| tree.at_point(p).len()).sum::<usize>())
+    });
+    group.bench_function("brute_force_point", |b| {
+        b.iter(|| points.iter().map(|&p| brute.at_point(p).len()).sum::<usize>())
+    });
+
+    group.bench_function("quadtree_range", |b| {
+        b.iter(|| ranges.iter().map(|r| tree.in_range(r).len()).sum::<usize>())
+    });
+    group.bench_function("brute_force_range", |b| {
+        b.iter(|| ranges.iter().map(|r| brute.in_range(r).len()).sum::<usize>())
+    });
+
+    group.bench_function("quadtree_nearest", |b| {
+        b.iter(|| points.iter().filter_map(|&p| tree.nearest(p)).count())
+    });
+    group.bench_function("brute_force_nearest", |b| {
+        b.iter(|| points.iter().filter_map(|&p| brute.nearest(p)).count())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_index);
+criterion_main!(benches);
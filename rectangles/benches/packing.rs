@@ -0,0 +1,57 @@
+// Compares the packing algorithms on the same random
+// items: a few hundred small labels, and fewer, mixed
+// sprites where rotation matters more.
+//
+// Each run also prints how many sheets each algorithm
+// needed and how full they were, since speed is only
+// half of the comparison.
+//
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use rectangles::packing::{self, Algorithm, Options};
+use rectangles::Rectangle;
+
+fn random_items(count: usize, min: u32, max: u32, seed: u64) -> Vec<Rectangle<u32>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..count)
+        .map(|_| Rectangle::new(rng.gen_range(min..=max), rng.gen_range(min..=max)))
+        .collect()
+}
+
+fn bench_packing(c: &mut Criterion) {
+    let sheet = Rectangle::new(1024, 1024);
+    let inputs = [
+        ("labels", random_items(500, 20, 120, 1)),
+        ("sprites", random_items(150, 16, 400, 2)),
+    ];
+
+    for (name, items) in &inputs {
+        let mut group = c.benchmark_group(format!("pack_{}", name));
+        for &algorithm in &Algorithm::ALL {
+            for &rotation in &[false, true] {
+                let options = Options { algorithm, rotation };
+                let packing = packing::pack(sheet, items, options);
+                println!(
+                    "{} {}{}: {} sheets, {:.1}% used",
+                    name,
+                    algorithm,
+                    if rotation { " with rotation" } else { "" },
+                    packing.sheets,
+                    packing.utilisation()
+                );
+
+                let id = format!("{}{}", algorithm, if rotation { "_rotate" } else { "" });
+                group.bench_with_input(BenchmarkId::from_parameter(id), items, |b, items| {
+                    b.iter(|| packing::pack(sheet, black_box(items), options))
+                });
+            }
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_packing);
+criterion_main!(benches);
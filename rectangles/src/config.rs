@@ -0,0 +1,261 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - Command line parsing is done by hand, the same way
+//   as in the guessing game: Command::new takes the
+//   arguments (program name first) and gives back what
+//   to do, or a message saying what was wrong.
+// - With no arguments the binary just runs its demo.
+// - The queries (area, can-hold, sort-by-area and
+//   largest) take rectangles as arguments, or read them
+//   from a file or standard input when there are none.
+//
+
+use std::path::PathBuf;
+
+use crate::packing;
+use crate::parse::{InputFormat, ParseError};
+use crate::rectangle::Rectangle;
+use crate::report::Format;
+
+pub const USAGE: &str = "\
+Usage: rectangles [COMMAND]
+
+Commands:
+    (none)                Show what a Rectangle can do
+    area [RECT...]        Print the area of each rectangle
+    can-hold OUTER [RECT...]
+                          Say whether OUTER can hold each of the others
+    sort-by-area [RECT...]
+                          Print the rectangles from smallest to largest
+    largest [RECT...]     Print the rectangle with the largest area
+    pack [OPTIONS] [FILE] Pack rectangles onto sheets, read from FILE (or
+                          standard input)
+
+Rectangles are written WxH, like 30x50, or WxH@X,Y to give an origin. With
+no RECT arguments they are read from --file (or standard input) instead.
+
+Input options:
+    --input <FORMAT>      text, json or csv (default text). Text is WxH
+                          separated by spaces, commas or new lines; JSON
+                          an array of \"WxH\" strings or objects with a
+                          width and height; CSV rows under a header of
+                          width,height and optionally x,y
+    --file <FILE>         Read the rectangles from FILE (not for pack)
+
+Pack options:
+    --sheet <WxH>         Size of each sheet (required)
+    --algorithm <NAME>    shelf, guillotine or maxrects (default maxrects)
+    --rotate              Allow turning rectangles 90 degrees
+    --format <FORMAT>     text, json, csv, svg or ascii (default text)
+    -h, --help            Print this help";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Query {
+    Area,
+    CanHold,
+    SortByArea,
+    Largest,
+}
+
+// Where a query's rectangles come from
+//
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Input {
+    pub format: InputFormat,
+    pub file: Option<PathBuf>,
+    pub rectangles: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackOptions {
+    pub sheet: Rectangle<u32>,
+    pub options: packing::Options,
+    pub format: Format,
+    pub input: InputFormat,
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Demo,
+    Pack(PackOptions),
+    Query(Query, Input),
+}
+
+impl Command {
+    pub fn new<I>(args: I) -> Result<Command, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter().skip(1);
+        match args.next().as_deref() {
+            None => Ok(Command::Demo),
+            Some("pack") => PackOptions::new(args).map(Command::Pack),
+            Some("area") => Input::new(args).map(|input| Command::Query(Query::Area, input)),
+            Some("can-hold") => Input::new(args).map(|input| Command::Query(Query::CanHold, input)),
+            Some("sort-by-area") => Input::new(args).map(|input| Command::Query(Query::SortByArea, input)),
+            Some("largest") => Input::new(args).map(|input| Command::Query(Query::Largest, input)),
+            Some(other) => Err(format!("unknown command '{}'", other)),
+        }
+    }
+}
+
+impl PackOptions {
+    fn new<I: Iterator<Item = String>>(mut args: I) -> Result<PackOptions, String> {
+        let mut sheet = None;
+        let mut options = packing::Options::default();
+        let mut format = Format::default();
+        let mut input = InputFormat::default();
+        let mut file = None;
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--sheet" => sheet = Some(parse_sheet(&value_for(&arg, &mut args)?)?),
+                "--algorithm" => options.algorithm = value_for(&arg, &mut args)?.parse()?,
+                "--rotate" => options.rotation = true,
+                "--format" => format = value_for(&arg, &mut args)?.parse()?,
+                "--input" => input = value_for(&arg, &mut args)?.parse()?,
+                _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+                _ if file.is_none() => file = Some(PathBuf::from(arg)),
+                _ => return Err(format!("unexpected argument '{}'", arg)),
+            }
+        }
+
+        let sheet = sheet.ok_or("pack needs a --sheet size")?;
+        if sheet.is_empty() {
+            return Err(String::from("the sheet must not be empty"));
+        }
+        Ok(PackOptions {
+            sheet,
+            options,
+            format,
+            input,
+            file,
+        })
+    }
+}
+
+impl Input {
+    fn new<I: Iterator<Item = String>>(mut args: I) -> Result<Input, String> {
+        let mut input = Input::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--input" => input.format = value_for(&arg, &mut args)?.parse()?,
+                "--file" => input.file = Some(PathBuf::from(value_for(&arg, &mut args)?)),
+                _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+                _ => input.rectangles.push(arg),
+            }
+        }
+        if input.file.is_some() && !input.rectangles.is_empty() {
+            return Err(String::from("give rectangles as arguments or with --file, not both"));
+        }
+        Ok(input)
+    }
+}
+
+fn value_for<I: Iterator<Item = String>>(flag: &str, args: &mut I) -> Result<String, String> {
+    args.next().ok_or_else(|| format!("{} needs a value", flag))
+}
+
+fn parse_sheet(text: &str) -> Result<Rectangle<u32>, String> {
+    text.parse().map_err(|e: ParseError| e.message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packing::Algorithm;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        std::iter::once("rectangles").chain(list.iter().copied()).map(String::from).collect()
+    }
+
+    #[test]
+    fn no_arguments_is_the_demo() {
+        assert_eq!(Command::new(args(&[])), Ok(Command::Demo));
+        assert!(Command::new(args(&["fold"])).is_err());
+    }
+
+    #[test]
+    fn pack_options() {
+        let command = Command::new(args(&[
+            "pack",
+            "--sheet",
+            "100x80",
+            "--algorithm",
+            "shelf",
+            "--rotate",
+            "--format",
+            "csv",
+            "--input",
+            "json",
+            "sizes.json",
+        ]));
+        assert_eq!(
+            command,
+            Ok(Command::Pack(PackOptions {
+                sheet: Rectangle::new(100, 80),
+                options: packing::Options {
+                    algorithm: Algorithm::Shelf,
+                    rotation: true,
+                },
+                format: Format::Csv,
+                input: InputFormat::Json,
+                file: Some(PathBuf::from("sizes.json")),
+            }))
+        );
+
+        let defaults = Command::new(args(&["pack", "--sheet", "10x10"])).unwrap();
+        assert!(matches!(defaults, Command::Pack(PackOptions { file: None, format: Format::Text, .. })));
+    }
+
+    #[test]
+    fn bad_pack_options() {
+        assert!(Command::new(args(&["pack"])).unwrap_err().contains("--sheet"));
+        assert!(Command::new(args(&["pack", "--sheet"])).is_err());
+        assert!(Command::new(args(&["pack", "--sheet", "0x10"])).is_err());
+        assert!(Command::new(args(&["pack", "--sheet", "10x10", "--algorithm", "best"])).is_err());
+        assert!(Command::new(args(&["pack", "--sheet", "10x10", "a", "b"])).is_err());
+        assert!(Command::new(args(&["pack", "--sheet", "10x10", "--fast"])).is_err());
+    }
+
+    #[test]
+    fn queries() {
+        let command = Command::new(args(&["can-hold", "30x50", "10x40"]));
+        let rectangles = vec![String::from("30x50"), String::from("10x40")];
+        assert_eq!(
+            command,
+            Ok(Command::Query(
+                Query::CanHold,
+                Input {
+                    rectangles,
+                    ..Input::default()
+                }
+            ))
+        );
+
+        let command = Command::new(args(&["largest", "--input", "csv", "--file", "sizes.csv"]));
+        assert_eq!(
+            command,
+            Ok(Command::Query(
+                Query::Largest,
+                Input {
+                    format: InputFormat::Csv,
+                    file: Some(PathBuf::from("sizes.csv")),
+                    rectangles: Vec::new(),
+                }
+            ))
+        );
+
+        assert!(Command::new(args(&["area", "--file", "a.txt", "1x1"])).is_err());
+        assert!(Command::new(args(&["area", "--input", "xml"])).is_err());
+        assert!(Command::new(args(&["sort-by-area", "--fast"])).is_err());
+    }
+
+    #[test]
+    fn sheet_sizes() {
+        assert_eq!(parse_sheet("30x50"), Ok(Rectangle::new(30, 50)));
+        assert_eq!(parse_sheet("30"), Err(String::from("'30' is not a size like 30x50")));
+    }
+}
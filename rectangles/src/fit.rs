@@ -0,0 +1,166 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - can_hold asks whether a rectangle is strictly bigger
+//   on both sides, so it can't hold an identical copy and
+//   never tries turning the other one sideways.
+//   can_hold_with takes a Fit policy to ask the other
+//   questions, and says which way round the other
+//   rectangle fits.
+// - Only sizes matter here, never positions.
+// - Where both orientations fit, Upright is preferred.
+//
+
+use crate::number::Number;
+use crate::rectangle::Rectangle;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Fit<T = i32> {
+    // Bigger on both sides (what can_hold does)
+    //
+    #[default]
+    Strict,
+    // At least as big on both sides
+    //
+    Inclusive,
+    // At least as big, either way round
+    //
+    AllowRotation,
+    // At least as big with `margin` to spare on every side,
+    // either way round
+    //
+    AllowRotationWithMargin(T),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    Upright,
+    Rotated,
+}
+
+impl<T: Number> Rectangle<T> {
+    // The same rectangle turned 90°: width and height swap,
+    // the origin stays put
+    //
+    pub fn rotate(&self) -> Rectangle<T> {
+        Rectangle {
+            width: self.height,
+            height: self.width,
+            ..*self
+        }
+    }
+
+    // How other fits inside this rectangle under `fit`, or
+    // None if it doesn't
+    //
+    pub fn can_hold_with(&self, other: &Rectangle<T>, fit: Fit<T>) -> Option<Orientation> {
+        let upright = (other.width, other.height);
+        let rotated = (other.height, other.width);
+
+        let fits = |(width, height): (T, T)| match fit {
+            Fit::Strict => self.width > width && self.height > height,
+            Fit::Inclusive | Fit::AllowRotation => self.width >= width && self.height >= height,
+            Fit::AllowRotationWithMargin(margin) => {
+                let margin = margin.widen();
+                self.width.widen() >= width.widen() + margin + margin
+                    && self.height.widen() >= height.widen() + margin + margin
+            }
+        };
+        let may_rotate = matches!(fit, Fit::AllowRotation | Fit::AllowRotationWithMargin(_));
+
+        if fits(upright) {
+            Some(Orientation::Upright)
+        } else if may_rotate && fits(rotated) {
+            Some(Orientation::Rotated)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strict_is_what_can_hold_always_did() {
+        let r1 = Rectangle::new(30, 50);
+        assert_eq!(r1.can_hold_with(&Rectangle::new(10, 40), Fit::Strict), Some(Orientation::Upright));
+        assert_eq!(r1.can_hold_with(&Rectangle::new(60, 45), Fit::Strict), None);
+        assert_eq!(r1.can_hold_with(&r1, Fit::Strict), None);
+        assert_eq!(r1.can_hold_with(&Rectangle::new(30, 10), Fit::Strict), None);
+        assert!(r1.can_hold(&Rectangle::new(10, 40)));
+        assert!(!r1.can_hold(&r1));
+        assert_eq!(Fit::default(), Fit::<i32>::Strict);
+    }
+
+    #[test]
+    fn inclusive_holds_an_identical_copy() {
+        let r = Rectangle::new(30, 50);
+        assert_eq!(r.can_hold_with(&r, Fit::Inclusive), Some(Orientation::Upright));
+        assert_eq!(r.can_hold_with(&Rectangle::new(30, 10), Fit::Inclusive), Some(Orientation::Upright));
+        assert_eq!(r.can_hold_with(&Rectangle::new(31, 10), Fit::Inclusive), None);
+        assert_eq!(r.can_hold_with(&r.rotate(), Fit::Inclusive), None);
+    }
+
+    #[test]
+    fn rotation_turns_the_other_rectangle_sideways() {
+        let tall = Rectangle::new(10, 50);
+        let wide = Rectangle::new(50, 10);
+        assert_eq!(tall.can_hold_with(&wide, Fit::AllowRotation), Some(Orientation::Rotated));
+        assert_eq!(tall.can_hold_with(&tall, Fit::AllowRotation), Some(Orientation::Upright));
+        assert_eq!(tall.can_hold_with(&Rectangle::new(11, 11), Fit::AllowRotation), None);
+
+        // A square fits either way, so it stays upright
+        //
+        let square = Rectangle::square(10);
+        assert_eq!(tall.can_hold_with(&square, Fit::AllowRotation), Some(Orientation::Upright));
+    }
+
+    #[test]
+    fn margins_go_on_every_side() {
+        let r = Rectangle::new(20, 30);
+        let fit = Fit::AllowRotationWithMargin(2);
+        assert_eq!(r.can_hold_with(&Rectangle::new(16, 26), fit), Some(Orientation::Upright));
+        assert_eq!(r.can_hold_with(&Rectangle::new(17, 26), fit), None);
+        assert_eq!(r.can_hold_with(&Rectangle::new(26, 16), fit), Some(Orientation::Rotated));
+        assert_eq!(
+            r.can_hold_with(&Rectangle::new(20, 30), Fit::AllowRotationWithMargin(0)),
+            Some(Orientation::Upright)
+        );
+    }
+
+    #[test]
+    fn edge_cases() {
+        // Empty rectangles fit into anything under the
+        // inclusive policies, even another empty one
+        //
+        let empty = Rectangle::new(0, 0);
+        assert_eq!(empty.can_hold_with(&empty, Fit::Inclusive), Some(Orientation::Upright));
+        assert_eq!(empty.can_hold_with(&empty, Fit::Strict), None);
+
+        // Margins are worked out wide, so huge ones don't
+        // overflow
+        //
+        let big = Rectangle::<u32>::new(u32::MAX, u32::MAX);
+        assert_eq!(big.can_hold_with(&Rectangle::new(1, 1), Fit::AllowRotationWithMargin(u32::MAX)), None);
+        assert_eq!(
+            big.can_hold_with(&Rectangle::new(1, 1), Fit::AllowRotationWithMargin(u32::MAX / 2)),
+            Some(Orientation::Upright)
+        );
+
+        // Floats work the same way
+        //
+        let sheet = Rectangle::new(8.5, 11.0);
+        assert_eq!(
+            sheet.can_hold_with(&Rectangle::new(11.0, 8.5), Fit::AllowRotationWithMargin(0.0)),
+            Some(Orientation::Rotated)
+        );
+        assert_eq!(sheet.can_hold_with(&Rectangle::new(11.0, 8.5), Fit::AllowRotationWithMargin(0.25)), None);
+    }
+
+    #[test]
+    fn rotate_keeps_the_origin() {
+        assert_eq!(Rectangle::at(1, 2, 3, 4).rotate(), Rectangle::at(1, 2, 4, 3));
+    }
+}
@@ -0,0 +1,499 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - A SpatialIndex holds positioned rectangles and finds
+//   the ones at a point, the ones overlapping a range, and
+//   the one nearest a point, without looking at them all.
+// - QuadTree is the real thing; BruteForce does the same
+//   by checking every rectangle, as the reference the
+//   quadtree is tested against.
+// - insert hands back an Id. Ids count up from 0 and are
+//   never reused, so the same inserts give the same ids
+//   in either index.
+// - Queries use the same rules as Rectangle:
+//     - at_point: contains_point (far edges are outside)
+//     - in_range: intersects (touching isn't enough)
+//     - nearest: the smallest distance from the point to
+//       a rectangle, edges included, so 0 for a point on
+//       or inside it. Ties go to the lowest Id.
+//   Results come back sorted by Id.
+//
+// - The quadtree covers every coordinate a Rectangle can
+//   reach. A node splits into four once it holds more than
+//   CAPACITY rectangles; a rectangle lives in the deepest
+//   node that holds all of it, so one that straddles a
+//   split line stays in the parent.
+//
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::rectangle::{Point, Rectangle};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Id(pub usize);
+
+pub trait SpatialIndex {
+    fn insert(&mut self, rect: Rectangle) -> Id;
+    fn remove(&mut self, id: Id) -> Option<Rectangle>;
+    fn get(&self, id: Id) -> Option<Rectangle>;
+    fn len(&self) -> usize;
+    fn at_point(&self, point: Point) -> Vec<Id>;
+    fn in_range(&self, range: &Rectangle) -> Vec<Id>;
+    fn nearest(&self, point: Point) -> Option<Id>;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// The squared distance from a point to the closest part
+// of the area between the given edges
+//
+fn distance(point: Point, left: i64, top: i64, right: i64, bottom: i64) -> u128 {
+    let gap = |p: i64, low: i64, high: i64| {
+        if p < low {
+            (low - p) as u128
+        } else if p > high {
+            (p - high) as u128
+        } else {
+            0
+        }
+    };
+    let dx = gap(point.x as i64, left, right);
+    let dy = gap(point.y as i64, top, bottom);
+    dx * dx + dy * dy
+}
+
+fn distance_to(point: Point, rect: &Rectangle) -> u128 {
+    distance(point, rect.left(), rect.top(), rect.right(), rect.bottom())
+}
+
+//////////////////////////////////////////////////
+// Brute force
+//
+
+#[derive(Debug, Clone, Default)]
+pub struct BruteForce {
+    rects: Vec<(Id, Rectangle)>,
+    next: usize,
+}
+
+impl BruteForce {
+    pub fn new() -> BruteForce {
+        BruteForce::default()
+    }
+
+    fn matching<F: Fn(&Rectangle) -> bool>(&self, f: F) -> Vec<Id> {
+        self.rects.iter().filter(|(_, r)| f(r)).map(|&(id, _)| id).collect()
+    }
+}
+
+impl SpatialIndex for BruteForce {
+    fn insert(&mut self, rect: Rectangle) -> Id {
+        let id = Id(self.next);
+        self.next += 1;
+        self.rects.push((id, rect));
+        id
+    }
+
+    fn remove(&mut self, id: Id) -> Option<Rectangle> {
+        let i = self.rects.iter().position(|&(other, _)| other == id)?;
+        Some(self.rects.remove(i).1)
+    }
+
+    fn get(&self, id: Id) -> Option<Rectangle> {
+        self.rects.iter().find(|&&(other, _)| other == id).map(|&(_, r)| r)
+    }
+
+    fn len(&self) -> usize {
+        self.rects.len()
+    }
+
+    fn at_point(&self, point: Point) -> Vec<Id> {
+        self.matching(|r| r.contains_point(point))
+    }
+
+    fn in_range(&self, range: &Rectangle) -> Vec<Id> {
+        self.matching(|r| r.intersects(range))
+    }
+
+    fn nearest(&self, point: Point) -> Option<Id> {
+        self.rects
+            .iter()
+            .min_by_key(|&&(id, r)| (distance_to(point, &r), id))
+            .map(|&(id, _)| id)
+    }
+}
+
+//////////////////////////////////////////////////
+// Quadtree
+//
+
+const CAPACITY: usize = 8;
+const MAX_DEPTH: u32 = 40;
+
+// The part of the plane a node covers, as half-open i64
+// ranges like Rectangle's edges
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Region {
+    left: i64,
+    top: i64,
+    right: i64,
+    bottom: i64,
+}
+
+impl Region {
+    // Everything from i32::MIN up to the furthest right or
+    // bottom edge a Rectangle can have
+    //
+    fn everything() -> Region {
+        let low = i32::MIN as i64;
+        let high = i32::MAX as i64 * 2 + 1;
+        Region {
+            left: low,
+            top: low,
+            right: high,
+            bottom: high,
+        }
+    }
+
+    fn quarters(&self) -> [Region; 4] {
+        let mx = self.left + (self.right - self.left) / 2;
+        let my = self.top + (self.bottom - self.top) / 2;
+        [
+            Region { right: mx, bottom: my, ..*self },
+            Region { left: mx, bottom: my, ..*self },
+            Region { right: mx, top: my, ..*self },
+            Region { left: mx, top: my, ..*self },
+        ]
+    }
+
+    fn holds(&self, rect: &Rectangle) -> bool {
+        self.left <= rect.left() && rect.right() <= self.right && self.top <= rect.top() && rect.bottom() <= self.bottom
+    }
+
+    fn contains_point(&self, point: Point) -> bool {
+        let (x, y) = (point.x as i64, point.y as i64);
+        self.left <= x && x < self.right && self.top <= y && y < self.bottom
+    }
+
+    fn overlaps(&self, rect: &Rectangle) -> bool {
+        self.left < rect.right() && rect.left() < self.right && self.top < rect.bottom() && rect.top() < self.bottom
+    }
+
+    fn distance(&self, point: Point) -> u128 {
+        distance(point, self.left, self.top, self.right, self.bottom)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    region: Region,
+    depth: u32,
+    rects: Vec<(Id, Rectangle)>,
+    children: Option<Box<[Node; 4]>>,
+}
+
+impl Node {
+    fn new(region: Region, depth: u32) -> Node {
+        Node {
+            region,
+            depth,
+            rects: Vec::new(),
+            children: None,
+        }
+    }
+
+    // The child that holds all of rect, if there is one
+    //
+    fn child_for(&mut self, rect: &Rectangle) -> Option<&mut Node> {
+        self.children
+            .as_mut()
+            .and_then(|children| children.iter_mut().find(|child| child.region.holds(rect)))
+    }
+
+    fn insert(&mut self, id: Id, rect: Rectangle) {
+        if let Some(child) = self.child_for(&rect) {
+            return child.insert(id, rect);
+        }
+        self.rects.push((id, rect));
+
+        if self.children.is_none() && self.rects.len() > CAPACITY && self.depth < MAX_DEPTH {
+            let [a, b, c, d] = self.region.quarters();
+            let depth = self.depth + 1;
+            self.children = Some(Box::new([
+                Node::new(a, depth),
+                Node::new(b, depth),
+                Node::new(c, depth),
+                Node::new(d, depth),
+            ]));
+            for (id, rect) in std::mem::take(&mut self.rects) {
+                self.insert(id, rect);
+            }
+        }
+    }
+
+    // Rectangles go back where insert would put them now,
+    // so this follows the same path
+    //
+    fn remove(&mut self, id: Id, rect: &Rectangle) -> bool {
+        if let Some(child) = self.child_for(rect) {
+            return child.remove(id, rect);
+        }
+        match self.rects.iter().position(|&(other, _)| other == id) {
+            Some(i) => {
+                self.rects.swap_remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn children(&self) -> impl Iterator<Item = &Node> {
+        self.children.iter().flat_map(|children| children.iter())
+    }
+
+    fn at_point(&self, point: Point, found: &mut Vec<Id>) {
+        found.extend(self.rects.iter().filter(|(_, r)| r.contains_point(point)).map(|&(id, _)| id));
+        for child in self.children().filter(|child| child.region.contains_point(point)) {
+            child.at_point(point, found);
+        }
+    }
+
+    fn in_range(&self, range: &Rectangle, found: &mut Vec<Id>) {
+        found.extend(self.rects.iter().filter(|(_, r)| r.intersects(range)).map(|&(id, _)| id));
+        for child in self.children().filter(|child| child.region.overlaps(range)) {
+            child.in_range(range, found);
+        }
+    }
+}
+
+// What the nearest neighbour search has still to look at:
+// a node that might hold something close, or a rectangle.
+// The heap gives back the closest first, nodes before
+// rectangles at the same distance (a node could still
+// hold a rectangle with a lower Id), then lower Ids first.
+//
+struct Candidate<'a> {
+    key: (u128, u8, Id),
+    node: Option<&'a Node>,
+}
+
+impl PartialEq for Candidate<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl Eq for Candidate<'_> {}
+
+impl PartialOrd for Candidate<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QuadTree {
+    root: Node,
+    rects: HashMap<Id, Rectangle>,
+    next: usize,
+}
+
+impl Default for QuadTree {
+    fn default() -> QuadTree {
+        QuadTree::new()
+    }
+}
+
+impl QuadTree {
+    pub fn new() -> QuadTree {
+        QuadTree {
+            root: Node::new(Region::everything(), 0),
+            rects: HashMap::new(),
+            next: 0,
+        }
+    }
+
+    // How deep the tree has grown, for the curious
+    //
+    pub fn depth(&self) -> u32 {
+        fn depth(node: &Node) -> u32 {
+            node.children().map(depth).max().unwrap_or(node.depth)
+        }
+        depth(&self.root)
+    }
+}
+
+impl SpatialIndex for QuadTree {
+    fn insert(&mut self, rect: Rectangle) -> Id {
+        let id = Id(self.next);
+        self.next += 1;
+        self.root.insert(id, rect);
+        self.rects.insert(id, rect);
+        id
+    }
+
+    fn remove(&mut self, id: Id) -> Option<Rectangle> {
+        let rect = self.rects.remove(&id)?;
+        let removed = self.root.remove(id, &rect);
+        debug_assert!(removed, "{:?} was not where it should be", id);
+        Some(rect)
+    }
+
+    fn get(&self, id: Id) -> Option<Rectangle> {
+        self.rects.get(&id).copied()
+    }
+
+    fn len(&self) -> usize {
+        self.rects.len()
+    }
+
+    fn at_point(&self, point: Point) -> Vec<Id> {
+        let mut found = Vec::new();
+        self.root.at_point(point, &mut found);
+        found.sort_unstable();
+        found
+    }
+
+    fn in_range(&self, range: &Rectangle) -> Vec<Id> {
+        let mut found = Vec::new();
+        if !range.is_empty() {
+            self.root.in_range(range, &mut found);
+        }
+        found.sort_unstable();
+        found
+    }
+
+    fn nearest(&self, point: Point) -> Option<Id> {
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse(Candidate {
+            key: (self.root.region.distance(point), 0, Id(0)),
+            node: Some(&self.root),
+        }));
+
+        while let Some(Reverse(candidate)) = heap.pop() {
+            let node = match candidate.node {
+                Some(node) => node,
+                None => return Some(candidate.key.2),
+            };
+            for &(id, rect) in &node.rects {
+                heap.push(Reverse(Candidate {
+                    key: (distance_to(point, &rect), 1, id),
+                    node: None,
+                }));
+            }
+            for child in node.children() {
+                heap.push(Reverse(Candidate {
+                    key: (child.region.distance(point), 0, Id(0)),
+                    node: Some(child),
+                }));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Both indexes, with the same rectangles inserted
+    //
+    fn both(rects: &[Rectangle]) -> (QuadTree, BruteForce) {
+        let mut tree = QuadTree::new();
+        let mut brute = BruteForce::new();
+        for &r in rects {
+            assert_eq!(tree.insert(r), brute.insert(r));
+        }
+        (tree, brute)
+    }
+
+    fn grid(n: i32) -> Vec<Rectangle> {
+        (0..n * n).map(|i| Rectangle::at((i % n) * 10, (i / n) * 10, 8, 8)).collect()
+    }
+
+    #[test]
+    fn point_and_range_queries() {
+        let (tree, brute) = both(&grid(10));
+        assert!(tree.depth() > 0);
+
+        assert_eq!(tree.at_point(Point::new(15, 25)), vec![Id(21)]);
+        assert_eq!(tree.at_point(Point::new(18, 25)), vec![]);
+        assert_eq!(tree.in_range(&Rectangle::at(5, 5, 10, 10)), vec![Id(0), Id(1), Id(10), Id(11)]);
+        assert_eq!(tree.in_range(&Rectangle::at(8, 8, 2, 2)), vec![]);
+
+        let everything = Rectangle::at(-1000, -1000, 5000, 5000);
+        assert_eq!(tree.in_range(&everything).len(), 100);
+        assert_eq!(tree.in_range(&everything), brute.in_range(&everything));
+    }
+
+    #[test]
+    fn straddling_rectangles_are_found() {
+        let mut rects = grid(6);
+        rects.push(Rectangle::at(-5, -5, 100, 100));
+        let (tree, brute) = both(&rects);
+
+        let point = Point::new(9, 9);
+        assert_eq!(tree.at_point(point), vec![Id(36)]);
+        assert_eq!(tree.at_point(Point::new(1, 1)), brute.at_point(Point::new(1, 1)));
+    }
+
+    #[test]
+    fn nearest_neighbours() {
+        let (tree, brute) = both(&grid(5));
+        assert_eq!(tree.nearest(Point::new(-100, -100)), Some(Id(0)));
+        assert_eq!(tree.nearest(Point::new(1000, 41)), Some(Id(24)));
+
+        // Halfway between two rectangles, the lower Id wins
+        //
+        assert_eq!(tree.nearest(Point::new(9, 3)), Some(Id(0)));
+        assert_eq!(brute.nearest(Point::new(9, 3)), Some(Id(0)));
+
+        assert_eq!(QuadTree::new().nearest(Point::new(0, 0)), None);
+    }
+
+    #[test]
+    fn remove_takes_rectangles_out() {
+        let (mut tree, _) = both(&grid(10));
+        assert_eq!(tree.remove(Id(21)), Some(Rectangle::at(10, 20, 8, 8)));
+        assert_eq!(tree.remove(Id(21)), None);
+        assert_eq!(tree.get(Id(21)), None);
+        assert_eq!(tree.len(), 99);
+        assert_eq!(tree.at_point(Point::new(15, 25)), vec![]);
+        assert_ne!(tree.nearest(Point::new(15, 25)), Some(Id(21)));
+    }
+
+    #[test]
+    fn far_corners_of_the_plane() {
+        let rects = [
+            Rectangle::at(i32::MIN, i32::MIN, 1, 1),
+            Rectangle::at(i32::MAX, i32::MAX, i32::MAX, i32::MAX),
+            Rectangle::at(i32::MIN, 0, i32::MAX, 1),
+        ];
+        let (tree, _) = both(&rects);
+        assert_eq!(tree.at_point(Point::new(i32::MAX, i32::MAX)), vec![Id(1)]);
+        assert_eq!(tree.at_point(Point::new(i32::MIN, i32::MIN)), vec![Id(0)]);
+        assert_eq!(tree.nearest(Point::new(0, 0)), Some(Id(2)));
+        assert_eq!(tree.nearest(Point::new(i32::MAX, 0)), Some(Id(1)));
+    }
+
+    #[test]
+    fn many_copies_of_one_rectangle() {
+        let rects = vec![Rectangle::at(3, 3, 1, 1); 100];
+        let (mut tree, _) = both(&rects);
+        assert_eq!(tree.at_point(Point::new(3, 3)).len(), 100);
+        for i in 0..100 {
+            assert!(tree.remove(Id(i)).is_some());
+        }
+        assert!(tree.is_empty());
+    }
+}
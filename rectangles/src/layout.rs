@@ -0,0 +1,481 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - A small flexbox: a Layout is a leaf, or a row, column
+//   or grid of other Layouts, and solve() works out where
+//   every one of them goes inside a container.
+// - Along a row (or down a column) each child has a Size:
+//     Fixed(n)        exactly n
+//     Fraction(w)     a share, by weight w, of the space
+//                     left after everything else
+//     MinMax(lo, hi)  at least lo, then grows like
+//                     Fraction(1) until it reaches hi
+//   Across a row (or a column) every child gets the whole
+//   height (or width). A grid sizes its columns and rows
+//   the same way and fills its cells a row at a time.
+// - Leftover space is shared out by weight. Children that
+//   reach their maximum stop growing and the rest share
+//   what they couldn't take. Units that don't divide
+//   evenly go one each to the first children that can
+//   still grow. With nothing that grows, the space is
+//   left at the end.
+// - Padding is inside a container, all the way round, and
+//   gaps go between its children.
+// - Overflow is found with can_hold: every node's slot
+//   must hold (Fit::Inclusive) what the node needs, which
+//   for a leaf is its own size and for a container is its
+//   padding, gaps and the Fixed and minimum sizes of its
+//   children. Overflowing children are still placed, in
+//   order, running past the end of their container.
+//
+
+use crate::fit::Fit;
+use crate::rectangle::Rectangle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Size {
+    Fixed(u32),
+    Fraction(u32),
+    MinMax(u32, u32),
+}
+
+impl Size {
+    fn base(self) -> u32 {
+        match self {
+            Size::Fixed(n) | Size::MinMax(n, _) => n,
+            Size::Fraction(_) => 0,
+        }
+    }
+
+    fn weight(self) -> u64 {
+        match self {
+            Size::Fixed(_) => 0,
+            Size::Fraction(w) => u64::from(w),
+            Size::MinMax(..) => 1,
+        }
+    }
+
+    fn max(self) -> u32 {
+        match self {
+            Size::Fixed(n) => n,
+            Size::Fraction(_) => u32::MAX,
+            Size::MinMax(lo, hi) => lo.max(hi),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kind {
+    Leaf { width: u32, height: u32 },
+    Row(Vec<(Size, Layout)>),
+    Column(Vec<(Size, Layout)>),
+    Grid {
+        columns: Vec<Size>,
+        rows: Vec<Size>,
+        cells: Vec<Layout>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub label: String,
+    pub padding: u32,
+    pub gap: u32,
+    pub kind: Kind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placed {
+    pub label: String,
+    pub rect: Rectangle<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overflow {
+    pub label: String,
+    // The size the node needed, and the slot it got
+    //
+    pub needed: Rectangle<u32>,
+    pub slot: Rectangle<u32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Solution {
+    // Every node, parents before their children
+    //
+    pub placed: Vec<Placed>,
+    pub overflows: Vec<Overflow>,
+}
+
+impl Solution {
+    pub fn get(&self, label: &str) -> Option<Rectangle<u32>> {
+        self.placed.iter().find(|p| p.label == label).map(|p| p.rect)
+    }
+
+    pub fn fits(&self) -> bool {
+        self.overflows.is_empty()
+    }
+}
+
+impl Layout {
+    // Something that needs width x height and takes whatever
+    // slot it's given
+    //
+    pub fn leaf(label: &str, width: u32, height: u32) -> Layout {
+        Layout::new(label, Kind::Leaf { width, height })
+    }
+
+    pub fn row(label: &str) -> Layout {
+        Layout::new(label, Kind::Row(Vec::new()))
+    }
+
+    pub fn column(label: &str) -> Layout {
+        Layout::new(label, Kind::Column(Vec::new()))
+    }
+
+    pub fn grid(label: &str, columns: Vec<Size>, rows: Vec<Size>) -> Layout {
+        Layout::new(
+            label,
+            Kind::Grid {
+                columns,
+                rows,
+                cells: Vec::new(),
+            },
+        )
+    }
+
+    fn new(label: &str, kind: Kind) -> Layout {
+        Layout {
+            label: String::from(label),
+            padding: 0,
+            gap: 0,
+            kind,
+        }
+    }
+
+    pub fn padding(mut self, padding: u32) -> Layout {
+        self.padding = padding;
+        self
+    }
+
+    pub fn gap(mut self, gap: u32) -> Layout {
+        self.gap = gap;
+        self
+    }
+
+    // Adds a child to a row or column
+    //
+    pub fn child(mut self, size: Size, child: Layout) -> Layout {
+        match &mut self.kind {
+            Kind::Row(children) | Kind::Column(children) => children.push((size, child)),
+            _ => panic!("Only rows and columns have sized children; '{}' doesn't.", self.label),
+        }
+        self
+    }
+
+    // Adds the next cell to a grid
+    //
+    pub fn cell(mut self, child: Layout) -> Layout {
+        match &mut self.kind {
+            Kind::Grid { cells, .. } => cells.push(child),
+            _ => panic!("Only grids have cells; '{}' doesn't.", self.label),
+        }
+        self
+    }
+
+    pub fn solve(&self, container: Rectangle<u32>) -> Solution {
+        let mut solution = Solution::default();
+        self.place(container, &mut solution);
+        solution
+    }
+
+    // The smallest slot this node fits in
+    //
+    pub fn needed(&self) -> Rectangle<u32> {
+        let outside = self.padding.saturating_mul(2);
+        let along = |sizes: Vec<Size>| {
+            let gaps = self.gap.saturating_mul((sizes.len() as u32).saturating_sub(1));
+            sizes.iter().fold(outside.saturating_add(gaps), |total, size| total.saturating_add(size.base()))
+        };
+        match &self.kind {
+            Kind::Leaf { width, height } => Rectangle::new(*width, *height),
+            Kind::Row(children) => Rectangle::new(along(children.iter().map(|c| c.0).collect()), outside),
+            Kind::Column(children) => Rectangle::new(outside, along(children.iter().map(|c| c.0).collect())),
+            Kind::Grid { columns, rows, .. } => Rectangle::new(along(columns.clone()), along(rows.clone())),
+        }
+    }
+
+    fn place(&self, slot: Rectangle<u32>, solution: &mut Solution) {
+        solution.placed.push(Placed {
+            label: self.label.clone(),
+            rect: slot,
+        });
+        let needed = self.needed();
+        if slot.can_hold_with(&needed, Fit::Inclusive).is_none() {
+            solution.overflows.push(Overflow {
+                label: self.label.clone(),
+                needed,
+                slot,
+            });
+        }
+
+        let inner = slot.inset(self.padding);
+        match &self.kind {
+            Kind::Leaf { .. } => {}
+            Kind::Row(children) => {
+                let sizes: Vec<Size> = children.iter().map(|c| c.0).collect();
+                let widths = distribute(&sizes, inner.width, self.gap);
+                for ((_, child), x, width) in each_track(children, inner.x, &widths, self.gap) {
+                    child.place(Rectangle::at(x, inner.y, width, inner.height), solution);
+                }
+            }
+            Kind::Column(children) => {
+                let sizes: Vec<Size> = children.iter().map(|c| c.0).collect();
+                let heights = distribute(&sizes, inner.height, self.gap);
+                for ((_, child), y, height) in each_track(children, inner.y, &heights, self.gap) {
+                    child.place(Rectangle::at(inner.x, y, inner.width, height), solution);
+                }
+            }
+            Kind::Grid { columns, rows, cells } => {
+                let widths = distribute(columns, inner.width, self.gap);
+                let heights = distribute(rows, inner.height, self.gap);
+                let xs: Vec<(u32, u32)> = each_track(columns, inner.x, &widths, self.gap).map(|t| (t.1, t.2)).collect();
+                let ys: Vec<(u32, u32)> = each_track(rows, inner.y, &heights, self.gap).map(|t| (t.1, t.2)).collect();
+
+                for (i, cell) in cells.iter().enumerate() {
+                    // A cell past the last row has nowhere to go,
+                    // so it gets an empty slot after the grid
+                    //
+                    let rect = match (xs.get(i % xs.len().max(1)), ys.get(i / xs.len().max(1))) {
+                        (Some(&(x, width)), Some(&(y, height))) => Rectangle::at(x, y, width, height),
+                        _ => Rectangle::at(inner.x, inner.y.saturating_add(inner.height), 0, 0),
+                    };
+                    cell.place(rect, solution);
+                }
+            }
+        }
+    }
+}
+
+// Each item with where its track starts and how long it is
+//
+fn each_track<'a, I>(items: &'a [I], start: u32, lengths: &'a [u32], gap: u32) -> impl Iterator<Item = (&'a I, u32, u32)> {
+    let mut next = start;
+    items.iter().zip(lengths).map(move |(item, &length)| {
+        let at = next;
+        next = next.saturating_add(length).saturating_add(gap);
+        (item, at, length)
+    })
+}
+
+// How long each track is, given the room there is for
+// them all and the gaps between them
+//
+fn distribute(sizes: &[Size], room: u32, gap: u32) -> Vec<u32> {
+    let gaps = gap.saturating_mul((sizes.len() as u32).saturating_sub(1));
+    let mut lengths: Vec<u32> = sizes.iter().map(|size| size.base()).collect();
+    let used = lengths.iter().fold(gaps, |total, &length| total.saturating_add(length));
+    let mut leftover = u64::from(room.saturating_sub(used));
+
+    let mut growing: Vec<usize> = (0..sizes.len()).filter(|&i| sizes[i].weight() > 0).collect();
+    while leftover > 0 && !growing.is_empty() {
+        let total: u64 = growing.iter().map(|&i| sizes[i].weight()).sum();
+        let share = |i: usize| leftover * sizes[i].weight() / total;
+
+        // Anything that would grow past its maximum is set to
+        // it, and the rest share out what's left again
+        //
+        let capped: Vec<usize> = growing
+            .iter()
+            .copied()
+            .filter(|&i| u64::from(lengths[i]) + share(i) >= u64::from(sizes[i].max()))
+            .collect();
+        if !capped.is_empty() {
+            for &i in &capped {
+                leftover -= u64::from(sizes[i].max() - lengths[i]);
+                lengths[i] = sizes[i].max();
+            }
+            growing.retain(|i| !capped.contains(i));
+            continue;
+        }
+
+        let shares: Vec<u64> = growing.iter().map(|&i| share(i)).collect();
+        for (&i, &share) in growing.iter().zip(&shares) {
+            lengths[i] += share as u32;
+        }
+        let mut remainder = leftover - shares.iter().sum::<u64>();
+        for &i in &growing {
+            if remainder > 0 && lengths[i] < sizes[i].max() {
+                lengths[i] += 1;
+                remainder -= 1;
+            }
+        }
+        break;
+    }
+    lengths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn widths(solution: &Solution, labels: &[&str]) -> Vec<u32> {
+        labels.iter().map(|label| solution.get(label).unwrap().width).collect()
+    }
+
+    #[test]
+    fn fixed_sizes_sit_side_by_side() {
+        let row = Layout::row("row")
+            .child(Size::Fixed(10), Layout::leaf("a", 0, 0))
+            .child(Size::Fixed(20), Layout::leaf("b", 0, 0));
+        let solution = row.solve(Rectangle::at(5, 5, 100, 10));
+        assert_eq!(solution.get("a"), Some(Rectangle::at(5, 5, 10, 10)));
+        assert_eq!(solution.get("b"), Some(Rectangle::at(15, 5, 20, 10)));
+        assert!(solution.fits());
+    }
+
+    #[test]
+    fn fractions_share_the_leftover_by_weight() {
+        let row = Layout::row("row")
+            .child(Size::Fixed(10), Layout::leaf("fixed", 0, 0))
+            .child(Size::Fraction(1), Layout::leaf("one", 0, 0))
+            .child(Size::Fraction(2), Layout::leaf("two", 0, 0));
+        let solution = row.solve(Rectangle::new(70, 1));
+        assert_eq!(widths(&solution, &["fixed", "one", "two"]), [10, 20, 40]);
+        assert_eq!(solution.get("two").unwrap().x, 30);
+    }
+
+    #[test]
+    fn uneven_leftovers_go_to_the_first_children() {
+        let row = Layout::row("row")
+            .child(Size::Fraction(1), Layout::leaf("a", 0, 0))
+            .child(Size::Fraction(1), Layout::leaf("b", 0, 0))
+            .child(Size::Fraction(1), Layout::leaf("c", 0, 0));
+        assert_eq!(widths(&row.solve(Rectangle::new(11, 1)), &["a", "b", "c"]), [4, 4, 3]);
+        assert_eq!(widths(&row.solve(Rectangle::new(10, 1)), &["a", "b", "c"]), [4, 3, 3]);
+    }
+
+    #[test]
+    fn min_max_sizes_grow_until_their_maximum() {
+        let row = Layout::row("row")
+            .child(Size::MinMax(5, 10), Layout::leaf("capped", 0, 0))
+            .child(Size::Fraction(1), Layout::leaf("rest", 0, 0));
+        assert_eq!(widths(&row.solve(Rectangle::new(100, 1)), &["capped", "rest"]), [10, 90]);
+        assert_eq!(widths(&row.solve(Rectangle::new(12, 1)), &["capped", "rest"]), [9, 3]);
+        assert_eq!(widths(&row.solve(Rectangle::new(5, 1)), &["capped", "rest"]), [5, 0]);
+    }
+
+    #[test]
+    fn space_nothing_grows_into_is_left_at_the_end() {
+        let column = Layout::column("column")
+            .child(Size::MinMax(1, 2), Layout::leaf("a", 0, 0))
+            .child(Size::Fixed(3), Layout::leaf("b", 0, 0));
+        let solution = column.solve(Rectangle::new(4, 50));
+        assert_eq!(solution.get("a"), Some(Rectangle::new(4, 2)));
+        assert_eq!(solution.get("b"), Some(Rectangle::at(0, 2, 4, 3)));
+    }
+
+    #[test]
+    fn padding_and_gaps() {
+        let row = Layout::row("row")
+            .padding(2)
+            .gap(3)
+            .child(Size::Fraction(1), Layout::leaf("a", 0, 0))
+            .child(Size::Fraction(1), Layout::leaf("b", 0, 0));
+        let solution = row.solve(Rectangle::new(27, 10));
+        assert_eq!(solution.get("a"), Some(Rectangle::at(2, 2, 10, 6)));
+        assert_eq!(solution.get("b"), Some(Rectangle::at(15, 2, 10, 6)));
+        assert_eq!(row.needed(), Rectangle::new(7, 4));
+    }
+
+    #[test]
+    fn grids_fill_a_row_at_a_time() {
+        let grid = Layout::grid("grid", vec![Size::Fixed(10), Size::Fraction(1)], vec![Size::Fraction(1); 2])
+            .gap(1)
+            .cell(Layout::leaf("a", 0, 0))
+            .cell(Layout::leaf("b", 0, 0))
+            .cell(Layout::leaf("c", 0, 0));
+        let solution = grid.solve(Rectangle::new(31, 21));
+        assert_eq!(solution.get("a"), Some(Rectangle::at(0, 0, 10, 10)));
+        assert_eq!(solution.get("b"), Some(Rectangle::at(11, 0, 20, 10)));
+        assert_eq!(solution.get("c"), Some(Rectangle::at(0, 11, 10, 10)));
+        assert!(solution.fits());
+    }
+
+    #[test]
+    fn nested_layouts() {
+        let page = Layout::column("page")
+            .padding(1)
+            .child(Size::Fixed(3), Layout::leaf("header", 20, 3))
+            .child(
+                Size::Fraction(1),
+                Layout::row("body")
+                    .gap(1)
+                    .child(Size::MinMax(10, 15), Layout::leaf("sidebar", 10, 5))
+                    .child(Size::Fraction(1), Layout::leaf("main", 20, 5)),
+            );
+        let solution = page.solve(Rectangle::new(50, 20));
+        assert!(solution.fits());
+        assert_eq!(solution.get("body"), Some(Rectangle::at(1, 4, 48, 15)));
+        assert_eq!(solution.get("sidebar"), Some(Rectangle::at(1, 4, 15, 15)));
+        assert_eq!(solution.get("main"), Some(Rectangle::at(17, 4, 32, 15)));
+        let labels: Vec<&str> = solution.placed.iter().map(|p| p.label.as_str()).collect();
+        assert_eq!(labels, ["page", "header", "body", "sidebar", "main"]);
+    }
+
+    #[test]
+    fn fixed_sizes_that_are_too_big_overflow() {
+        let row = Layout::row("row")
+            .gap(2)
+            .child(Size::Fixed(10), Layout::leaf("a", 0, 0))
+            .child(Size::Fixed(10), Layout::leaf("b", 0, 0));
+        let solution = row.solve(Rectangle::new(15, 5));
+        assert_eq!(
+            solution.overflows,
+            [Overflow {
+                label: String::from("row"),
+                needed: Rectangle::new(22, 0),
+                slot: Rectangle::new(15, 5),
+            }]
+        );
+        // Still placed, running off the end
+        //
+        assert_eq!(solution.get("b"), Some(Rectangle::at(12, 0, 10, 5)));
+    }
+
+    #[test]
+    fn leaves_too_big_for_their_slot_overflow() {
+        let column = Layout::column("column")
+            .child(Size::Fraction(1), Layout::leaf("fits", 10, 5))
+            .child(Size::Fraction(1), Layout::leaf("too wide", 11, 5))
+            .child(Size::Fraction(1), Layout::leaf("too tall", 1, 6));
+        let solution = column.solve(Rectangle::new(10, 15));
+        let overflowing: Vec<&str> = solution.overflows.iter().map(|o| o.label.as_str()).collect();
+        assert_eq!(overflowing, ["too wide", "too tall"]);
+        assert_eq!(solution.overflows[1].slot, Rectangle::at(0, 10, 10, 5));
+    }
+
+    #[test]
+    fn exactly_full_is_not_an_overflow() {
+        let row = Layout::row("row").padding(1).child(Size::Fixed(8), Layout::leaf("a", 8, 8));
+        assert!(row.solve(Rectangle::new(10, 10)).fits());
+        assert!(!row.solve(Rectangle::new(9, 10)).fits());
+    }
+
+    #[test]
+    fn extra_grid_cells_overflow() {
+        let grid = Layout::grid("grid", vec![Size::Fraction(1)], vec![Size::Fraction(1)])
+            .cell(Layout::leaf("in", 1, 1))
+            .cell(Layout::leaf("out", 1, 1));
+        let solution = grid.solve(Rectangle::new(4, 4));
+        assert_eq!(solution.get("out"), Some(Rectangle::at(0, 4, 0, 0)));
+        assert_eq!(solution.overflows.len(), 1);
+        assert_eq!(solution.overflows[0].label, "out");
+    }
+
+    #[test]
+    #[should_panic(expected = "Only grids have cells")]
+    fn rows_have_no_cells() {
+        Layout::row("row").cell(Layout::leaf("a", 1, 1));
+    }
+}
@@ -0,0 +1,28 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - Rectangle lives in a library crate so that other
+//   code (and the tests in tests/) can use it, while
+//   src/main.rs stays a small demo and command line
+//   frontend.
+//
+
+pub mod config;
+pub mod fit;
+pub mod index;
+pub mod layout;
+pub mod number;
+pub mod packing;
+pub mod parse;
+pub mod rectangle;
+pub mod rectset;
+pub mod render;
+pub mod report;
+pub mod shape;
+
+pub use crate::fit::{Fit, Orientation};
+pub use crate::index::{BruteForce, Id, QuadTree, SpatialIndex};
+pub use crate::number::Number;
+pub use crate::rectangle::{Point, Rectangle};
+pub use crate::rectset::RectSet;
+pub use crate::shape::{Circle, Polygon, Scene, Shape, Triangle};
@@ -5,44 +5,114 @@
 // - Rust supports automatic referencing/dereferencing
 //   so that you don't need to do stuff like
 //      (&r1).area()
-// 
+// - Rectangle itself lives in src/rectangle.rs, in the
+//   library crate
+//
 
-#[derive(Debug)]
-struct Rectangle {
-    width: u32,
-    height: u32
-}
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process;
+
+use rectangles::config::{self, Command, Input, PackOptions, Query};
+use rectangles::parse::{self, InputFormat};
+use rectangles::render::{Drawing, Style};
+use rectangles::{packing, report};
+use rectangles::{Fit, Point, Rectangle};
 
-impl Rectangle {
-    fn area(&self) -> u32 {
-        self.width * self.height
+fn main() {
+    if env::args().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", config::USAGE);
+        return;
     }
 
-    fn can_hold(&self, other: &Rectangle) -> bool {
-        self.width > other.width && self.height > other.height
+    let command = Command::new(env::args()).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {}", err);
+        eprintln!("{}", config::USAGE);
+        process::exit(1);
+    });
+
+    let result = match command {
+        Command::Demo => {
+            demo();
+            Ok(())
+        }
+        Command::Pack(options) => pack(&options),
+        Command::Query(query, input) => run_query(query, &input),
+    };
+    if let Err(err) = result {
+        eprintln!("Error: {}", err);
+        process::exit(1);
     }
 }
 
-// Example of an associated function defined in a new
-// impl block. Associated functions do not take self parameters.
+// Rectangles from a file, or from standard input when
+// there's no file. Errors name the file.
 //
-// Each struct is allowed to have multiple impl blocks.
-//
-impl Rectangle {
-    fn square(size: u32) -> Rectangle {
-        Rectangle {
-            width: size,
-            height: size
+fn read(file: Option<&Path>, format: InputFormat) -> Result<Vec<Rectangle<u32>>, String> {
+    let text = match file {
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?,
+        None => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text).map_err(|e| e.to_string())?;
+            text
         }
-    }
+    };
+    parse::parse(&text, format).map_err(|e| match file {
+        Some(path) => format!("{}: {}", path.display(), e),
+        None => e.to_string(),
+    })
 }
 
-fn main() {
-    let r1 = Rectangle {
-        width: 30,
-        height: 50
+fn pack(options: &PackOptions) -> Result<(), String> {
+    let items = read(options.file.as_deref(), options.input)?;
+
+    let packing = packing::pack(options.sheet, &items, options.options);
+    report::write(&packing, &items, options.options, options.format, &mut io::stdout()).map_err(|e| e.to_string())
+}
+
+fn run_query(query: Query, input: &Input) -> Result<(), String> {
+    let rects = if input.rectangles.is_empty() {
+        read(input.file.as_deref(), input.format)?
+    } else {
+        parse::parse_args(&input.rectangles).map_err(|e| e.to_string())?
     };
 
+    match query {
+        Query::Area => {
+            for r in &rects {
+                println!("{}: {}", r, r.area());
+            }
+        }
+        Query::CanHold => {
+            let (outer, others) = rects.split_first().ok_or("can-hold needs a rectangle to hold the others")?;
+            for r in others {
+                let verdict = if outer.can_hold(r) { "can hold" } else { "can't hold" };
+                println!("{} {} {}", outer, verdict, r);
+            }
+        }
+        Query::SortByArea => {
+            let mut sorted = rects;
+            sorted.sort_by_key(|r| r.area());
+            for r in &sorted {
+                println!("{}", r);
+            }
+        }
+        Query::Largest => {
+            // max_by_key keeps the last of equals, so going
+            // backwards gives the first
+            //
+            let largest = rects.iter().rev().max_by_key(|r| r.area()).ok_or("there are no rectangles")?;
+            println!("{}", largest);
+        }
+    }
+    Ok(())
+}
+
+fn demo() {
+    let r1 = Rectangle::<u32>::new(30, 50);
+
     println!("Area: {}", area(&r1));
     println!("r1: {:?}", r1);
 
@@ -51,23 +121,57 @@ fn main() {
     println!("r1: {:#?}", r1);
     println!("r1.area(): {}", r1.area());
 
-    let r2 = Rectangle {
-        width: 10,
-        height: 40
-    };
-
-    let r3 = Rectangle {
-        width: 60,
-        height: 45
-    };
+    let r2 = Rectangle::<u32>::new(10, 40);
+    let r3 = Rectangle::<u32>::new(60, 45);
 
     println!("r2 in r1? {}", r1.can_hold(&r2));
     println!("r3 in r1? {}", r1.can_hold(&r3));
 
-    let sq = Rectangle::square(3);
+    // can_hold is strict; can_hold_with takes a Fit policy
+    // and says which way round the other one fits
+    //
+    println!("r1 in r1? {}", r1.can_hold(&r1));
+    println!("r1 in r1, inclusive? {:?}", r1.can_hold_with(&r1, Fit::Inclusive));
+    println!("r1 turned in r1? {:?}", r1.can_hold_with(&r1.rotate(), Fit::AllowRotation));
+    println!(
+        "r2 turned in r1 with a margin of 5? {:?}",
+        r1.can_hold_with(&r2.rotate(), Fit::AllowRotationWithMargin(5))
+    );
+
+    // Draw r2 inside r1 to see why it fits
+    //
+    let mut drawing = Drawing::new();
+    drawing.add(&r1, Some("r1"), Style::default());
+    drawing.add(&r2.translate(5, 5), Some("r2"), Style::default());
+    print!("{}", drawing.to_ascii(21));
+
+    let sq = Rectangle::<u32>::square(3);
     println!("sq: {:?}", sq);
+
+    // Rectangles with an origin
+    //
+    let moved = r2.translate(25, 5);
+    println!("r2 moved: {:?}", moved);
+    println!("r1.perimeter(): {}", r1.perimeter());
+    println!("r1 contains (29, 49)? {}", r1.contains_point(Point::new(29, 49)));
+    println!("r1 and moved intersect? {}", r1.intersects(&moved));
+    println!("r1 ∩ moved: {:?}", r1.intersection(&moved));
+    println!("r1 ∪ moved: {:?}", r1.union(&moved));
+    println!("r1 inset by 5: {:?}", r1.inset(5));
+    println!("sq scaled by 4: {:?}", sq.scale(4));
+
+    // Sides this long would overflow a u32 area, so area()
+    // gives a u64 and checked_area() says it doesn't fit
+    //
+    let big = Rectangle::<u32>::new(100_000, 100_000);
+    println!("big.area(): {}", big.area());
+    println!("big.checked_area(): {:?}", big.checked_area());
+    println!("r1 as f64: {:?}", r1.try_cast::<f64>());
 }
 
-fn area(r: &Rectangle) -> u32 {
-    r.width * r.height
+// The same as Rectangle::area, widened so it can't
+// overflow
+//
+fn area(r: &Rectangle<u32>) -> u64 {
+    u64::from(r.width) * u64::from(r.height)
 }
@@ -0,0 +1,298 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - Number is what a Rectangle<T> needs from T. It is
+//   implemented for i32, u32, i64, u64, f32 and f64.
+// - Every type has a Wide type that edges are worked out
+//   in (x + width can't overflow there) and an Area type
+//   that width * height always fits in: u64 for the 32 bit
+//   integers, u128 for the 64 bit ones and f64 for floats.
+// - Converting between types is always explicit (cast)
+//   and only succeeds when the value is represented
+//   exactly, so 2.5 never quietly becomes 2 and 300 never
+//   quietly wraps around.
+//
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+
+pub trait Number:
+    Copy
+    + PartialOrd
+    + Default
+    + fmt::Debug
+    + fmt::Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+{
+    type Wide: Copy + PartialOrd + fmt::Debug + Add<Output = Self::Wide> + Sub<Output = Self::Wide>;
+    type Area: Copy + PartialOrd + Default + fmt::Debug + fmt::Display + Add<Output = Self::Area> + Mul<Output = Self::Area>;
+
+    const ZERO: Self;
+    const ONE: Self;
+    const MIN: Self;
+    const MAX: Self;
+
+    fn widen(self) -> Self::Wide;
+
+    // Back from the wide type, clamped to MIN..=MAX
+    //
+    fn narrow(wide: Self::Wide) -> Self;
+
+    // Only meant for values that are not negative
+    //
+    fn to_area(self) -> Self::Area;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn saturating_add(self, other: Self) -> Self;
+    fn saturating_sub(self, other: Self) -> Self;
+    fn saturating_mul(self, other: Self) -> Self;
+
+    // The value as a whole number, if it is one
+    //
+    fn to_i128(self) -> Option<i128>;
+    fn from_i128(value: i128) -> Option<Self>;
+    fn to_f64(self) -> f64;
+    fn from_f64(value: f64) -> Option<Self>;
+
+    // The same value as another type, or None if that type
+    // can't hold it exactly
+    //
+    fn cast<U: Number>(self) -> Option<U> {
+        match self.to_i128() {
+            Some(whole) => U::from_i128(whole),
+            None => U::from_f64(self.to_f64()),
+        }
+    }
+}
+
+// Floats with a magnitude below this convert to i128 exactly
+//
+const WHOLE_LIMIT: f64 = 1e36;
+
+macro_rules! integer {
+    ($($t:ty => $wide:ty, $area:ty;)*) => {
+        $(
+            impl Number for $t {
+                type Wide = $wide;
+                type Area = $area;
+
+                const ZERO: $t = 0;
+                const ONE: $t = 1;
+                const MIN: $t = <$t>::MIN;
+                const MAX: $t = <$t>::MAX;
+
+                fn widen(self) -> $wide {
+                    self as $wide
+                }
+
+                fn narrow(wide: $wide) -> $t {
+                    if wide < <$t>::MIN as $wide {
+                        <$t>::MIN
+                    } else if wide > <$t>::MAX as $wide {
+                        <$t>::MAX
+                    } else {
+                        wide as $t
+                    }
+                }
+
+                fn to_area(self) -> $area {
+                    self as $area
+                }
+
+                fn checked_add(self, other: $t) -> Option<$t> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: $t) -> Option<$t> {
+                    <$t>::checked_sub(self, other)
+                }
+
+                fn checked_mul(self, other: $t) -> Option<$t> {
+                    <$t>::checked_mul(self, other)
+                }
+
+                fn saturating_add(self, other: $t) -> $t {
+                    <$t>::saturating_add(self, other)
+                }
+
+                fn saturating_sub(self, other: $t) -> $t {
+                    <$t>::saturating_sub(self, other)
+                }
+
+                fn saturating_mul(self, other: $t) -> $t {
+                    <$t>::saturating_mul(self, other)
+                }
+
+                fn to_i128(self) -> Option<i128> {
+                    Some(self as i128)
+                }
+
+                fn from_i128(value: i128) -> Option<$t> {
+                    <$t>::try_from(value).ok()
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(value: f64) -> Option<$t> {
+                    if value.fract() == 0.0 && value.abs() < WHOLE_LIMIT {
+                        <$t>::from_i128(value as i128)
+                    } else {
+                        None
+                    }
+                }
+            }
+        )*
+    };
+}
+
+integer! {
+    i32 => i64, u64;
+    u32 => i64, u64;
+    i64 => i128, u128;
+    u64 => i128, u128;
+}
+
+macro_rules! float {
+    ($($t:ident;)*) => {
+        $(
+            impl Number for $t {
+                type Wide = f64;
+                type Area = f64;
+
+                const ZERO: $t = 0.0;
+                const ONE: $t = 1.0;
+                const MIN: $t = $t::MIN;
+                const MAX: $t = $t::MAX;
+
+                fn widen(self) -> f64 {
+                    self as f64
+                }
+
+                fn narrow(wide: f64) -> $t {
+                    (wide as $t).clamp($t::MIN, $t::MAX)
+                }
+
+                fn to_area(self) -> f64 {
+                    self as f64
+                }
+
+                // A float "overflows" when the result is no
+                // longer finite
+                //
+                fn checked_add(self, other: $t) -> Option<$t> {
+                    finite(self + other)
+                }
+
+                fn checked_sub(self, other: $t) -> Option<$t> {
+                    finite(self - other)
+                }
+
+                fn checked_mul(self, other: $t) -> Option<$t> {
+                    finite(self * other)
+                }
+
+                fn saturating_add(self, other: $t) -> $t {
+                    (self + other).clamp($t::MIN, $t::MAX)
+                }
+
+                fn saturating_sub(self, other: $t) -> $t {
+                    (self - other).clamp($t::MIN, $t::MAX)
+                }
+
+                fn saturating_mul(self, other: $t) -> $t {
+                    (self * other).clamp($t::MIN, $t::MAX)
+                }
+
+                fn to_i128(self) -> Option<i128> {
+                    if self.fract() == 0.0 && (self as f64).abs() < WHOLE_LIMIT {
+                        Some(self as i128)
+                    } else {
+                        None
+                    }
+                }
+
+                fn from_i128(value: i128) -> Option<$t> {
+                    let float = value as $t;
+                    if (float as f64).abs() < WHOLE_LIMIT && float as i128 == value {
+                        Some(float)
+                    } else {
+                        None
+                    }
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(value: f64) -> Option<$t> {
+                    let float = value as $t;
+                    if float as f64 == value {
+                        Some(float)
+                    } else {
+                        None
+                    }
+                }
+            }
+        )*
+    };
+}
+
+float! {
+    f32;
+    f64;
+}
+
+fn finite<T: Into<f64> + Copy>(value: T) -> Option<T> {
+    if value.into().is_finite() {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn narrow_clamps_to_the_range() {
+        assert_eq!(i32::narrow(i64::MAX), i32::MAX);
+        assert_eq!(i32::narrow(-5_000_000_000), i32::MIN);
+        assert_eq!(u32::narrow(-1), 0);
+        assert_eq!(u64::narrow(1 << 70), u64::MAX);
+        assert_eq!(f32::narrow(1e300), f32::MAX);
+        assert_eq!(i32::narrow(42), 42);
+    }
+
+    #[test]
+    fn casts_are_exact_or_nothing() {
+        assert_eq!(300i32.cast::<u32>(), Some(300));
+        assert_eq!((-1i32).cast::<u32>(), None);
+        assert_eq!(u32::MAX.cast::<i32>(), None);
+        assert_eq!(u64::MAX.cast::<i64>(), None);
+        assert_eq!(2.0f64.cast::<i32>(), Some(2));
+        assert_eq!(2.5f64.cast::<i32>(), None);
+        assert_eq!(f64::NAN.cast::<i64>(), None);
+        assert_eq!(f64::INFINITY.cast::<u32>(), None);
+        assert_eq!(1e20f64.cast::<i64>(), None);
+        assert_eq!(0.5f64.cast::<f32>(), Some(0.5));
+        assert_eq!(0.1f64.cast::<f32>(), None);
+        assert_eq!(((1u64 << 53) + 1).cast::<f64>(), None);
+        assert_eq!((1u64 << 60).cast::<f64>(), Some((1u64 << 60) as f64));
+    }
+
+    #[test]
+    fn float_overflow_is_not_finite() {
+        assert_eq!(Number::checked_mul(f64::MAX, 2.0), None);
+        assert_eq!(Number::checked_add(1.5f32, 2.0), Some(3.5));
+        assert_eq!(Number::saturating_mul(f64::MAX, -2.0), f64::MIN);
+    }
+}
@@ -0,0 +1,486 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - pack() places rectangles onto as many sheets of one
+//   size as it takes, opening a new sheet whenever an
+//   item doesn't fit on any sheet so far. Items that
+//   wouldn't fit even on an empty sheet are left over.
+// - Every "does it fit here?" question is asked with
+//   Rectangle::can_hold_with, using Fit::Inclusive, or
+//   Fit::AllowRotation when rotation is allowed.
+// - Items are packed biggest first (longest side, then
+//   area), which suits all three algorithms:
+//     - Shelf: rows of items, each as tall as the first
+//       item on it. Quick, but wastes the space above
+//       shorter items.
+//     - Guillotine: keeps a list of free rectangles and
+//       splits the one an item goes into in two, the way
+//       a guillotine cut would.
+//     - MaxRects: keeps every maximal free rectangle, so
+//       no free space is ever lost to a bad split. The
+//       slowest and usually the tightest.
+// - Only the sizes of the items and the sheet matter.
+//   Placements are positioned on their sheet, with the
+//   sheet's top left corner at (0, 0).
+//
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::fit::{Fit, Orientation};
+use crate::rectangle::Rectangle;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    Shelf,
+    Guillotine,
+    #[default]
+    MaxRects,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 3] = [Algorithm::Shelf, Algorithm::Guillotine, Algorithm::MaxRects];
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Algorithm, String> {
+        match s.to_lowercase().as_str() {
+            "shelf" => Ok(Algorithm::Shelf),
+            "guillotine" => Ok(Algorithm::Guillotine),
+            "maxrects" | "max-rects" => Ok(Algorithm::MaxRects),
+            _ => Err(format!("unknown algorithm '{}' (expected shelf, guillotine or maxrects)", s)),
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Algorithm::Shelf => "shelf",
+            Algorithm::Guillotine => "guillotine",
+            Algorithm::MaxRects => "maxrects",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Options {
+    pub algorithm: Algorithm,
+    pub rotation: bool,
+}
+
+impl Options {
+    fn fit(&self) -> Fit<u32> {
+        if self.rotation {
+            Fit::AllowRotation
+        } else {
+            Fit::Inclusive
+        }
+    }
+}
+
+// Where item number `item` (an index into the items that
+// were packed) ended up
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    pub item: usize,
+    pub sheet: usize,
+    pub rect: Rectangle<u32>,
+    pub orientation: Orientation,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Packing {
+    pub sheet: Rectangle<u32>,
+    pub sheets: usize,
+    pub placements: Vec<Placement>,
+    pub unplaced: Vec<usize>,
+}
+
+impl Packing {
+    pub fn on_sheet(&self, sheet: usize) -> impl Iterator<Item = &Placement> {
+        self.placements.iter().filter(move |p| p.sheet == sheet)
+    }
+
+    pub fn used_area(&self) -> u64 {
+        self.placements.iter().map(|p| p.rect.area()).sum()
+    }
+
+    // The share of the sheets used, as a percentage
+    //
+    pub fn utilisation(&self) -> f64 {
+        let total = self.sheet.area() * self.sheets as u64;
+        if total == 0 {
+            0.0
+        } else {
+            self.used_area() as f64 * 100.0 / total as f64
+        }
+    }
+}
+
+pub fn pack(sheet: Rectangle<u32>, items: &[Rectangle<u32>], options: Options) -> Packing {
+    let sheet = Rectangle::new(sheet.width, sheet.height);
+    let fit = options.fit();
+
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by_key(|&i| {
+        let item = &items[i];
+        std::cmp::Reverse((item.width.max(item.height), item.area()))
+    });
+
+    let mut bins: Vec<Box<dyn Bin>> = Vec::new();
+    let mut placements = Vec::new();
+    let mut unplaced = Vec::new();
+
+    for i in order {
+        let item = &items[i];
+        if sheet.can_hold_with(item, fit).is_none() {
+            unplaced.push(i);
+            continue;
+        }
+
+        let mut placed = None;
+        for (n, bin) in bins.iter_mut().enumerate() {
+            if let Some(found) = bin.insert(item, options.rotation) {
+                placed = Some((n, found));
+                break;
+            }
+        }
+
+        let (n, (rect, orientation)) = match placed {
+            Some(placed) => placed,
+            None => {
+                let mut bin = new_bin(options.algorithm, sheet);
+                let found = bin
+                    .insert(item, options.rotation)
+                    .expect("an item that fits the sheet fits an empty sheet");
+                bins.push(bin);
+                (bins.len() - 1, found)
+            }
+        };
+        placements.push(Placement {
+            item: i,
+            sheet: n,
+            rect,
+            orientation,
+        });
+    }
+
+    placements.sort_by_key(|p| (p.sheet, p.item));
+    unplaced.sort_unstable();
+    Packing {
+        sheet,
+        sheets: bins.len(),
+        placements,
+        unplaced,
+    }
+}
+
+// One sheet being filled by one of the algorithms
+//
+trait Bin {
+    fn insert(&mut self, item: &Rectangle<u32>, rotation: bool) -> Option<(Rectangle<u32>, Orientation)>;
+}
+
+fn new_bin(algorithm: Algorithm, sheet: Rectangle<u32>) -> Box<dyn Bin> {
+    match algorithm {
+        Algorithm::Shelf => Box::new(ShelfBin {
+            sheet,
+            shelves: Vec::new(),
+        }),
+        Algorithm::Guillotine => Box::new(GuillotineBin { free: vec![sheet] }),
+        Algorithm::MaxRects => Box::new(MaxRectsBin { free: vec![sheet] }),
+    }
+}
+
+// The item's size turned the given way, placed at (x, y)
+//
+fn place(item: &Rectangle<u32>, orientation: Orientation, x: u32, y: u32) -> Rectangle<u32> {
+    let size = match orientation {
+        Orientation::Upright => *item,
+        Orientation::Rotated => item.rotate(),
+    };
+    Rectangle::at(x, y, size.width, size.height)
+}
+
+// The ways round an item may go, best first when `flat`
+// asks for the lower one
+//
+fn orientations(item: &Rectangle<u32>, rotation: bool, flat: bool) -> Vec<Orientation> {
+    match (rotation, flat && item.height > item.width) {
+        (false, _) => vec![Orientation::Upright],
+        (true, false) => vec![Orientation::Upright, Orientation::Rotated],
+        (true, true) => vec![Orientation::Rotated, Orientation::Upright],
+    }
+}
+
+struct Shelf {
+    y: u32,
+    height: u32,
+    used: u32,
+}
+
+struct ShelfBin {
+    sheet: Rectangle<u32>,
+    shelves: Vec<Shelf>,
+}
+
+impl Bin for ShelfBin {
+    fn insert(&mut self, item: &Rectangle<u32>, rotation: bool) -> Option<(Rectangle<u32>, Orientation)> {
+        let fit = Options { rotation, ..Options::default() }.fit();
+
+        for shelf in &mut self.shelves {
+            let free = Rectangle::at(shelf.used, shelf.y, self.sheet.width - shelf.used, shelf.height);
+            if let Some(orientation) = free.can_hold_with(item, fit) {
+                let rect = place(item, orientation, shelf.used, shelf.y);
+                shelf.used += rect.width;
+                return Some((rect, orientation));
+            }
+        }
+
+        // A new shelf goes on top of the last one, with the
+        // item lying flat so the shelf stays low
+        //
+        let top = self.shelves.last().map_or(0, |shelf| shelf.y + shelf.height);
+        let free = Rectangle::at(0, top, self.sheet.width, self.sheet.height - top);
+        for orientation in orientations(item, rotation, true) {
+            let rect = place(item, orientation, 0, top);
+            if free.can_hold_with(&rect, Fit::Inclusive).is_some() {
+                self.shelves.push(Shelf {
+                    y: top,
+                    height: rect.height,
+                    used: rect.width,
+                });
+                return Some((rect, orientation));
+            }
+        }
+        None
+    }
+}
+
+struct GuillotineBin {
+    free: Vec<Rectangle<u32>>,
+}
+
+impl Bin for GuillotineBin {
+    // Best area fit: the free rectangle that leaves the
+    // least over
+    //
+    fn insert(&mut self, item: &Rectangle<u32>, rotation: bool) -> Option<(Rectangle<u32>, Orientation)> {
+        let fit = Options { rotation, ..Options::default() }.fit();
+        let (index, orientation) = self
+            .free
+            .iter()
+            .enumerate()
+            .filter_map(|(i, free)| free.can_hold_with(item, fit).map(|o| (i, o)))
+            .min_by_key(|&(i, _)| self.free[i].area() - item.area())?;
+
+        let free = self.free.swap_remove(index);
+        let rect = place(item, orientation, free.x, free.y);
+
+        // Cut along the shorter leftover side, so the bigger
+        // piece stays as big as possible
+        //
+        let right_width = free.width - rect.width;
+        let below_height = free.height - rect.height;
+        let (right, below) = if right_width < below_height {
+            (
+                Rectangle::at(rect.x + rect.width, free.y, right_width, rect.height),
+                Rectangle::at(free.x, rect.y + rect.height, free.width, below_height),
+            )
+        } else {
+            (
+                Rectangle::at(rect.x + rect.width, free.y, right_width, free.height),
+                Rectangle::at(free.x, rect.y + rect.height, rect.width, below_height),
+            )
+        };
+        self.free.extend([right, below].iter().filter(|r| !r.is_empty()));
+
+        Some((rect, orientation))
+    }
+}
+
+struct MaxRectsBin {
+    free: Vec<Rectangle<u32>>,
+}
+
+impl Bin for MaxRectsBin {
+    // Best short side fit: the free rectangle and
+    // orientation that leave the thinnest sliver
+    //
+    fn insert(&mut self, item: &Rectangle<u32>, rotation: bool) -> Option<(Rectangle<u32>, Orientation)> {
+        let mut best: Option<((u32, u32), Rectangle<u32>, Orientation)> = None;
+        for free in &self.free {
+            for orientation in orientations(item, rotation, false) {
+                let rect = place(item, orientation, free.x, free.y);
+                if free.can_hold_with(&rect, Fit::Inclusive).is_none() {
+                    continue;
+                }
+                let (dw, dh) = (free.width - rect.width, free.height - rect.height);
+                let score = (dw.min(dh), dw.max(dh));
+                if best.is_none_or(|(best, _, _)| score < best) {
+                    best = Some((score, rect, orientation));
+                }
+            }
+        }
+
+        let (_, rect, orientation) = best?;
+        self.split(&rect);
+        Some((rect, orientation))
+    }
+}
+
+impl MaxRectsBin {
+    // Every free rectangle the placed one overlaps is
+    // replaced by the (up to four) maximal pieces around it,
+    // then any piece inside another is dropped
+    //
+    fn split(&mut self, placed: &Rectangle<u32>) {
+        let mut free = Vec::with_capacity(self.free.len() + 4);
+        for f in &self.free {
+            if !f.intersects(placed) {
+                free.push(*f);
+                continue;
+            }
+            let (f_right, f_bottom) = (f.x + f.width, f.y + f.height);
+            let (p_right, p_bottom) = (placed.x + placed.width, placed.y + placed.height);
+            let pieces = [
+                Rectangle::at(f.x, f.y, placed.x.saturating_sub(f.x), f.height),
+                Rectangle::at(p_right, f.y, f_right.saturating_sub(p_right), f.height),
+                Rectangle::at(f.x, f.y, f.width, placed.y.saturating_sub(f.y)),
+                Rectangle::at(f.x, p_bottom, f.width, f_bottom.saturating_sub(p_bottom)),
+            ];
+            free.extend(pieces.iter().filter(|r| !r.is_empty()));
+        }
+
+        let mut kept: Vec<Rectangle<u32>> = Vec::with_capacity(free.len());
+        for (i, f) in free.iter().enumerate() {
+            let covered = free
+                .iter()
+                .enumerate()
+                .any(|(j, other)| j != i && other.contains(f) && (other != f || j < i));
+            if !covered {
+                kept.push(*f);
+            }
+        }
+        self.free = kept;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sizes(list: &[(u32, u32)]) -> Vec<Rectangle<u32>> {
+        list.iter().map(|&(w, h)| Rectangle::new(w, h)).collect()
+    }
+
+    fn options(algorithm: Algorithm, rotation: bool) -> Options {
+        Options { algorithm, rotation }
+    }
+
+    // Placements lie on their sheet, keep the item's size
+    // and never overlap
+    //
+    fn check(packing: &Packing, items: &[Rectangle<u32>]) {
+        for p in &packing.placements {
+            assert!(packing.sheet.contains(&p.rect), "{:?} is off the sheet", p);
+            let item = items[p.item];
+            let size = match p.orientation {
+                Orientation::Upright => item,
+                Orientation::Rotated => item.rotate(),
+            };
+            assert_eq!((p.rect.width, p.rect.height), (size.width, size.height));
+        }
+        for (i, a) in packing.placements.iter().enumerate() {
+            for b in &packing.placements[i + 1..] {
+                assert!(a.sheet != b.sheet || !a.rect.intersects(&b.rect), "{:?} overlaps {:?}", a, b);
+            }
+        }
+        assert_eq!(packing.placements.len() + packing.unplaced.len(), items.len());
+    }
+
+    #[test]
+    fn four_quarters_fill_a_sheet() {
+        let items = sizes(&[(5, 5), (5, 5), (5, 5), (5, 5)]);
+        for &algorithm in &Algorithm::ALL {
+            let packing = pack(Rectangle::new(10, 10), &items, options(algorithm, false));
+            check(&packing, &items);
+            assert_eq!(packing.sheets, 1, "{}", algorithm);
+            assert_eq!(packing.utilisation(), 100.0);
+        }
+    }
+
+    #[test]
+    fn overflow_opens_more_sheets() {
+        let items = sizes(&[(6, 6), (6, 6), (6, 6)]);
+        for &algorithm in &Algorithm::ALL {
+            let packing = pack(Rectangle::new(10, 10), &items, options(algorithm, false));
+            check(&packing, &items);
+            assert_eq!(packing.sheets, 3);
+            assert_eq!(packing.on_sheet(2).count(), 1);
+            assert_eq!(packing.utilisation(), 36.0);
+        }
+    }
+
+    #[test]
+    fn rotation_lets_long_items_in() {
+        let items = sizes(&[(2, 10), (10, 2)]);
+        for &algorithm in &Algorithm::ALL {
+            let upright = pack(Rectangle::new(10, 4), &items, options(algorithm, false));
+            assert_eq!(upright.unplaced, vec![0]);
+
+            let rotated = pack(Rectangle::new(10, 4), &items, options(algorithm, true));
+            check(&rotated, &items);
+            assert!(rotated.unplaced.is_empty());
+            assert_eq!(rotated.sheets, 1);
+            assert_eq!(rotated.placements[0].orientation, Orientation::Rotated);
+        }
+    }
+
+    #[test]
+    fn items_bigger_than_the_sheet_are_left_over() {
+        let items = sizes(&[(3, 3), (11, 1), (1, 1)]);
+        let packing = pack(Rectangle::new(10, 10), &items, Options::default());
+        check(&packing, &items);
+        assert_eq!(packing.unplaced, vec![1]);
+        assert_eq!(packing.used_area(), 10);
+    }
+
+    #[test]
+    fn nothing_to_pack() {
+        let packing = pack(Rectangle::new(10, 10), &[], Options::default());
+        assert_eq!(packing.sheets, 0);
+        assert_eq!(packing.utilisation(), 0.0);
+    }
+
+    #[test]
+    fn maxrects_uses_space_a_shelf_wastes() {
+        // One tall item and many small ones: shelves waste
+        // the space beside the tall item's row
+        //
+        let mut list = vec![(4, 10)];
+        list.extend(std::iter::repeat_n((3, 3), 6));
+        let items = sizes(&list);
+
+        let shelf = pack(Rectangle::new(10, 10), &items, options(Algorithm::Shelf, false));
+        let maxrects = pack(Rectangle::new(10, 10), &items, options(Algorithm::MaxRects, false));
+        check(&shelf, &items);
+        check(&maxrects, &items);
+        assert_eq!(maxrects.sheets, 1);
+        assert!(shelf.sheets > maxrects.sheets);
+    }
+
+    #[test]
+    fn algorithm_names() {
+        for &algorithm in &Algorithm::ALL {
+            assert_eq!(algorithm.to_string().parse::<Algorithm>(), Ok(algorithm));
+        }
+        assert_eq!("Max-Rects".parse::<Algorithm>(), Ok(Algorithm::MaxRects));
+        assert!("tetris".parse::<Algorithm>().is_err());
+    }
+}
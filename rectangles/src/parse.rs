@@ -0,0 +1,387 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - A Rectangle is written as WxH, like 30x50, with
+//   @X,Y on the end when it isn't at the origin, like
+//   30x50@10,20. Display and FromStr are a pair:
+//   whatever Display writes, FromStr reads back as the
+//   same Rectangle.
+// - Lists of rectangles come in three formats:
+//     text  WxH separated by spaces, commas or new lines
+//           (a comma straight after @X is the origin's)
+//     json  an array of "WxH" strings and/or objects
+//           with width, height and optional x and y
+//     csv   rows under a header naming the columns
+//           width, height and optionally x and y
+// - Errors say where the problem is as a row and a
+//   column, both counted from 1. In text and JSON the
+//   row is the line and the column is the character; in
+//   CSV the column is the field. Rectangles given as
+//   command line arguments count as a row each.
+//
+
+use std::error::Error;
+use std::fmt;
+use std::iter;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::number::Number;
+use crate::rectangle::Rectangle;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub row: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(row: usize, column: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            row,
+            column,
+            message: message.into(),
+        }
+    }
+
+    // Moves an error found inside one word of a longer text
+    // to where that word is
+    //
+    fn at(self, row: usize, column: usize) -> ParseError {
+        ParseError::new(row, column + self.column - 1, self.message)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "row {}, column {}: {}", self.row, self.column, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InputFormat {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for InputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<InputFormat, String> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(InputFormat::Text),
+            "json" => Ok(InputFormat::Json),
+            "csv" => Ok(InputFormat::Csv),
+            _ => Err(format!("unknown input format '{}' (expected text, json or csv)", s)),
+        }
+    }
+}
+
+impl fmt::Display for InputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            InputFormat::Text => "text",
+            InputFormat::Json => "json",
+            InputFormat::Csv => "csv",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl<T: Number> fmt::Display for Rectangle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)?;
+        if self.x != T::ZERO || self.y != T::ZERO {
+            write!(f, "@{},{}", self.x, self.y)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T> FromStr for Rectangle<T>
+where
+    T: Number + FromStr,
+    T::Err: fmt::Display,
+{
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Rectangle<T>, ParseError> {
+        let expected = || ParseError::new(1, 1, format!("'{}' is not a size like 30x50", text));
+        let (size, origin) = match text.split_once('@') {
+            Some((size, origin)) => (size, Some(origin)),
+            None => (text, None),
+        };
+        let (width, height) = size.split_once(['x', 'X']).ok_or_else(expected)?;
+
+        // Each number, with the column it starts at
+        //
+        let number = |s: &str, column: usize| {
+            s.parse::<T>()
+                .map_err(|e| ParseError::new(1, column, format!("'{}' in '{}': {}", s, text, e)))
+        };
+        let mut rect = Rectangle::new(number(width, 1)?, number(height, width.chars().count() + 2)?);
+        if let Some(origin) = origin {
+            let column = size.chars().count() + 2;
+            let (x, y) = origin
+                .split_once(',')
+                .ok_or_else(|| ParseError::new(1, column, format!("'{}' needs an origin like @10,20", text)))?;
+            rect.x = number(x, column)?;
+            rect.y = number(y, column + x.chars().count() + 1)?;
+        }
+        Ok(rect)
+    }
+}
+
+// In JSON a Rectangle is written the way Display writes it,
+// and read either that way or as an object
+//
+impl<T: Number> Serialize for Rectangle<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Sides<T> {
+    #[serde(default)]
+    x: T,
+    #[serde(default)]
+    y: T,
+    width: T,
+    height: T,
+}
+
+impl<T> From<Sides<T>> for Rectangle<T> {
+    fn from(sides: Sides<T>) -> Rectangle<T> {
+        Rectangle {
+            x: sides.x,
+            y: sides.y,
+            width: sides.width,
+            height: sides.height,
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Rectangle<T>
+where
+    T: Number + FromStr + Deserialize<'de>,
+    T::Err: fmt::Display,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Rectangle<T>, D::Error> {
+        struct RectangleVisitor<T>(PhantomData<T>);
+
+        impl<'de, T> Visitor<'de> for RectangleVisitor<T>
+        where
+            T: Number + FromStr + Deserialize<'de>,
+            T::Err: fmt::Display,
+        {
+            type Value = Rectangle<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a size like \"30x50\" or an object with a width and height")
+            }
+
+            fn visit_str<E: de::Error>(self, text: &str) -> Result<Rectangle<T>, E> {
+                text.parse().map_err(|e: ParseError| E::custom(e.message))
+            }
+
+            fn visit_map<M: MapAccess<'de>>(self, map: M) -> Result<Rectangle<T>, M::Error> {
+                Sides::deserialize(de::value::MapAccessDeserializer::new(map)).map(Rectangle::from)
+            }
+        }
+
+        deserializer.deserialize_any(RectangleVisitor(PhantomData))
+    }
+}
+
+pub fn parse<T>(text: &str, format: InputFormat) -> Result<Vec<Rectangle<T>>, ParseError>
+where
+    T: Number + FromStr + for<'de> Deserialize<'de>,
+    T::Err: fmt::Display,
+{
+    match format {
+        InputFormat::Text => parse_text(text),
+        InputFormat::Json => parse_json(text),
+        InputFormat::Csv => parse_csv(text),
+    }
+}
+
+pub fn parse_text<T>(text: &str) -> Result<Vec<Rectangle<T>>, ParseError>
+where
+    T: Number + FromStr,
+    T::Err: fmt::Display,
+{
+    let mut rects = Vec::new();
+    for (row, line) in text.lines().enumerate() {
+        let mut word = String::new();
+        let mut start = 0;
+        for (i, c) in line.chars().chain(iter::once(' ')).enumerate() {
+            // A comma straight after @X is part of the origin
+            //
+            let in_origin = word.split_once('@').is_some_and(|(_, origin)| !origin.contains(','));
+            if c.is_whitespace() || (c == ',' && !in_origin) {
+                if !word.is_empty() {
+                    rects.push(word.parse().map_err(|e: ParseError| e.at(row + 1, start + 1))?);
+                    word.clear();
+                }
+            } else {
+                if word.is_empty() {
+                    start = i;
+                }
+                word.push(c);
+            }
+        }
+    }
+    Ok(rects)
+}
+
+// One rectangle per argument
+//
+pub fn parse_args<T, S>(args: &[S]) -> Result<Vec<Rectangle<T>>, ParseError>
+where
+    T: Number + FromStr,
+    T::Err: fmt::Display,
+    S: AsRef<str>,
+{
+    args.iter()
+        .enumerate()
+        .map(|(i, arg)| arg.as_ref().parse().map_err(|e: ParseError| e.at(i + 1, 1)))
+        .collect()
+}
+
+pub fn parse_json<T>(text: &str) -> Result<Vec<Rectangle<T>>, ParseError>
+where
+    T: Number + FromStr + for<'de> Deserialize<'de>,
+    T::Err: fmt::Display,
+{
+    serde_json::from_str(text).map_err(|e| {
+        // serde_json puts the position on the end of the
+        // message, and we show it at the start instead
+        //
+        let message = e.to_string();
+        let message = message.rsplit_once(" at line ").map_or(message.as_str(), |(m, _)| m);
+        ParseError::new(e.line(), e.column(), message)
+    })
+}
+
+pub fn parse_csv<T>(text: &str) -> Result<Vec<Rectangle<T>>, ParseError>
+where
+    T: Number + for<'de> Deserialize<'de>,
+{
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(text.as_bytes());
+    let mut rects = Vec::new();
+    for sides in reader.deserialize::<Sides<T>>() {
+        rects.push(sides.map_err(csv_error)?.into());
+    }
+    Ok(rects)
+}
+
+fn csv_error(e: csv::Error) -> ParseError {
+    let row = e.position().map_or(1, |p| p.line() as usize);
+    match e.kind() {
+        csv::ErrorKind::Deserialize { err, .. } => {
+            let column = err.field().map_or(1, |field| field as usize + 1);
+            ParseError::new(row, column, err.kind().to_string())
+        }
+        csv::ErrorKind::UnequalLengths { expected_len, len, .. } => {
+            let column = (*len).min(*expected_len) as usize + 1;
+            ParseError::new(row, column, "this row has a different number of fields to the header")
+        }
+        _ => ParseError::new(row, 1, e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_and_from_str() {
+        let at_origin: Rectangle<u32> = Rectangle::new(30, 50);
+        assert_eq!(at_origin.to_string(), "30x50");
+        let moved: Rectangle = Rectangle::at(-10, 20, 3, 4);
+        assert_eq!(moved.to_string(), "3x4@-10,20");
+        let float = Rectangle::at(0.5, 0.0, 1.25, 2.0);
+        assert_eq!(float.to_string(), "1.25x2@0.5,0");
+
+        assert_eq!("30x50".parse(), Ok(at_origin));
+        assert_eq!("30X50".parse(), Ok(at_origin));
+        assert_eq!("3x4@-10,20".parse(), Ok(moved));
+        assert_eq!("1.25x2@0.5,0".parse(), Ok(float));
+    }
+
+    #[test]
+    fn errors_point_at_the_problem() {
+        let error = |text: &str| text.parse::<Rectangle<u32>>().unwrap_err();
+        assert_eq!(error("30").to_string(), "row 1, column 1: '30' is not a size like 30x50");
+        assert_eq!(error("30x-5").column, 4);
+        assert_eq!(error("30x5@1").column, 6);
+        assert_eq!(error("30x5@1,y").column, 8);
+        assert!(error("30x-5").message.starts_with("'-5' in '30x-5': invalid digit"));
+    }
+
+    #[test]
+    fn text_lists() {
+        let rects = parse_text::<u32>("1x2, 3x4\n\n5x6 7x8@1,1,9x9\n").unwrap();
+        assert_eq!(rects.len(), 5);
+        assert_eq!(rects[3], Rectangle::at(1, 1, 7, 8));
+        assert_eq!(rects[4], Rectangle::new(9, 9));
+
+        let e = parse_text::<u32>("1x2\n3x4, 5y6").unwrap_err();
+        assert_eq!((e.row, e.column), (2, 6));
+        let e = parse_text::<u32>("1x2\n3x4  5xz").unwrap_err();
+        assert_eq!((e.row, e.column), (2, 8));
+    }
+
+    #[test]
+    fn argument_lists() {
+        let rects = parse_args::<u32, _>(&["1x2", "3x4"]).unwrap();
+        assert_eq!(rects, [Rectangle::new(1, 2), Rectangle::new(3, 4)]);
+        let e = parse_args::<u32, _>(&["1x2", "3x"]).unwrap_err();
+        assert_eq!((e.row, e.column), (2, 3));
+    }
+
+    #[test]
+    fn json_lists() {
+        let rects = parse_json::<u32>(r#"["30x50", {"width": 10, "height": 40, "x": 5}]"#).unwrap();
+        assert_eq!(rects, [Rectangle::new(30, 50), Rectangle::at(5, 0, 10, 40)]);
+        assert_eq!(serde_json::to_string(&rects).unwrap(), r#"["30x50","10x40@5,0"]"#);
+
+        let e = parse_json::<u32>("[\n  \"30x50\",\n  {\"width\": 10}\n]").unwrap_err();
+        assert_eq!(e.row, 3);
+        assert!(e.message.contains("missing field `height`"));
+        let e = parse_json::<u32>("[\"30y50\"]").unwrap_err();
+        assert_eq!((e.row, e.column), (1, 8));
+        assert_eq!(e.message, "'30y50' is not a size like 30x50");
+    }
+
+    #[test]
+    fn csv_lists() {
+        let rects = parse_csv::<i32>("width,height,x,y\n30,50,0,0\n10, 40, -5, 5\n").unwrap();
+        assert_eq!(rects, [Rectangle::new(30, 50), Rectangle::at(-5, 5, 10, 40)]);
+        assert_eq!(parse_csv::<u32>("height,width\n1,2\n").unwrap(), [Rectangle::new(2, 1)]);
+
+        let e = parse_csv::<u32>("width,height\n1,2\n3,four\n").unwrap_err();
+        assert_eq!((e.row, e.column), (3, 2));
+        let e = parse_csv::<u32>("width,height\n1,2,3\n").unwrap_err();
+        assert_eq!((e.row, e.column), (2, 3));
+        assert!(parse_csv::<u32>("width\n1\n").is_err());
+    }
+
+    #[test]
+    fn input_format_names() {
+        for format in [InputFormat::Text, InputFormat::Json, InputFormat::Csv] {
+            assert_eq!(format.to_string().parse::<InputFormat>(), Ok(format));
+        }
+        assert!("toml".parse::<InputFormat>().is_err());
+    }
+}
@@ -0,0 +1,416 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - A Rectangle has an origin (its top left corner, with
+//   y growing downwards like on a screen) and a size.
+// - It covers the half-open ranges x..x + width and
+//   y..y + height, so two rectangles that only share an
+//   edge do not intersect, and a rectangle with a width or
+//   height of 0 (or less) is empty and contains no points.
+// - Rectangle<T> works with any Number (src/number.rs);
+//   plain `Rectangle` is Rectangle<i32>.
+// - Nothing here overflows: edges are worked out in the
+//   wide type, area() is widened, and the operations that
+//   build a new rectangle saturate at the ends of T's
+//   range, with a checked_ version for when that matters.
+//
+
+use crate::fit::Fit;
+use crate::number::Number;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Point<T = i32> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Number> Point<T> {
+    pub fn new(x: T, y: T) -> Point<T> {
+        Point { x, y }
+    }
+
+    pub fn try_cast<U: Number>(&self) -> Option<Point<U>> {
+        Some(Point::new(self.x.cast()?, self.y.cast()?))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rectangle<T = i32> {
+    pub x: T,
+    pub y: T,
+    pub width: T,
+    pub height: T,
+}
+
+impl<T: Number> Rectangle<T> {
+    // A rectangle at the origin
+    //
+    pub fn new(width: T, height: T) -> Rectangle<T> {
+        Rectangle::at(T::ZERO, T::ZERO, width, height)
+    }
+
+    pub fn at(x: T, y: T, width: T, height: T) -> Rectangle<T> {
+        Rectangle { x, y, width, height }
+    }
+
+    // The area in the wider Area type, which always has
+    // room for it
+    //
+    pub fn area(&self) -> T::Area {
+        if self.is_empty() {
+            T::Area::default()
+        } else {
+            self.width.to_area() * self.height.to_area()
+        }
+    }
+
+    // The area as a T, or None if it doesn't fit
+    //
+    pub fn checked_area(&self) -> Option<T> {
+        if self.is_empty() {
+            Some(T::ZERO)
+        } else {
+            self.width.checked_mul(self.height)
+        }
+    }
+
+    pub fn saturating_area(&self) -> T {
+        if self.is_empty() {
+            T::ZERO
+        } else {
+            self.width.saturating_mul(self.height)
+        }
+    }
+
+    // Strictly bigger on both sides; see can_hold_with in
+    // src/fit.rs for the other ways of fitting
+    //
+    pub fn can_hold(&self, other: &Rectangle<T>) -> bool {
+        self.can_hold_with(other, Fit::Strict).is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !(self.width > T::ZERO && self.height > T::ZERO)
+    }
+
+    pub fn origin(&self) -> Point<T> {
+        Point::new(self.x, self.y)
+    }
+
+    pub fn left(&self) -> T::Wide {
+        self.x.widen()
+    }
+
+    pub fn top(&self) -> T::Wide {
+        self.y.widen()
+    }
+
+    pub fn right(&self) -> T::Wide {
+        self.left() + self.width.widen()
+    }
+
+    pub fn bottom(&self) -> T::Wide {
+        self.top() + self.height.widen()
+    }
+
+    // A negative side counts as 0
+    //
+    pub fn perimeter(&self) -> T::Area {
+        let width = non_negative(self.width).to_area();
+        let height = non_negative(self.height).to_area();
+        width + width + height + height
+    }
+
+    pub fn contains_point(&self, point: Point<T>) -> bool {
+        let (x, y) = (point.x.widen(), point.y.widen());
+        self.left() <= x && x < self.right() && self.top() <= y && y < self.bottom()
+    }
+
+    // True when other lies entirely inside this rectangle.
+    // An empty rectangle is inside anything that covers
+    // its origin.
+    //
+    pub fn contains(&self, other: &Rectangle<T>) -> bool {
+        self.left() <= other.left()
+            && other.right() <= self.right()
+            && self.top() <= other.top()
+            && other.bottom() <= self.bottom()
+    }
+
+    pub fn intersects(&self, other: &Rectangle<T>) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    // The overlap of the two rectangles, or None when they
+    // don't overlap by any area
+    //
+    pub fn intersection(&self, other: &Rectangle<T>) -> Option<Rectangle<T>> {
+        let left = max(self.left(), other.left());
+        let top = max(self.top(), other.top());
+        let right = min(self.right(), other.right());
+        let bottom = min(self.bottom(), other.bottom());
+
+        if left < right && top < bottom {
+            Some(Rectangle::from_edges(left, top, right, bottom))
+        } else {
+            None
+        }
+    }
+
+    // The smallest rectangle that covers both (their
+    // bounding box). Empty rectangles don't add anything,
+    // and two of them give an empty one at the smaller
+    // origin. A box too big for T is capped at T::MAX.
+    //
+    pub fn union(&self, other: &Rectangle<T>) -> Rectangle<T> {
+        match (self.is_empty(), other.is_empty()) {
+            (true, true) => return Rectangle::at(min(self.x, other.x), min(self.y, other.y), T::ZERO, T::ZERO),
+            (false, true) => return *self,
+            (true, false) => return *other,
+            (false, false) => {}
+        }
+
+        Rectangle::from_edges(
+            min(self.left(), other.left()),
+            min(self.top(), other.top()),
+            max(self.right(), other.right()),
+            max(self.bottom(), other.bottom()),
+        )
+    }
+
+    // The bounding box of any number of rectangles, or None
+    // for none at all
+    //
+    pub fn bounding_box<'a, I>(rectangles: I) -> Option<Rectangle<T>>
+    where
+        I: IntoIterator<Item = &'a Rectangle<T>>,
+        T: 'a,
+    {
+        rectangles.into_iter().fold(None, |bounds, r| match bounds {
+            Some(bounds) => Some(r.union(&bounds)),
+            None => Some(*r),
+        })
+    }
+
+    // Moves the rectangle, stopping at the ends of T's range
+    //
+    pub fn translate(&self, dx: T, dy: T) -> Rectangle<T> {
+        Rectangle {
+            x: self.x.saturating_add(dx),
+            y: self.y.saturating_add(dy),
+            ..*self
+        }
+    }
+
+    pub fn checked_translate(&self, dx: T, dy: T) -> Option<Rectangle<T>> {
+        Some(Rectangle {
+            x: self.x.checked_add(dx)?,
+            y: self.y.checked_add(dy)?,
+            ..*self
+        })
+    }
+
+    // Scales the size, keeping the origin where it is
+    //
+    pub fn scale(&self, factor: T) -> Rectangle<T> {
+        Rectangle {
+            width: self.width.saturating_mul(factor),
+            height: self.height.saturating_mul(factor),
+            ..*self
+        }
+    }
+
+    pub fn checked_scale(&self, factor: T) -> Option<Rectangle<T>> {
+        Some(Rectangle {
+            width: self.width.checked_mul(factor)?,
+            height: self.height.checked_mul(factor)?,
+            ..*self
+        })
+    }
+
+    // Moves every edge `by` towards the middle (or away from
+    // it, for a negative `by`). A rectangle too small for
+    // that collapses to an empty one at its centre.
+    //
+    pub fn inset(&self, by: T) -> Rectangle<T> {
+        let twice = by.saturating_add(by);
+        let two = T::ONE + T::ONE;
+        let dx = min(by, non_negative(self.width) / two);
+        let dy = min(by, non_negative(self.height) / two);
+        Rectangle::at(
+            self.x.saturating_add(dx),
+            self.y.saturating_add(dy),
+            non_negative(self.width.saturating_sub(twice)),
+            non_negative(self.height.saturating_sub(twice)),
+        )
+    }
+
+    // The same rectangle with another number type, or None
+    // if any of its values can't be held exactly
+    //
+    pub fn try_cast<U: Number>(&self) -> Option<Rectangle<U>> {
+        Some(Rectangle::at(
+            self.x.cast()?,
+            self.y.cast()?,
+            self.width.cast()?,
+            self.height.cast()?,
+        ))
+    }
+
+    pub(crate) fn from_edges(left: T::Wide, top: T::Wide, right: T::Wide, bottom: T::Wide) -> Rectangle<T> {
+        Rectangle::at(T::narrow(left), T::narrow(top), T::narrow(right - left), T::narrow(bottom - top))
+    }
+}
+
+// Example of an associated function defined in a new
+// impl block. Associated functions do not take self parameters.
+//
+// Each struct is allowed to have multiple impl blocks.
+//
+impl<T: Number> Rectangle<T> {
+    pub fn square(size: T) -> Rectangle<T> {
+        Rectangle::new(size, size)
+    }
+}
+
+// PartialOrd stand-ins for Ord::min/max, since floats
+// aren't Ord
+//
+fn min<N: PartialOrd>(a: N, b: N) -> N {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+fn max<N: PartialOrd>(a: N, b: N) -> N {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+fn non_negative<T: Number>(value: T) -> T {
+    max(value, T::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edges_and_perimeter() {
+        let r: Rectangle = Rectangle::at(-5, 10, 30, 50);
+        assert_eq!((r.left(), r.top(), r.right(), r.bottom()), (-5, 10, 25, 60));
+        assert_eq!(r.perimeter(), 160u64);
+        assert_eq!(r.origin(), Point::new(-5, 10));
+        assert_eq!(Rectangle::square(3), Rectangle::at(0, 0, 3, 3));
+    }
+
+    #[test]
+    fn points_on_the_far_edges_are_outside() {
+        let r = Rectangle::at(0, 0, 10, 5);
+        assert!(r.contains_point(Point::new(0, 0)));
+        assert!(r.contains_point(Point::new(9, 4)));
+        assert!(!r.contains_point(Point::new(10, 4)));
+        assert!(!r.contains_point(Point::new(9, 5)));
+        assert!(!r.contains_point(Point::new(-1, 0)));
+        assert!(!Rectangle::at(3, 3, 0, 7).contains_point(Point::new(3, 3)));
+    }
+
+    #[test]
+    fn overlapping_and_touching_rectangles() {
+        let a = Rectangle::at(0, 0, 10, 10);
+        let b = Rectangle::at(5, -5, 10, 10);
+        assert_eq!(a.intersection(&b), Some(Rectangle::at(5, 0, 5, 5)));
+        assert!(a.intersects(&b));
+
+        let beside = Rectangle::at(10, 0, 10, 10);
+        assert_eq!(a.intersection(&beside), None);
+        assert!(!a.intersects(&beside));
+    }
+
+    #[test]
+    fn union_is_the_bounding_box() {
+        let a = Rectangle::at(0, 0, 10, 10);
+        let b = Rectangle::at(20, -5, 5, 5);
+        assert_eq!(a.union(&b), Rectangle::at(0, -5, 25, 15));
+        assert_eq!(a.union(&Rectangle::at(100, 100, 0, 0)), a);
+
+        let (e, f) = (Rectangle::at(4, 1, 0, 0), Rectangle::at(2, 3, 0, 5));
+        assert_eq!(e.union(&f), Rectangle::at(2, 1, 0, 0));
+        assert_eq!(f.union(&e), e.union(&f));
+
+        assert_eq!(Rectangle::bounding_box(&[a, b]), Some(a.union(&b)));
+        assert_eq!(Rectangle::<i32>::bounding_box(&[]), None);
+    }
+
+    #[test]
+    fn translate_scale_and_inset() {
+        let r = Rectangle::at(1, 2, 10, 6);
+        assert_eq!(r.translate(-3, 4), Rectangle::at(-2, 6, 10, 6));
+        assert_eq!(r.scale(3), Rectangle::at(1, 2, 30, 18));
+        assert_eq!(r.inset(2), Rectangle::at(3, 4, 6, 2));
+        assert_eq!(r.inset(4), Rectangle::at(5, 5, 2, 0));
+        assert_eq!(r.inset(50), Rectangle::at(6, 5, 0, 0));
+        assert_eq!(r.inset(-1), Rectangle::at(0, 1, 12, 8));
+    }
+
+    #[test]
+    fn far_edges_do_not_overflow() {
+        let r = Rectangle::at(i32::MAX, i32::MAX, i32::MAX, 1);
+        assert_eq!(r.right(), i32::MAX as i64 * 2);
+        assert!(r.contains_point(Point::new(i32::MAX, i32::MAX)));
+
+        let wide = Rectangle::at(i32::MIN, 0, 1, 1).union(&Rectangle::at(i32::MAX - 1, 0, 1, 1));
+        assert_eq!(wide, Rectangle::at(i32::MIN, 0, i32::MAX, 1));
+    }
+
+    #[test]
+    fn area_is_widened() {
+        let big = Rectangle::<u32>::new(u32::MAX, u32::MAX);
+        assert_eq!(big.area(), u32::MAX as u64 * u32::MAX as u64);
+        assert_eq!(big.checked_area(), None);
+        assert_eq!(big.saturating_area(), u32::MAX);
+
+        let huge = Rectangle::<i64>::new(i64::MAX, 2);
+        assert_eq!(huge.area(), i64::MAX as u128 * 2);
+        assert_eq!(Rectangle::new(3u64, 4).checked_area(), Some(12));
+
+        assert_eq!(Rectangle::new(1.5, 2.0).area(), 3.0);
+        assert_eq!(Rectangle::new(f32::MAX, 2.0).checked_area(), None);
+    }
+
+    #[test]
+    fn empty_rectangles_have_no_area() {
+        assert_eq!(Rectangle::new(-3, -4).area(), 0u64);
+        assert_eq!(Rectangle::new(-3, -4).checked_area(), Some(0));
+        assert_eq!(Rectangle::new(-3, 4).perimeter(), 8u64);
+        assert!(Rectangle::new(f64::NAN, 1.0).is_empty());
+    }
+
+    #[test]
+    fn moving_saturates_or_is_checked() {
+        let r = Rectangle::at(i32::MAX - 1, 0, 5, 5);
+        assert_eq!(r.translate(5, 0).x, i32::MAX);
+        assert_eq!(r.checked_translate(5, 0), None);
+        assert_eq!(r.checked_translate(1, -1), Some(Rectangle::at(i32::MAX, -1, 5, 5)));
+
+        let s = Rectangle::<u32>::new(1 << 20, 3);
+        assert_eq!(s.scale(1 << 12).width, u32::MAX);
+        assert_eq!(s.checked_scale(1 << 12), None);
+        assert_eq!(s.checked_scale(2), Some(Rectangle::new(1 << 21, 6)));
+    }
+
+    #[test]
+    fn casts_are_explicit_and_fallible() {
+        let r = Rectangle::at(-1, 2, 3, 4);
+        assert_eq!(r.try_cast::<f64>(), Some(Rectangle::at(-1.0, 2.0, 3.0, 4.0)));
+        assert_eq!(r.try_cast::<u32>(), None);
+        assert_eq!(r.translate(1, 0).try_cast::<u32>(), Some(Rectangle::at(0, 2, 3, 4)));
+        assert_eq!(Rectangle::new(0.5, 1.0).try_cast::<i32>(), None);
+        assert_eq!(Point::new(7u64, 8).try_cast::<i32>(), Some(Point::new(7, 8)));
+    }
+}
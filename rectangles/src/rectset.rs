@@ -0,0 +1,352 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - A RectSet is the area covered by a number of
+//   rectangles, which may overlap. Empty rectangles cover
+//   nothing and are never kept.
+// - union_area sweeps a line across the set from left to
+//   right, keeping a segment tree of how much of the line
+//   is covered, so overlaps are only counted once. It is
+//   O(n log n) for n rectangles.
+// - Rectangle::subtract leaves up to four pieces: a strip
+//   above the hole and one below it, both the full width,
+//   then one on each side of it between them.
+// - merged() gives the same area as a set of rectangles
+//   that don't overlap, with neighbours that line up
+//   joined, so no two of them share a whole edge. That
+//   isn't always the fewest rectangles possible (finding
+//   those is much harder) but it is usually close. It
+//   looks at every rectangle for every strip, so it is
+//   O(n^2 log n).
+// - Like the quadtree, RectSet works on Rectangle<i32>.
+//   Areas are u128, since the union of rectangles
+//   anywhere in i32 can cover more than a u64 can count.
+//
+
+use std::iter::FromIterator;
+
+use crate::number::Number;
+use crate::rectangle::{Point, Rectangle};
+
+impl<T: Number> Rectangle<T> {
+    // What's left of this rectangle with other cut out of it
+    //
+    pub fn subtract(&self, other: &Rectangle<T>) -> Vec<Rectangle<T>> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let hole = match self.intersection(other) {
+            Some(hole) => hole,
+            None => return vec![*self],
+        };
+
+        let (left, top, right, bottom) = (self.left(), self.top(), self.right(), self.bottom());
+        let mut pieces = Vec::new();
+        if hole.top() > top {
+            pieces.push(Rectangle::from_edges(left, top, right, hole.top()));
+        }
+        if hole.bottom() < bottom {
+            pieces.push(Rectangle::from_edges(left, hole.bottom(), right, bottom));
+        }
+        if hole.left() > left {
+            pieces.push(Rectangle::from_edges(left, hole.top(), hole.left(), hole.bottom()));
+        }
+        if hole.right() < right {
+            pieces.push(Rectangle::from_edges(hole.right(), hole.top(), right, hole.bottom()));
+        }
+        pieces
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RectSet {
+    rects: Vec<Rectangle>,
+}
+
+impl FromIterator<Rectangle> for RectSet {
+    fn from_iter<I: IntoIterator<Item = Rectangle>>(iter: I) -> RectSet {
+        let mut set = RectSet::new();
+        for r in iter {
+            set.insert(r);
+        }
+        set
+    }
+}
+
+impl RectSet {
+    pub fn new() -> RectSet {
+        RectSet::default()
+    }
+
+    pub fn insert(&mut self, rect: Rectangle) {
+        if !rect.is_empty() {
+            self.rects.push(rect);
+        }
+    }
+
+    pub fn rects(&self) -> &[Rectangle] {
+        &self.rects
+    }
+
+    pub fn len(&self) -> usize {
+        self.rects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    pub fn contains_point(&self, point: Point) -> bool {
+        self.rects.iter().any(|r| r.contains_point(point))
+    }
+
+    pub fn union_area(&self) -> u128 {
+        union_area(&self.rects)
+    }
+
+    // How much of region the set covers
+    //
+    pub fn area_within(&self, region: &Rectangle) -> u128 {
+        let clipped: Vec<Rectangle> = self.rects.iter().filter_map(|r| r.intersection(region)).collect();
+        union_area(&clipped)
+    }
+
+    // The same as a fraction of region, from 0 to 1. An
+    // empty region is never covered.
+    //
+    pub fn coverage(&self, region: &Rectangle) -> f64 {
+        if region.is_empty() {
+            return 0.0;
+        }
+        self.area_within(region) as f64 / region.area() as f64
+    }
+
+    pub fn subtract(&self, other: &Rectangle) -> RectSet {
+        self.rects.iter().flat_map(|r| r.subtract(other)).collect()
+    }
+
+    pub fn merged(&self) -> RectSet {
+        let mut xs: Vec<i64> = self.rects.iter().flat_map(|r| [r.left(), r.right()]).collect();
+        xs.sort_unstable();
+        xs.dedup();
+
+        // Each strip between two neighbouring xs is covered by
+        // some runs of y. A run carries on into the next strip
+        // if it's there too, and ends (becoming a rectangle)
+        // when it isn't.
+        //
+        let mut merged = RectSet::new();
+        let mut open: Vec<(i64, i64, i64)> = Vec::new();
+        for strip in xs.windows(2) {
+            let (x0, x1) = (strip[0], strip[1]);
+            let runs = covered_runs(self.rects.iter().filter(|r| r.left() <= x0 && r.right() >= x1));
+
+            let mut next = Vec::with_capacity(runs.len());
+            for (y0, y1) in runs {
+                let start = open.iter().find(|run| (run.0, run.1) == (y0, y1)).map_or(x0, |run| run.2);
+                next.push((y0, y1, start));
+            }
+            for &(y0, y1, start) in &open {
+                if !next.iter().any(|run| (run.0, run.1, run.2) == (y0, y1, start)) {
+                    merged.insert(Rectangle::from_edges(start, y0, x0, y1));
+                }
+            }
+            open = next;
+        }
+        if let Some(&end) = xs.last() {
+            for (y0, y1, start) in open {
+                merged.insert(Rectangle::from_edges(start, y0, end, y1));
+            }
+        }
+        merged
+    }
+}
+
+// The runs of y covered by some rectangles, in order, with
+// overlapping and touching ones joined up
+//
+fn covered_runs<'a, I: Iterator<Item = &'a Rectangle>>(rects: I) -> Vec<(i64, i64)> {
+    let mut spans: Vec<(i64, i64)> = rects.map(|r| (r.top(), r.bottom())).collect();
+    spans.sort_unstable();
+    let mut runs: Vec<(i64, i64)> = Vec::new();
+    for (top, bottom) in spans {
+        match runs.last_mut() {
+            Some(last) if top <= last.1 => last.1 = last.1.max(bottom),
+            _ => runs.push((top, bottom)),
+        }
+    }
+    runs
+}
+
+fn union_area(rects: &[Rectangle]) -> u128 {
+    let mut ys: Vec<i64> = rects.iter().flat_map(|r| [r.top(), r.bottom()]).collect();
+    ys.sort_unstable();
+    ys.dedup();
+    if ys.len() < 2 {
+        return 0;
+    }
+
+    // The sweep line meets each rectangle's left edge, where
+    // its y span starts being covered, and its right edge,
+    // where it stops
+    //
+    let mut events: Vec<(i64, i32, i64, i64)> = Vec::with_capacity(2 * rects.len());
+    for r in rects.iter().filter(|r| !r.is_empty()) {
+        events.push((r.left(), 1, r.top(), r.bottom()));
+        events.push((r.right(), -1, r.top(), r.bottom()));
+    }
+    events.sort_unstable();
+
+    let mut tree = Coverage::new(ys);
+    let mut area = 0u128;
+    let mut last_x = events.first().map_or(0, |e| e.0);
+    for (x, delta, top, bottom) in events {
+        area += u128::from(tree.covered()) * (x - last_x) as u128;
+        tree.update(top, bottom, delta);
+        last_x = x;
+    }
+    area
+}
+
+// A segment tree over the gaps between neighbouring ys.
+// Each node counts the spans that cover all of it, and
+// knows how much of it is covered by anything.
+//
+struct Coverage {
+    ys: Vec<i64>,
+    count: Vec<i32>,
+    covered: Vec<u64>,
+}
+
+impl Coverage {
+    fn new(ys: Vec<i64>) -> Coverage {
+        let nodes = 4 * ys.len();
+        Coverage {
+            ys,
+            count: vec![0; nodes],
+            covered: vec![0; nodes],
+        }
+    }
+
+    fn covered(&self) -> u64 {
+        self.covered[1]
+    }
+
+    fn update(&mut self, top: i64, bottom: i64, delta: i32) {
+        let from = self.ys.binary_search(&top).unwrap();
+        let to = self.ys.binary_search(&bottom).unwrap();
+        self.update_node(1, 0, self.ys.len() - 1, from, to, delta);
+    }
+
+    // Node covers the gaps from ys[lo] to ys[hi]
+    //
+    fn update_node(&mut self, node: usize, lo: usize, hi: usize, from: usize, to: usize, delta: i32) {
+        if to <= lo || hi <= from {
+            return;
+        }
+        if from <= lo && hi <= to {
+            self.count[node] += delta;
+        } else {
+            let mid = (lo + hi) / 2;
+            self.update_node(2 * node, lo, mid, from, to, delta);
+            self.update_node(2 * node + 1, mid, hi, from, to, delta);
+        }
+
+        self.covered[node] = if self.count[node] > 0 {
+            (self.ys[hi] - self.ys[lo]) as u64
+        } else if hi - lo == 1 {
+            0
+        } else {
+            self.covered[2 * node] + self.covered[2 * node + 1]
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subtracting_a_hole_leaves_four_pieces() {
+        let r = Rectangle::new(10, 10);
+        let pieces = r.subtract(&Rectangle::at(3, 4, 2, 2));
+        assert_eq!(
+            pieces,
+            [
+                Rectangle::at(0, 0, 10, 4),
+                Rectangle::at(0, 6, 10, 4),
+                Rectangle::at(0, 4, 3, 2),
+                Rectangle::at(5, 4, 5, 2),
+            ]
+        );
+        let left: u64 = pieces.iter().map(|p| p.area()).sum();
+        assert_eq!(left, 96);
+    }
+
+    #[test]
+    fn subtracting_from_the_edge() {
+        let r = Rectangle::new(10, 10);
+        assert_eq!(r.subtract(&Rectangle::at(5, -5, 20, 20)), [Rectangle::new(5, 10)]);
+        assert_eq!(r.subtract(&Rectangle::at(-1, -1, 12, 12)), []);
+        assert_eq!(r.subtract(&Rectangle::at(10, 0, 5, 5)), [r]);
+        assert_eq!(Rectangle::new(0, 5).subtract(&r), []);
+    }
+
+    #[test]
+    fn overlaps_are_counted_once() {
+        let set: RectSet = vec![Rectangle::new(10, 10), Rectangle::at(5, 5, 10, 10), Rectangle::at(2, 2, 2, 2)]
+            .into_iter()
+            .collect();
+        assert_eq!(set.union_area(), 175);
+        assert_eq!(set.area_within(&Rectangle::at(0, 0, 5, 5)), 25);
+        assert_eq!(set.coverage(&Rectangle::at(10, 0, 10, 10)), 0.25);
+        assert_eq!(RectSet::new().union_area(), 0);
+    }
+
+    #[test]
+    fn empty_rectangles_are_left_out() {
+        let set: RectSet = vec![Rectangle::new(0, 10), Rectangle::new(-3, 3), Rectangle::new(1, 1)]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn areas_wider_than_u64() {
+        // Nine tiles, each as big as a Rectangle can be
+        //
+        let starts = [i32::MIN, -1, i32::MAX - 1];
+        let set: RectSet = starts
+            .iter()
+            .flat_map(|&x| starts.iter().map(move |&y| Rectangle::at(x, y, i32::MAX, i32::MAX)))
+            .collect();
+        let side = i32::MAX as u128;
+        assert_eq!(set.union_area(), 9 * side * side);
+        assert!(set.union_area() > u128::from(u64::MAX));
+    }
+
+    #[test]
+    fn neighbours_are_merged() {
+        let set: RectSet = vec![
+            Rectangle::at(0, 0, 5, 5),
+            Rectangle::at(5, 0, 5, 5),
+            Rectangle::at(0, 5, 10, 5),
+            Rectangle::at(20, 0, 5, 5),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(set.merged().rects(), [Rectangle::new(10, 10), Rectangle::at(20, 0, 5, 5)]);
+    }
+
+    #[test]
+    fn merging_splits_overlaps() {
+        let set: RectSet = vec![Rectangle::new(10, 10), Rectangle::at(5, 5, 10, 10)].into_iter().collect();
+        let merged = set.merged();
+        assert_eq!(
+            merged.rects(),
+            [Rectangle::new(5, 10), Rectangle::at(5, 0, 5, 15), Rectangle::at(10, 5, 5, 10)]
+        );
+        let total: u64 = merged.rects().iter().map(|r| r.area()).sum();
+        assert_eq!(u128::from(total), set.union_area());
+    }
+}
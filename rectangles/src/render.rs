@@ -0,0 +1,343 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - A Drawing is a list of rectangles, each with an
+//   optional label and style, that can be written out as
+//   an SVG document or as ASCII art for the terminal.
+// - Rectangles of any Number type can go in; they are
+//   drawn with f64 coordinates.
+// - Later rectangles are drawn over earlier ones. In
+//   ASCII art every rectangle is an outline, and where
+//   two outlines cross the crossing is a '+'.
+// - ASCII art is scaled to a given number of columns.
+//   Terminal cells are about twice as tall as they are
+//   wide, so a rectangle gets half as many rows per unit
+//   as columns.
+//
+
+use std::fmt::Write;
+
+use crate::fit::Orientation;
+use crate::number::Number;
+use crate::packing::Packing;
+use crate::rectangle::Rectangle;
+
+// Fills for the rectangles of a packing, used in turn
+//
+const PALETTE: [&str; 6] = ["#8dd3c7", "#ffffb3", "#bebada", "#fb8072", "#80b1d3", "#fdb462"];
+
+// How much shorter a terminal row is than a column is wide
+//
+const ASPECT: f64 = 0.5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+    pub stroke: String,
+    pub stroke_width: f64,
+    pub fill: String,
+    pub opacity: f64,
+}
+
+impl Default for Style {
+    fn default() -> Style {
+        Style {
+            stroke: String::from("black"),
+            stroke_width: 1.0,
+            fill: String::from("none"),
+            opacity: 1.0,
+        }
+    }
+}
+
+impl Style {
+    pub fn filled(fill: &str) -> Style {
+        Style {
+            fill: String::from(fill),
+            ..Style::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+    // SVG units per unit of the rectangles
+    //
+    pub scale: f64,
+    // Space around the drawing, in SVG units
+    //
+    pub margin: f64,
+    pub labels: bool,
+    pub font_size: f64,
+}
+
+impl Default for SvgOptions {
+    fn default() -> SvgOptions {
+        SvgOptions {
+            scale: 1.0,
+            margin: 10.0,
+            labels: true,
+            font_size: 12.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub rect: Rectangle<f64>,
+    pub label: Option<String>,
+    pub style: Style,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Drawing {
+    pub items: Vec<Item>,
+}
+
+impl Drawing {
+    pub fn new() -> Drawing {
+        Drawing::default()
+    }
+
+    pub fn add<T: Number>(&mut self, rect: &Rectangle<T>, label: Option<&str>, style: Style) -> &mut Drawing {
+        self.items.push(Item {
+            rect: Rectangle::at(rect.x.to_f64(), rect.y.to_f64(), rect.width.to_f64(), rect.height.to_f64()),
+            label: label.map(String::from),
+            style,
+        });
+        self
+    }
+
+    // Every sheet of a packing, side by side with a gap
+    // between them, and the placed rectangles labelled
+    // with their item numbers (an 'r' marks a rotated one)
+    //
+    pub fn packing(packing: &Packing) -> Drawing {
+        let sheet = packing.sheet;
+        let step = sheet.width as f64 + (sheet.width as f64 / 10.0).max(1.0);
+        let sheet_style = Style {
+            stroke: String::from("gray"),
+            ..Style::filled("white")
+        };
+
+        let mut drawing = Drawing::new();
+        for n in 0..packing.sheets {
+            let dx = step * n as f64;
+            let outline = Rectangle::at(dx, 0.0, sheet.width as f64, sheet.height as f64);
+            drawing.add(&outline, None, sheet_style.clone());
+
+            for p in packing.on_sheet(n) {
+                let rect = Rectangle::at(p.rect.x as f64 + dx, p.rect.y as f64, p.rect.width as f64, p.rect.height as f64);
+                let turned = if p.orientation == Orientation::Rotated { "r" } else { "" };
+                let label = format!("{}{}", p.item + 1, turned);
+                drawing.add(&rect, Some(&label), Style::filled(PALETTE[p.item % PALETTE.len()]));
+            }
+        }
+        drawing
+    }
+
+    pub fn bounds(&self) -> Option<Rectangle<f64>> {
+        Rectangle::bounding_box(self.items.iter().map(|item| &item.rect).filter(|r| !r.is_empty()))
+    }
+
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        let bounds = self.bounds().unwrap_or_default();
+        let scale = options.scale;
+        let (width, height) = (bounds.width * scale + 2.0 * options.margin, bounds.height * scale + 2.0 * options.margin);
+        let x = |v: f64| (v - bounds.x) * scale + options.margin;
+        let y = |v: f64| (v - bounds.y) * scale + options.margin;
+
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">",
+            width, height, width, height
+        )
+        .unwrap();
+        for item in &self.items {
+            let r = &item.rect;
+            let style = &item.style;
+            writeln!(
+                svg,
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" fill-opacity=\"{}\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                x(r.x),
+                y(r.y),
+                r.width * scale,
+                r.height * scale,
+                escape(&style.fill),
+                style.opacity,
+                escape(&style.stroke),
+                style.stroke_width
+            )
+            .unwrap();
+        }
+        if options.labels {
+            for item in &self.items {
+                if let Some(label) = &item.label {
+                    let r = &item.rect;
+                    writeln!(
+                        svg,
+                        "  <text x=\"{}\" y=\"{}\" font-family=\"monospace\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+                        x(r.x + r.width / 2.0),
+                        y(r.y + r.height / 2.0),
+                        options.font_size,
+                        escape(label)
+                    )
+                    .unwrap();
+                }
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    // The drawing scaled to `columns` wide, with labels in
+    // the top left corner of their rectangles
+    //
+    pub fn to_ascii(&self, columns: usize) -> String {
+        let bounds = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return String::new(),
+        };
+        let sx = (columns.max(2) - 1) as f64 / bounds.width;
+        let sy = sx * ASPECT;
+        let col = |v: f64| ((v - bounds.x) * sx).round() as usize;
+        let row = |v: f64| ((v - bounds.y) * sy).round() as usize;
+
+        // Empty rectangles aren't in the bounds, so they
+        // aren't drawn (they might be off the grid)
+        //
+        let drawn: Vec<&Item> = self.items.iter().filter(|item| !item.rect.is_empty()).collect();
+        let mut grid = vec![vec![' '; col(bounds.x + bounds.width) + 1]; row(bounds.y + bounds.height) + 1];
+        for item in &drawn {
+            let r = &item.rect;
+            let (c0, c1) = (col(r.x), col(r.x + r.width));
+            let (r0, r1) = (row(r.y), row(r.y + r.height));
+
+            for &rr in &[r0, r1] {
+                for cell in &mut grid[rr][c0..=c1] {
+                    *cell = cross(*cell, '-');
+                }
+            }
+            for cells in &mut grid[r0..=r1] {
+                for &c in &[c0, c1] {
+                    cells[c] = cross(cells[c], '|');
+                }
+            }
+            for &(rr, c) in &[(r0, c0), (r0, c1), (r1, c0), (r1, c1)] {
+                grid[rr][c] = '+';
+            }
+        }
+
+        // Labels go on after all the outlines, and stop at
+        // the first one in their way
+        //
+        for item in &drawn {
+            let r = &item.rect;
+            let (c0, r0, r1) = (col(r.x), row(r.y), row(r.y + r.height));
+            if let (Some(label), true) = (&item.label, r0 + 1 < r1) {
+                let cells = grid[r0 + 1][c0 + 1..].iter_mut().take_while(|cell| **cell == ' ');
+                for (cell, ch) in cells.zip(label.chars()) {
+                    *cell = ch;
+                }
+            }
+        }
+
+        let mut ascii = String::new();
+        for cells in grid {
+            let line: String = cells.into_iter().collect();
+            ascii.push_str(line.trim_end());
+            ascii.push('\n');
+        }
+        ascii
+    }
+}
+
+// What a cell becomes when an edge is drawn over it
+//
+fn cross(cell: char, edge: char) -> char {
+    match (cell, edge) {
+        ('-', '|') | ('|', '-') | ('+', _) => '+',
+        _ => edge,
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_rectangle_in_ascii() {
+        let mut drawing = Drawing::new();
+        drawing.add(&Rectangle::new(10, 4), Some("box"), Style::default());
+        assert_eq!(drawing.to_ascii(11), "+---------+\n|box      |\n+---------+\n");
+    }
+
+    #[test]
+    fn crossing_outlines() {
+        let mut drawing = Drawing::new();
+        drawing.add(&Rectangle::new(8, 8), None, Style::default());
+        drawing.add(&Rectangle::at(4, 4, 8, 8), None, Style::default());
+        assert_eq!(
+            drawing.to_ascii(13),
+            "+-------+\n\
+             |       |\n\
+             |   +---+---+\n\
+             |   |   |   |\n\
+             +---+---+   |\n\
+             \x20   |       |\n\
+             \x20   +-------+\n"
+        );
+    }
+
+    #[test]
+    fn labels_are_cut_to_fit() {
+        let mut drawing = Drawing::new();
+        drawing.add(&Rectangle::new(4, 4), Some("a long label"), Style::default());
+        assert_eq!(drawing.to_ascii(5), "+---+\n|a l|\n+---+\n");
+    }
+
+    #[test]
+    fn empty_rectangles_are_left_out() {
+        let mut drawing = Drawing::new();
+        drawing.add(&Rectangle::new(10, 4), None, Style::default());
+        drawing.add(&Rectangle::at(100, 100, 0, 0), Some("x"), Style::default());
+        drawing.add(&Rectangle::at(-50, 2, 0, 3), Some("y"), Style::default());
+
+        let mut alone = Drawing::new();
+        alone.add(&Rectangle::new(10, 4), None, Style::default());
+        assert_eq!(drawing.to_ascii(20), alone.to_ascii(20));
+    }
+
+    #[test]
+    fn nothing_to_draw() {
+        assert_eq!(Drawing::new().to_ascii(40), "");
+        assert!(Drawing::new().to_svg(&SvgOptions::default()).ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn svg_escapes_labels_and_styles() {
+        let mut drawing = Drawing::new();
+        drawing.add(&Rectangle::new(1.5, 2.0), Some("<a & b>"), Style::filled("#f00"));
+        let svg = drawing.to_svg(&SvgOptions {
+            scale: 10.0,
+            margin: 0.0,
+            ..SvgOptions::default()
+        });
+        assert!(svg.contains("width=\"15\" height=\"20\""));
+        assert!(svg.contains("fill=\"#f00\""));
+        assert!(svg.contains(">&lt;a &amp; b&gt;</text>"));
+
+        let unlabelled = drawing.to_svg(&SvgOptions {
+            labels: false,
+            ..SvgOptions::default()
+        });
+        assert!(!unlabelled.contains("<text"));
+    }
+}
@@ -0,0 +1,228 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - Writes a Packing out as a text table for people, or
+//   as JSON or CSV for other programs. JSON and CSV have
+//   one record per placed rectangle; text also lists
+//   whatever couldn't be placed.
+// - Items are numbered from 1, in the order they were
+//   given.
+// - svg and ascii draw the sheets instead, using
+//   src/render.rs.
+//
+
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::fit::Orientation;
+use crate::packing::{Options, Packing};
+use crate::rectangle::Rectangle;
+use crate::render::{Drawing, SvgOptions};
+
+// How wide ascii drawings of a packing are
+//
+const ASCII_COLUMNS: usize = 78;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+    Svg,
+    Ascii,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "svg" => Ok(Format::Svg),
+            "ascii" => Ok(Format::Ascii),
+            _ => Err(format!("unknown format '{}' (expected text, json, csv, svg or ascii)", s)),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Format::Text => "text",
+            Format::Json => "json",
+            Format::Csv => "csv",
+            Format::Svg => "svg",
+            Format::Ascii => "ascii",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlacementRecord {
+    pub item: usize,
+    pub sheet: usize,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub rotated: bool,
+}
+
+pub fn records(packing: &Packing) -> Vec<PlacementRecord> {
+    packing
+        .placements
+        .iter()
+        .map(|p| PlacementRecord {
+            item: p.item + 1,
+            sheet: p.sheet + 1,
+            x: p.rect.x,
+            y: p.rect.y,
+            width: p.rect.width,
+            height: p.rect.height,
+            rotated: p.orientation == Orientation::Rotated,
+        })
+        .collect()
+}
+
+pub fn write<W: Write>(
+    packing: &Packing,
+    items: &[Rectangle<u32>],
+    options: Options,
+    format: Format,
+    output: &mut W,
+) -> io::Result<()> {
+    match format {
+        Format::Text => write_text(packing, items, options, output),
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *output, &records(packing))?;
+            writeln!(output)
+        }
+        Format::Csv => {
+            writeln!(output, "item,sheet,x,y,width,height,rotated")?;
+            for r in records(packing) {
+                writeln!(
+                    output,
+                    "{},{},{},{},{},{},{}",
+                    r.item, r.sheet, r.x, r.y, r.width, r.height, r.rotated
+                )?;
+            }
+            Ok(())
+        }
+        Format::Svg => write!(output, "{}", Drawing::packing(packing).to_svg(&SvgOptions::default())),
+        Format::Ascii => write!(output, "{}", Drawing::packing(packing).to_ascii(ASCII_COLUMNS)),
+    }
+}
+
+fn write_text<W: Write>(
+    packing: &Packing,
+    items: &[Rectangle<u32>],
+    options: Options,
+    output: &mut W,
+) -> io::Result<()> {
+    let sheet = packing.sheet;
+    for n in 0..packing.sheets {
+        writeln!(output, "Sheet {} ({}x{}):", n + 1, sheet.width, sheet.height)?;
+        writeln!(output, "  Item       Size      x      y")?;
+        for p in packing.on_sheet(n) {
+            let turned = if p.orientation == Orientation::Rotated { " (rotated)" } else { "" };
+            writeln!(
+                output,
+                "  {:>4}  {:>9}  {:>5}  {:>5}{}",
+                p.item + 1,
+                format!("{}x{}", p.rect.width, p.rect.height),
+                p.rect.x,
+                p.rect.y,
+                turned
+            )?;
+        }
+    }
+
+    writeln!(
+        output,
+        "Packed {} of {} rectangles onto {} sheet{} with {}{}: {:.1}% used.",
+        packing.placements.len(),
+        items.len(),
+        packing.sheets,
+        if packing.sheets == 1 { "" } else { "s" },
+        options.algorithm,
+        if options.rotation { " and rotation" } else { "" },
+        packing.utilisation()
+    )?;
+    for &i in &packing.unplaced {
+        writeln!(
+            output,
+            "Item {} ({}x{}) is too big for the sheet.",
+            i + 1,
+            items[i].width,
+            items[i].height
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packing::{self, Algorithm};
+
+    fn example() -> (Vec<Rectangle<u32>>, Packing, Options) {
+        let items = vec![Rectangle::new(4, 10), Rectangle::new(6, 4), Rectangle::new(20, 1)];
+        let options = Options {
+            algorithm: Algorithm::Shelf,
+            rotation: true,
+        };
+        let packing = packing::pack(Rectangle::new(10, 10), &items, options);
+        (items, packing, options)
+    }
+
+    fn written(format: Format) -> String {
+        let (items, packing, options) = example();
+        let mut output = Vec::new();
+        write(&packing, &items, options, format, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn text_table() {
+        assert_eq!(
+            written(Format::Text),
+            "Sheet 1 (10x10):\n\
+             \x20 Item       Size      x      y\n\
+             \x20    1       10x4      0      0 (rotated)\n\
+             \x20    2        6x4      0      4\n\
+             Packed 2 of 3 rectangles onto 1 sheet with shelf and rotation: 64.0% used.\n\
+             Item 3 (20x1) is too big for the sheet.\n"
+        );
+    }
+
+    #[test]
+    fn csv_rows() {
+        assert_eq!(
+            written(Format::Csv),
+            "item,sheet,x,y,width,height,rotated\n1,1,0,0,10,4,true\n2,1,0,4,6,4,false\n"
+        );
+    }
+
+    #[test]
+    fn json_records() {
+        let json: serde_json::Value = serde_json::from_str(&written(Format::Json)).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 2);
+        assert_eq!(json[0]["rotated"], true);
+        assert_eq!(json[1]["y"], 4);
+    }
+
+    #[test]
+    fn format_names() {
+        for format in [Format::Text, Format::Json, Format::Csv, Format::Svg, Format::Ascii] {
+            assert_eq!(format.to_string().parse::<Format>(), Ok(format));
+        }
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,474 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - Shape is what every shape can do. It works in f64 so
+//   that different shapes can sit together in a
+//   Vec<Box<dyn Shape>>, which is what a Scene is.
+// - Rectangle<T> is a Shape for any Number T. Its own
+//   area, perimeter and contains_point stay as they are
+//   (exact, in T); the Shape versions are for mixing it
+//   with other shapes.
+// - contains_point follows the same rule everywhere: a
+//   point on the left or top edge is inside and one on
+//   the right or bottom edge is outside, the way
+//   Rectangle::contains_point already works. Triangles and
+//   polygons get this from the crossing number test, and
+//   a circle's edge is outside.
+// - A Polygon is simple: its edges only meet their
+//   neighbours, at the corners. Polygon::new checks every
+//   pair of edges, so it is O(n^2) in the corners.
+//
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::number::Number;
+use crate::rectangle::{Point, Rectangle};
+
+pub trait Shape: fmt::Debug {
+    fn area(&self) -> f64;
+    fn perimeter(&self) -> f64;
+    fn bounding_box(&self) -> Rectangle<f64>;
+    fn contains_point(&self, point: Point<f64>) -> bool;
+}
+
+impl<T: Number> Shape for Rectangle<T> {
+    fn area(&self) -> f64 {
+        if self.is_empty() {
+            0.0
+        } else {
+            self.width.to_f64() * self.height.to_f64()
+        }
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * (self.width.to_f64().max(0.0) + self.height.to_f64().max(0.0))
+    }
+
+    fn bounding_box(&self) -> Rectangle<f64> {
+        Rectangle::at(self.x.to_f64(), self.y.to_f64(), self.width.to_f64(), self.height.to_f64())
+    }
+
+    fn contains_point(&self, point: Point<f64>) -> bool {
+        let r = Shape::bounding_box(self);
+        r.x <= point.x && point.x < r.x + r.width && r.y <= point.y && point.y < r.y + r.height
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    pub center: Point<f64>,
+    pub radius: f64,
+}
+
+impl Circle {
+    pub fn new(center: Point<f64>, radius: f64) -> Circle {
+        if radius.is_nan() || radius < 0.0 {
+            panic!("A circle's radius must not be negative, got {}.", radius);
+        }
+        Circle { center, radius }
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        PI * self.radius * self.radius
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * PI * self.radius
+    }
+
+    fn bounding_box(&self) -> Rectangle<f64> {
+        let Circle { center, radius } = *self;
+        Rectangle::at(center.x - radius, center.y - radius, 2.0 * radius, 2.0 * radius)
+    }
+
+    fn contains_point(&self, point: Point<f64>) -> bool {
+        let (dx, dy) = (point.x - self.center.x, point.y - self.center.y);
+        dx * dx + dy * dy < self.radius * self.radius
+    }
+}
+
+// Any three corners make a triangle, even in a line (it
+// just has no area)
+//
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Triangle {
+    pub a: Point<f64>,
+    pub b: Point<f64>,
+    pub c: Point<f64>,
+}
+
+impl Triangle {
+    pub fn new(a: Point<f64>, b: Point<f64>, c: Point<f64>) -> Triangle {
+        Triangle { a, b, c }
+    }
+}
+
+impl Shape for Triangle {
+    fn area(&self) -> f64 {
+        shoelace(&[self.a, self.b, self.c])
+    }
+
+    fn perimeter(&self) -> f64 {
+        outline_length(&[self.a, self.b, self.c])
+    }
+
+    fn bounding_box(&self) -> Rectangle<f64> {
+        corners_box(&[self.a, self.b, self.c])
+    }
+
+    fn contains_point(&self, point: Point<f64>) -> bool {
+        crossing_number(&[self.a, self.b, self.c], point)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolygonError {
+    TooFewCorners(usize),
+    // The corner that is the same as the one before it
+    //
+    RepeatedCorner(usize),
+    // The two edges that cross or overlap, where edge i
+    // runs from corner i to the next one
+    //
+    SelfIntersecting(usize, usize),
+}
+
+impl fmt::Display for PolygonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolygonError::TooFewCorners(n) => write!(f, "a polygon needs at least 3 corners, not {}", n),
+            PolygonError::RepeatedCorner(i) => write!(f, "corner {} is the same as the one before it", i),
+            PolygonError::SelfIntersecting(i, j) => write!(f, "edges {} and {} cross", i, j),
+        }
+    }
+}
+
+impl std::error::Error for PolygonError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    corners: Vec<Point<f64>>,
+}
+
+impl Polygon {
+    // The corners in order, either way round. The last
+    // corner joins back up to the first.
+    //
+    pub fn new(corners: Vec<Point<f64>>) -> Result<Polygon, PolygonError> {
+        let n = corners.len();
+        if n < 3 {
+            return Err(PolygonError::TooFewCorners(n));
+        }
+        let edge = |i: usize| (corners[i], corners[(i + 1) % n]);
+
+        for i in 0..n {
+            if corners[i] == corners[(i + n - 1) % n] {
+                return Err(PolygonError::RepeatedCorner(i));
+            }
+        }
+        for i in 0..n {
+            for j in i + 1..n {
+                let neighbours = j == i + 1 || (i == 0 && j == n - 1);
+                let (p, q) = edge(i);
+                let (r, s) = edge(j);
+                // Neighbours always share a corner, so they
+                // only count if they fold back over each other
+                //
+                let crossed = if neighbours {
+                    let (shared, one, other) = if j == i + 1 { (q, p, s) } else { (p, q, r) };
+                    cross(shared, one, other) == 0.0
+                        && (on_segment(shared, one, other) || on_segment(shared, other, one))
+                } else {
+                    segments_meet(p, q, r, s)
+                };
+                if crossed {
+                    return Err(PolygonError::SelfIntersecting(i, j));
+                }
+            }
+        }
+        Ok(Polygon { corners })
+    }
+
+    pub fn corners(&self) -> &[Point<f64>] {
+        &self.corners
+    }
+}
+
+impl Shape for Polygon {
+    fn area(&self) -> f64 {
+        shoelace(&self.corners)
+    }
+
+    fn perimeter(&self) -> f64 {
+        outline_length(&self.corners)
+    }
+
+    fn bounding_box(&self) -> Rectangle<f64> {
+        corners_box(&self.corners)
+    }
+
+    fn contains_point(&self, point: Point<f64>) -> bool {
+        crossing_number(&self.corners, point)
+    }
+}
+
+// Shapes of any kind, kept in the order they were added
+// until they're sorted. Overlapping shapes are counted in
+// full by total_area.
+//
+#[derive(Debug, Default)]
+pub struct Scene {
+    pub shapes: Vec<Box<dyn Shape>>,
+}
+
+impl Scene {
+    pub fn new() -> Scene {
+        Scene::default()
+    }
+
+    pub fn add<S: Shape + 'static>(&mut self, shape: S) -> &mut Scene {
+        self.shapes.push(Box::new(shape));
+        self
+    }
+
+    pub fn total_area(&self) -> f64 {
+        self.shapes.iter().map(|shape| shape.area()).sum()
+    }
+
+    pub fn bounding_box(&self) -> Option<Rectangle<f64>> {
+        let boxes: Vec<Rectangle<f64>> = self.shapes.iter().map(|shape| shape.bounding_box()).collect();
+        Rectangle::bounding_box(&boxes)
+    }
+
+    pub fn at_point(&self, point: Point<f64>) -> Vec<&dyn Shape> {
+        self.shapes
+            .iter()
+            .filter(|shape| shape.contains_point(point))
+            .map(|shape| shape.as_ref())
+            .collect()
+    }
+
+    // Smallest first. Shapes with the same area keep their
+    // order.
+    //
+    pub fn sort_by_area(&mut self) {
+        self.shapes.sort_by(|a, b| a.area().total_cmp(&b.area()));
+    }
+
+    pub fn sort_by_perimeter(&mut self) {
+        self.shapes.sort_by(|a, b| a.perimeter().total_cmp(&b.perimeter()));
+    }
+}
+
+// Twice the signed area of the triangle o, a, b: positive
+// when a to b turns one way round o, negative the other,
+// and 0 when they're in a line
+//
+fn cross(o: Point<f64>, a: Point<f64>, b: Point<f64>) -> f64 {
+    (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+}
+
+// Whether p, already known to be in line with a and b,
+// lies between them
+//
+fn on_segment(a: Point<f64>, b: Point<f64>, p: Point<f64>) -> bool {
+    p.x >= a.x.min(b.x) && p.x <= a.x.max(b.x) && p.y >= a.y.min(b.y) && p.y <= a.y.max(b.y)
+}
+
+// Whether segments pq and rs have any point in common,
+// ends included
+//
+fn segments_meet(p: Point<f64>, q: Point<f64>, r: Point<f64>, s: Point<f64>) -> bool {
+    let (d1, d2) = (cross(r, s, p), cross(r, s, q));
+    let (d3, d4) = (cross(p, q, r), cross(p, q, s));
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0)) && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0)) {
+        return true;
+    }
+    (d1 == 0.0 && on_segment(r, s, p))
+        || (d2 == 0.0 && on_segment(r, s, q))
+        || (d3 == 0.0 && on_segment(p, q, r))
+        || (d4 == 0.0 && on_segment(p, q, s))
+}
+
+fn shoelace(corners: &[Point<f64>]) -> f64 {
+    let n = corners.len();
+    let twice: f64 = (0..n)
+        .map(|i| {
+            let (a, b) = (corners[i], corners[(i + 1) % n]);
+            a.x * b.y - b.x * a.y
+        })
+        .sum();
+    twice.abs() / 2.0
+}
+
+fn outline_length(corners: &[Point<f64>]) -> f64 {
+    let n = corners.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (corners[i], corners[(i + 1) % n]);
+            (b.x - a.x).hypot(b.y - a.y)
+        })
+        .sum()
+}
+
+fn corners_box(corners: &[Point<f64>]) -> Rectangle<f64> {
+    let (mut left, mut top) = (f64::INFINITY, f64::INFINITY);
+    let (mut right, mut bottom) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for p in corners {
+        left = left.min(p.x);
+        top = top.min(p.y);
+        right = right.max(p.x);
+        bottom = bottom.max(p.y);
+    }
+    Rectangle::at(left, top, right - left, bottom - top)
+}
+
+// Counts the edges crossed by a ray from the point
+// towards +x; an odd count is inside. An edge covers the
+// y values from its lower end up to but not including
+// its upper end, and a crossing exactly at the point
+// doesn't count, which is what puts the left and top
+// edges inside and the right and bottom ones outside.
+//
+fn crossing_number(corners: &[Point<f64>], point: Point<f64>) -> bool {
+    let n = corners.len();
+    let mut inside = false;
+    for i in 0..n {
+        let (a, b) = (corners[i], corners[(i + 1) % n]);
+        if (a.y <= point.y) != (b.y <= point.y) {
+            let x = a.x + (point.y - a.y) * (b.x - a.x) / (b.y - a.y);
+            if point.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(x: f64, y: f64) -> Point<f64> {
+        Point::new(x, y)
+    }
+
+    fn square(side: f64) -> Polygon {
+        Polygon::new(vec![p(0.0, 0.0), p(side, 0.0), p(side, side), p(0.0, side)]).unwrap()
+    }
+
+    #[test]
+    fn rectangles_are_shapes() {
+        let r = Rectangle::at(1, 2, 3, 4);
+        assert_eq!(Shape::area(&r), 12.0);
+        assert_eq!(Shape::perimeter(&r), 14.0);
+        assert_eq!(Shape::bounding_box(&r), Rectangle::at(1.0, 2.0, 3.0, 4.0));
+        assert!(Shape::contains_point(&r, p(1.0, 2.0)));
+        assert!(!Shape::contains_point(&r, p(4.0, 2.0)));
+        assert_eq!(Shape::area(&Rectangle::new(-3, 4)), 0.0);
+    }
+
+    #[test]
+    fn circles() {
+        let c = Circle::new(p(1.0, 1.0), 2.0);
+        assert_eq!(c.area(), 4.0 * PI);
+        assert_eq!(c.perimeter(), 4.0 * PI);
+        assert_eq!(c.bounding_box(), Rectangle::at(-1.0, -1.0, 4.0, 4.0));
+        assert!(c.contains_point(p(2.0, 2.0)));
+        assert!(!c.contains_point(p(3.0, 1.0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "must not be negative")]
+    fn circles_need_a_radius() {
+        Circle::new(p(0.0, 0.0), -1.0);
+    }
+
+    #[test]
+    fn triangles() {
+        let t = Triangle::new(p(0.0, 0.0), p(4.0, 0.0), p(0.0, 3.0));
+        assert_eq!(t.area(), 6.0);
+        assert_eq!(t.perimeter(), 12.0);
+        assert_eq!(t.bounding_box(), Rectangle::at(0.0, 0.0, 4.0, 3.0));
+        assert!(t.contains_point(p(1.0, 1.0)));
+        assert!(t.contains_point(p(0.0, 0.0)));
+        assert!(!t.contains_point(p(3.0, 2.0)));
+
+        let flat = Triangle::new(p(0.0, 0.0), p(1.0, 1.0), p(2.0, 2.0));
+        assert_eq!(flat.area(), 0.0);
+        assert!(!flat.contains_point(p(1.0, 1.0)));
+    }
+
+    #[test]
+    fn polygons_either_way_round() {
+        let l = vec![p(0.0, 0.0), p(2.0, 0.0), p(2.0, 1.0), p(1.0, 1.0), p(1.0, 2.0), p(0.0, 2.0)];
+        let backwards: Vec<Point<f64>> = l.iter().rev().copied().collect();
+        for corners in [l, backwards] {
+            let shape = Polygon::new(corners).unwrap();
+            assert_eq!(shape.area(), 3.0);
+            assert_eq!(shape.perimeter(), 8.0);
+            assert!(shape.contains_point(p(0.5, 1.5)));
+            assert!(!shape.contains_point(p(1.5, 1.5)));
+        }
+    }
+
+    #[test]
+    fn polygon_edges_match_rectangles() {
+        let square = square(2.0);
+        let rect = Rectangle::new(2.0, 2.0);
+        for &(x, y) in &[(0.0, 0.0), (2.0, 0.0), (0.0, 2.0), (2.0, 2.0), (1.0, 0.0), (0.0, 1.0), (2.0, 1.0)] {
+            assert_eq!(square.contains_point(p(x, y)), Shape::contains_point(&rect, p(x, y)), "({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn bad_polygons() {
+        assert_eq!(Polygon::new(vec![p(0.0, 0.0), p(1.0, 0.0)]), Err(PolygonError::TooFewCorners(2)));
+        assert_eq!(
+            Polygon::new(vec![p(0.0, 0.0), p(1.0, 0.0), p(1.0, 0.0), p(0.0, 1.0)]),
+            Err(PolygonError::RepeatedCorner(2))
+        );
+
+        // A bow tie
+        //
+        let bow_tie = vec![p(0.0, 0.0), p(2.0, 2.0), p(2.0, 0.0), p(0.0, 2.0)];
+        assert_eq!(Polygon::new(bow_tie), Err(PolygonError::SelfIntersecting(0, 2)));
+
+        // Two corners touching without the edges crossing
+        //
+        let touching = vec![p(0.0, 0.0), p(4.0, 0.0), p(2.0, 2.0), p(4.0, 4.0), p(0.0, 4.0), p(2.0, 2.0)];
+        assert!(matches!(Polygon::new(touching), Err(PolygonError::SelfIntersecting(..))));
+
+        // An edge that doubles back along the one before it
+        //
+        let spike = vec![p(0.0, 0.0), p(4.0, 0.0), p(2.0, 0.0), p(2.0, 2.0)];
+        assert_eq!(Polygon::new(spike), Err(PolygonError::SelfIntersecting(0, 1)));
+        let spike = vec![p(0.0, 0.0), p(2.0, 0.0), p(2.0, 2.0), p(1.0, 0.0)];
+        assert!(matches!(Polygon::new(spike), Err(PolygonError::SelfIntersecting(..))));
+    }
+
+    #[test]
+    fn scenes() {
+        let mut scene = Scene::new();
+        scene
+            .add(Rectangle::new(3, 4))
+            .add(Circle::new(p(0.0, 0.0), 1.0))
+            .add(Triangle::new(p(0.0, 0.0), p(1.0, 0.0), p(0.0, 1.0)))
+            .add(square(2.0));
+        assert_eq!(scene.total_area(), 12.0 + PI + 0.5 + 4.0);
+        assert_eq!(scene.bounding_box(), Some(Rectangle::at(-1.0, -1.0, 4.0, 5.0)));
+        assert_eq!(scene.at_point(p(0.25, 0.25)).len(), 4);
+        assert_eq!(scene.at_point(p(2.5, 3.5)).len(), 1);
+
+        scene.sort_by_area();
+        let areas: Vec<f64> = scene.shapes.iter().map(|shape| shape.area()).collect();
+        assert_eq!(areas, [0.5, PI, 4.0, 12.0]);
+
+        scene.sort_by_perimeter();
+        assert_eq!(scene.shapes[3].perimeter(), 14.0);
+        assert!(Scene::new().bounding_box().is_none());
+    }
+}
@@ -0,0 +1,110 @@
+// Runs the rectangles binary the way a user would
+//
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn rectangles(args: &[&str], input: &str) -> (bool, String, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rectangles"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+#[test]
+fn packs_sizes_from_standard_input() {
+    let (ok, stdout, _) = rectangles(
+        &["pack", "--sheet", "10x10", "--format", "csv"],
+        "5x5 5x5\n5x5,5x5\n5x5\n",
+    );
+    assert!(ok);
+    let rows: Vec<&str> = stdout.lines().collect();
+    assert_eq!(rows[0], "item,sheet,x,y,width,height,rotated");
+    assert_eq!(rows.len(), 6);
+    assert_eq!(rows[5], "5,2,0,0,5,5,false");
+}
+
+#[test]
+fn reports_bad_sizes_by_line() {
+    let (ok, _, stderr) = rectangles(&["pack", "--sheet", "10x10"], "5x5\n5 by 5\n");
+    assert!(!ok);
+    assert!(stderr.contains("row 2, column 1: '5' is not a size like 30x50"));
+}
+
+#[test]
+fn runs_the_demo_without_arguments() {
+    let (ok, stdout, _) = rectangles(&[], "");
+    assert!(ok);
+    assert!(stdout.starts_with("Area: 1500\n"));
+}
+
+#[test]
+fn draws_a_packing() {
+    let (ok, stdout, _) = rectangles(&["pack", "--sheet", "10x10", "--format", "ascii"], "10x5 10x5");
+    assert!(ok);
+    assert!(stdout.starts_with("+----"));
+    assert!(stdout.contains("|1"));
+    assert!(stdout.contains("|2"));
+
+    let (ok, stdout, _) = rectangles(&["pack", "--sheet", "10x10", "--format", "svg"], "10x5");
+    assert!(ok);
+    assert!(stdout.starts_with("<svg ") && stdout.ends_with("</svg>\n"));
+}
+
+#[test]
+fn queries_take_rectangles_as_arguments() {
+    let (ok, stdout, _) = rectangles(&["area", "30x50", "10x40@5,5"], "");
+    assert!(ok);
+    assert_eq!(stdout, "30x50: 1500\n10x40@5,5: 400\n");
+
+    let (ok, stdout, _) = rectangles(&["can-hold", "30x50", "10x40", "60x45"], "");
+    assert!(ok);
+    assert_eq!(stdout, "30x50 can hold 10x40\n30x50 can't hold 60x45\n");
+
+    let (ok, stdout, _) = rectangles(&["sort-by-area", "60x45", "3x3", "30x50"], "");
+    assert!(ok);
+    assert_eq!(stdout, "3x3\n30x50\n60x45\n");
+
+    let (ok, stdout, _) = rectangles(&["largest", "10x10", "20x5", "5x20"], "");
+    assert!(ok);
+    assert_eq!(stdout, "10x10\n");
+}
+
+#[test]
+fn queries_read_json_and_csv() {
+    let json = r#"["30x50", {"width": 60, "height": 45}]"#;
+    let (ok, stdout, _) = rectangles(&["largest", "--input", "json"], json);
+    assert!(ok);
+    assert_eq!(stdout, "60x45\n");
+
+    let csv = "width,height,x,y\n30,50,0,0\n3,3,1,2\n";
+    let (ok, stdout, _) = rectangles(&["sort-by-area", "--input", "csv"], csv);
+    assert!(ok);
+    assert_eq!(stdout, "3x3@1,2\n30x50\n");
+}
+
+#[test]
+fn query_errors_give_the_row_and_column() {
+    let (ok, _, stderr) = rectangles(&["area", "30x50", "10xforty"], "");
+    assert!(!ok);
+    assert!(stderr.contains("row 2, column 4: 'forty' in '10xforty'"));
+
+    let (ok, _, stderr) = rectangles(&["area", "--input", "csv"], "width,height\n1,2\n3,-4\n");
+    assert!(!ok);
+    assert!(stderr.contains("row 3, column 2:"));
+
+    let (ok, _, stderr) = rectangles(&["largest", "--input", "json"], "[]");
+    assert!(!ok);
+    assert!(stderr.contains("there are no rectangles"));
+}
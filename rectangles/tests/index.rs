@@ -0,0 +1,91 @@
+// Differential tests: the quadtree must give the same
+// answers as the brute force reference for any mix of
+// inserts, removes and queries.
+//
+
+use proptest::prelude::*;
+use rectangles::{BruteForce, Id, Point, QuadTree, Rectangle, SpatialIndex};
+
+#[derive(Debug, Clone)]
+enum Op {
+    Insert(Rectangle),
+    Remove(usize),
+    AtPoint(Point),
+    InRange(Rectangle),
+    Nearest(Point),
+}
+
+// Small coordinates so that rectangles overlap a lot, with
+// the odd huge or empty one
+//
+fn rectangle() -> impl Strategy<Value = Rectangle> {
+    prop_oneof![
+        8 => (-300..300i32, -300..300i32, 0..80i32, 0..80i32).prop_map(|(x, y, w, h)| Rectangle::at(x, y, w, h)),
+        1 => (any::<i32>(), any::<i32>(), 0..i32::MAX, 0..i32::MAX).prop_map(|(x, y, w, h)| Rectangle::at(x, y, w, h)),
+    ]
+}
+
+fn point() -> impl Strategy<Value = Point> {
+    prop_oneof![
+        8 => (-400..400i32, -400..400i32).prop_map(|(x, y)| Point::new(x, y)),
+        1 => (any::<i32>(), any::<i32>()).prop_map(|(x, y)| Point::new(x, y)),
+    ]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        6 => rectangle().prop_map(Op::Insert),
+        2 => any::<usize>().prop_map(Op::Remove),
+        2 => point().prop_map(Op::AtPoint),
+        2 => rectangle().prop_map(Op::InRange),
+        2 => point().prop_map(Op::Nearest),
+    ]
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn quadtree_matches_brute_force(ops in prop::collection::vec(op(), 1..400)) {
+        let mut tree = QuadTree::new();
+        let mut brute = BruteForce::new();
+        let mut inserted = 0;
+
+        for op in ops {
+            match op {
+                Op::Insert(r) => {
+                    prop_assert_eq!(tree.insert(r), brute.insert(r));
+                    inserted += 1;
+                }
+                Op::Remove(n) => {
+                    // Some removes hit an Id that is already gone
+                    //
+                    let id = Id(n % (inserted + 1));
+                    prop_assert_eq!(tree.remove(id), brute.remove(id));
+                }
+                Op::AtPoint(p) => prop_assert_eq!(tree.at_point(p), brute.at_point(p)),
+                Op::InRange(r) => prop_assert_eq!(tree.in_range(&r), brute.in_range(&r)),
+                Op::Nearest(p) => prop_assert_eq!(tree.nearest(p), brute.nearest(p)),
+            }
+            prop_assert_eq!(tree.len(), brute.len());
+        }
+    }
+}
+
+#[test]
+fn a_thousand_rectangles_agree() {
+    let mut tree = QuadTree::new();
+    let mut brute = BruteForce::new();
+    for i in 0..1000 {
+        let r = Rectangle::at((i * 37) % 1000, (i * 91) % 1000, i % 23 + 1, i % 17 + 1);
+        tree.insert(r);
+        brute.insert(r);
+    }
+    for i in 0..200 {
+        let p = Point::new((i * 53) % 1100 - 50, (i * 29) % 1100 - 50);
+        assert_eq!(tree.at_point(p), brute.at_point(p));
+        assert_eq!(tree.nearest(p), brute.nearest(p));
+        let range = Rectangle::at(p.x, p.y, 60, 40);
+        assert_eq!(tree.in_range(&range), brute.in_range(&range));
+    }
+}
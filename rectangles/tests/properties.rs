@@ -0,0 +1,224 @@
+// Property tests for the geometry in src/rectangle.rs.
+// Coordinates stay small enough that nothing here can
+// overflow, so the properties are about the geometry.
+//
+
+use proptest::prelude::*;
+use std::convert::TryFrom;
+use rectangles::{Fit, Orientation, Point, Polygon, Rectangle, Shape};
+
+fn point() -> impl Strategy<Value = Point> {
+    (-200..200i32, -200..200i32).prop_map(|(x, y)| Point::new(x, y))
+}
+
+fn rectangle() -> impl Strategy<Value = Rectangle> {
+    (-100..100i32, -100..100i32, 0..100i32, 0..100i32).prop_map(|(x, y, w, h)| Rectangle::at(x, y, w, h))
+}
+
+proptest! {
+    #[test]
+    fn perimeter_is_twice_the_sides(r in rectangle()) {
+        prop_assert_eq!(r.perimeter(), 2 * r.width as u64 + 2 * r.height as u64);
+    }
+
+    #[test]
+    fn contains_point_matches_the_edges(r in rectangle(), p in point()) {
+        let inside = r.left() <= p.x as i64
+            && (p.x as i64) < r.right()
+            && r.top() <= p.y as i64
+            && (p.y as i64) < r.bottom();
+        prop_assert_eq!(r.contains_point(p), inside);
+    }
+
+    #[test]
+    fn intersection_is_never_larger_than_either(a in rectangle(), b in rectangle()) {
+        if let Some(i) = a.intersection(&b) {
+            prop_assert!(i.area() <= a.area());
+            prop_assert!(i.area() <= b.area());
+            prop_assert!(a.contains(&i));
+            prop_assert!(b.contains(&i));
+            prop_assert!(!i.is_empty());
+        }
+    }
+
+    #[test]
+    fn intersection_is_symmetric(a in rectangle(), b in rectangle()) {
+        prop_assert_eq!(a.intersection(&b), b.intersection(&a));
+        prop_assert_eq!(a.intersects(&b), b.intersects(&a));
+    }
+
+    #[test]
+    fn points_in_the_intersection_are_in_both(a in rectangle(), b in rectangle(), p in point()) {
+        let in_both = a.contains_point(p) && b.contains_point(p);
+        let in_intersection = a.intersection(&b).is_some_and(|i| i.contains_point(p));
+        prop_assert_eq!(in_intersection, in_both);
+    }
+
+    #[test]
+    fn a_rectangle_intersects_itself_unless_empty(r in rectangle()) {
+        prop_assert_eq!(r.intersection(&r), if r.is_empty() { None } else { Some(r) });
+    }
+
+    #[test]
+    fn union_covers_both(a in rectangle(), b in rectangle(), p in point()) {
+        let u = a.union(&b);
+        prop_assert_eq!(u, b.union(&a));
+        if !a.is_empty() {
+            prop_assert!(u.contains(&a));
+        }
+        if !b.is_empty() {
+            prop_assert!(u.contains(&b));
+        }
+        if a.contains_point(p) || b.contains_point(p) {
+            prop_assert!(u.contains_point(p));
+        }
+        prop_assert!(u.area() >= a.area().max(b.area()));
+    }
+
+    #[test]
+    fn union_is_the_smallest_cover(a in rectangle(), b in rectangle()) {
+        prop_assume!(!a.is_empty() && !b.is_empty());
+        let u = a.union(&b);
+        prop_assert_eq!(u.left(), a.left().min(b.left()));
+        prop_assert_eq!(u.top(), a.top().min(b.top()));
+        prop_assert_eq!(u.right(), a.right().max(b.right()));
+        prop_assert_eq!(u.bottom(), a.bottom().max(b.bottom()));
+    }
+
+    #[test]
+    fn translate_moves_points_along(r in rectangle(), p in point(), dx in -50..50i32, dy in -50..50i32) {
+        let moved = r.translate(dx, dy);
+        prop_assert_eq!((moved.width, moved.height), (r.width, r.height));
+        prop_assert_eq!(moved.contains_point(Point::new(p.x + dx, p.y + dy)), r.contains_point(p));
+        prop_assert_eq!(moved.translate(-dx, -dy), r);
+    }
+
+    #[test]
+    fn scale_multiplies_the_area(r in rectangle(), factor in 0..10i32) {
+        let scaled = r.scale(factor);
+        let factor = factor as u64;
+        prop_assert_eq!(scaled.origin(), r.origin());
+        prop_assert_eq!(scaled.area(), r.area() * factor * factor);
+        prop_assert_eq!(scaled.perimeter(), r.perimeter() * factor);
+    }
+
+    #[test]
+    fn inset_stays_inside(r in rectangle(), by in 0..60i32) {
+        let inset = r.inset(by);
+        prop_assert!(r.contains(&inset));
+        prop_assert!(inset.area() <= r.area());
+        if 2 * by < r.width.min(r.height) {
+            prop_assert_eq!(inset.width, r.width - 2 * by);
+            prop_assert_eq!(inset.height, r.height - 2 * by);
+            prop_assert_eq!(inset.origin(), Point::new(r.x + by, r.y + by));
+        }
+    }
+
+    #[test]
+    fn area_never_overflows(w in any::<u32>(), h in any::<u32>()) {
+        let r = Rectangle::<u32>::new(w, h);
+        let exact = w as u64 * h as u64;
+        prop_assert_eq!(r.area(), exact);
+        prop_assert_eq!(r.checked_area(), u32::try_from(exact).ok());
+        prop_assert_eq!(r.saturating_area() as u64, exact.min(u32::MAX as u64));
+    }
+
+    #[test]
+    fn wide_area_never_overflows(w in any::<i64>(), h in any::<i64>()) {
+        let r = Rectangle::<i64>::new(w, h);
+        let exact = if w > 0 && h > 0 { w as u128 * h as u128 } else { 0 };
+        prop_assert_eq!(r.area(), exact);
+        prop_assert_eq!(r.checked_area(), i64::try_from(exact).ok());
+    }
+
+    #[test]
+    fn edges_never_overflow(x in any::<i32>(), y in any::<i32>(), w in any::<i32>(), h in any::<i32>()) {
+        let r = Rectangle::at(x, y, w, h);
+        prop_assert_eq!(r.right(), x as i64 + w as i64);
+        prop_assert_eq!(r.bottom(), y as i64 + h as i64);
+        prop_assert_eq!(r.intersection(&r), if r.is_empty() { None } else { Some(r) });
+        let _ = r.union(&Rectangle::at(i32::MIN, i32::MIN, 1, 1));
+        let _ = r.translate(x, y).scale(w).inset(h);
+    }
+
+    #[test]
+    fn casts_round_trip(r in rectangle()) {
+        let float = r.try_cast::<f64>().unwrap();
+        prop_assert_eq!(float.try_cast::<i32>(), Some(r));
+        prop_assert_eq!(float.area(), r.area() as f64);
+
+        let unsigned = r.try_cast::<u32>();
+        prop_assert_eq!(unsigned.is_some(), r.x >= 0 && r.y >= 0);
+    }
+
+    #[test]
+    fn display_and_from_str_round_trip(
+        x in any::<i32>(), y in any::<i32>(), w in any::<i32>(), h in any::<i32>(), f in any::<f64>()
+    ) {
+        let r = Rectangle::at(x, y, w, h);
+        prop_assert_eq!(r.to_string().parse::<Rectangle>(), Ok(r));
+
+        let float = Rectangle::at(f, f / 3.0, f * 7.0, 0.1);
+        let back = float.to_string().parse::<Rectangle<f64>>().unwrap();
+        prop_assert_eq!(back.to_string(), float.to_string());
+        prop_assert!(back == float || f.is_nan() || f.is_infinite());
+    }
+
+    #[test]
+    fn a_rectangle_as_a_polygon_is_the_same_shape(r in rectangle(), p in point()) {
+        prop_assume!(!r.is_empty());
+        let (left, top) = (r.x as f64, r.y as f64);
+        let (right, bottom) = (left + r.width as f64, top + r.height as f64);
+        let corners = vec![
+            Point::new(left, top),
+            Point::new(right, top),
+            Point::new(right, bottom),
+            Point::new(left, bottom),
+        ];
+        let polygon = Polygon::new(corners).unwrap();
+        let point = Point::new(p.x as f64, p.y as f64);
+
+        prop_assert_eq!(polygon.area(), Shape::area(&r));
+        prop_assert_eq!(polygon.perimeter(), Shape::perimeter(&r));
+        prop_assert_eq!(polygon.bounding_box(), Shape::bounding_box(&r));
+        prop_assert_eq!(polygon.contains_point(point), r.contains_point(p));
+    }
+
+    #[test]
+    fn fit_policies_are_ordered(a in rectangle(), b in rectangle(), margin in 0..20i32) {
+        let strict = a.can_hold_with(&b, Fit::Strict);
+        let inclusive = a.can_hold_with(&b, Fit::Inclusive);
+        let rotation = a.can_hold_with(&b, Fit::AllowRotation);
+        let margin = a.can_hold_with(&b, Fit::AllowRotationWithMargin(margin));
+
+        prop_assert_eq!(a.can_hold(&b), strict.is_some());
+        prop_assert!(strict.is_none() || inclusive.is_some());
+        prop_assert!(inclusive.is_none() || rotation == Some(Orientation::Upright));
+        prop_assert!(margin.is_none() || rotation.is_some());
+        prop_assert_eq!(a.can_hold_with(&b, Fit::AllowRotationWithMargin(0)), rotation);
+    }
+
+    #[test]
+    fn the_reported_orientation_fits(a in rectangle(), b in rectangle(), margin in 0..20i32) {
+        for fit in [Fit::Strict, Fit::Inclusive, Fit::AllowRotation, Fit::AllowRotationWithMargin(margin)] {
+            let inner = match a.can_hold_with(&b, fit) {
+                Some(Orientation::Upright) => b,
+                Some(Orientation::Rotated) => b.rotate(),
+                None => continue,
+            };
+            let room = match fit {
+                Fit::AllowRotationWithMargin(margin) => a.inset(margin),
+                _ => a,
+            };
+            prop_assert!(room.width >= inner.width && room.height >= inner.height);
+        }
+    }
+
+    #[test]
+    fn rotating_the_other_one_never_matters_when_rotation_is_allowed(a in rectangle(), b in rectangle()) {
+        prop_assert_eq!(
+            a.can_hold_with(&b, Fit::AllowRotation).is_some(),
+            a.can_hold_with(&b.rotate(), Fit::AllowRotation).is_some()
+        );
+    }
+}
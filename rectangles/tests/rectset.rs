@@ -0,0 +1,87 @@
+// RectSet against a brute force oracle: with whole number
+// corners, every unit square is either in a rectangle or
+// out of it, so sampling one point per square on a grid
+// (its top left corner) gives exact areas.
+//
+
+use proptest::prelude::*;
+use rectangles::{Point, RectSet, Rectangle};
+
+const GRID: i32 = 24;
+
+fn rectangle() -> impl Strategy<Value = Rectangle> {
+    (-GRID..GRID, -GRID..GRID, -2..16i32, -2..16i32).prop_map(|(x, y, w, h)| Rectangle::at(x, y, w, h))
+}
+
+fn set() -> impl Strategy<Value = RectSet> {
+    prop::collection::vec(rectangle(), 0..12).prop_map(|rects| rects.into_iter().collect())
+}
+
+// Every sample point on the grid, which is big enough to
+// hold any rectangle the strategies make
+//
+fn grid() -> impl Iterator<Item = Point> {
+    (-2 * GRID..2 * GRID).flat_map(|y| (-2 * GRID..2 * GRID).map(move |x| Point::new(x, y)))
+}
+
+fn sampled_area<F: Fn(Point) -> bool>(covered: F) -> u128 {
+    grid().filter(|&p| covered(p)).count() as u128
+}
+
+proptest! {
+    #[test]
+    fn union_area_matches_the_oracle(set in set()) {
+        prop_assert_eq!(set.union_area(), sampled_area(|p| set.contains_point(p)));
+    }
+
+    #[test]
+    fn area_within_matches_the_oracle(set in set(), region in rectangle()) {
+        let expected = sampled_area(|p| set.contains_point(p) && region.contains_point(p));
+        prop_assert_eq!(set.area_within(&region), expected);
+        if !region.is_empty() {
+            prop_assert!((0.0..=1.0).contains(&set.coverage(&region)));
+        }
+    }
+
+    #[test]
+    fn subtract_leaves_up_to_four_separate_pieces(a in rectangle(), b in rectangle()) {
+        let pieces = a.subtract(&b);
+        prop_assert!(pieces.len() <= 4);
+        prop_assert!(pieces.iter().all(|piece| !piece.is_empty()));
+        for p in grid() {
+            let inside = pieces.iter().filter(|piece| piece.contains_point(p)).count();
+            let expected = a.contains_point(p) && !b.contains_point(p);
+            prop_assert_eq!(inside, expected as usize, "at {:?}", p);
+        }
+    }
+
+    #[test]
+    fn set_subtract_matches_the_oracle(set in set(), hole in rectangle()) {
+        let left = set.subtract(&hole);
+        for p in grid() {
+            prop_assert_eq!(left.contains_point(p), set.contains_point(p) && !hole.contains_point(p));
+        }
+    }
+
+    #[test]
+    fn merged_covers_the_same_squares_once(set in set()) {
+        let merged = set.merged();
+        for p in grid() {
+            let inside = merged.rects().iter().filter(|r| r.contains_point(p)).count();
+            prop_assert_eq!(inside, set.contains_point(p) as usize, "at {:?}", p);
+        }
+        prop_assert_eq!(merged.union_area(), set.union_area());
+    }
+
+    #[test]
+    fn merged_rectangles_never_share_a_whole_edge(set in set()) {
+        let merged = set.merged();
+        for a in merged.rects() {
+            for b in merged.rects() {
+                let side_by_side = a.right() == b.left() && a.top() == b.top() && a.bottom() == b.bottom();
+                let stacked = a.bottom() == b.top() && a.left() == b.left() && a.right() == b.right();
+                prop_assert!(!side_by_side && !stacked, "{:?} and {:?}", a, b);
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+// Golden-file tests for src/render.rs: each drawing is
+// compared with a file in tests/golden/. After a change
+// to the output on purpose, run the tests with
+// UPDATE_GOLDEN=1 to write the files again, and check the
+// difference before committing it.
+//
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use rectangles::packing::{self, Algorithm, Options};
+use rectangles::render::{Drawing, Style, SvgOptions};
+use rectangles::{Fit, Rectangle};
+
+fn golden(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(name);
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    assert!(
+        actual == expected,
+        "{} doesn't match; run with UPDATE_GOLDEN=1 to update it\n--- expected\n{}--- actual\n{}",
+        name,
+        expected,
+        actual
+    );
+}
+
+// r2 inside r1 with a margin of 5, as in the demo, and r3
+// sticking out of r1 because it's too big to hold
+//
+fn can_hold() -> Drawing {
+    let r1 = Rectangle::<u32>::new(30, 50);
+    let r2 = Rectangle::<u32>::new(10, 40);
+    let r3 = Rectangle::<u32>::new(60, 45);
+    assert!(r1.can_hold_with(&r2, Fit::AllowRotationWithMargin(5)).is_some());
+    assert!(!r1.can_hold(&r3));
+
+    let mut drawing = Drawing::new();
+    drawing.add(&r1, Some("r1"), Style::default());
+    drawing.add(&r2.translate(5, 5), Some("r2"), Style::filled("#8dd3c7"));
+    drawing.add(
+        &r3.translate(20, 20),
+        Some("r3"),
+        Style {
+            stroke: String::from("red"),
+            stroke_width: 2.0,
+            opacity: 0.5,
+            ..Style::filled("#fb8072")
+        },
+    );
+    drawing
+}
+
+fn packing() -> Drawing {
+    let items = [
+        Rectangle::new(30, 20),
+        Rectangle::new(20, 30),
+        Rectangle::new(40, 10),
+        Rectangle::new(15, 15),
+        Rectangle::new(25, 10),
+        Rectangle::new(10, 45),
+        Rectangle::new(60, 60),
+    ];
+    let options = Options {
+        algorithm: Algorithm::MaxRects,
+        rotation: true,
+    };
+    Drawing::packing(&packing::pack(Rectangle::new(50, 40), &items, options))
+}
+
+#[test]
+fn can_hold_as_svg() {
+    golden("can_hold.svg", &can_hold().to_svg(&SvgOptions::default()));
+}
+
+#[test]
+fn can_hold_as_ascii() {
+    golden("can_hold.txt", &can_hold().to_ascii(41));
+}
+
+#[test]
+fn packing_as_svg() {
+    let options = SvgOptions {
+        scale: 4.0,
+        ..SvgOptions::default()
+    };
+    golden("packing.svg", &packing().to_svg(&options));
+}
+
+#[test]
+fn packing_as_ascii() {
+    golden("packing.txt", &packing().to_ascii(78));
+}
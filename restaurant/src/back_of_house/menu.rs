@@ -0,0 +1,419 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - A Menu is a list of Items, each in a Category, with a
+//   price in cents (never floating point, so sums come
+//   out exact), the allergens it contains, and optionally
+//   seasonal variants and a window of the day it's served
+//   in.
+// - A Category can have a window too: breakfast is only
+//   served in the morning. An item is available when the
+//   time is inside both its category's window and its own.
+//   A window can run past midnight.
+// - A seasonal variant replaces the item's name, and
+//   maybe its price, in that season. Out of season the
+//   item is what it says.
+// - Menus are read from TOML or JSON (see menu.toml for
+//   the layout); Menu::load goes by the file's extension.
+//   The house menu is menu.toml, built into the crate.
+//
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+const HOUSE_MENU: &str = include_str!("../../menu.toml");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Breakfast,
+    Appetizer,
+    Main,
+    Dessert,
+    Drink,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Allergen {
+    Gluten,
+    Dairy,
+    Eggs,
+    Nuts,
+    Peanuts,
+    Soy,
+    Fish,
+    Shellfish,
+    Sesame,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    // The northern hemisphere's seasons, by month (1 to 12)
+    //
+    pub fn of_month(month: u32) -> Season {
+        match month {
+            3..=5 => Season::Spring,
+            6..=8 => Season::Summer,
+            9..=11 => Season::Autumn,
+            1 | 2 | 12 => Season::Winter,
+            _ => panic!("Month must be between 1 and 12, got {}.", month),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Price(pub u32);
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "${}.{:02}", self.0 / 100, self.0 % 100)
+    }
+}
+
+// Minutes since midnight, written "HH:MM"
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TimeOfDay(u16);
+
+impl TimeOfDay {
+    pub fn new(hour: u16, minute: u16) -> TimeOfDay {
+        if hour > 23 || minute > 59 {
+            panic!("{}:{:02} isn't a time of day.", hour, minute);
+        }
+        TimeOfDay(hour * 60 + minute)
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.0 / 60, self.0 % 60)
+    }
+}
+
+impl FromStr for TimeOfDay {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<TimeOfDay, String> {
+        let bad = || format!("'{}' is not a time like 07:30", s);
+        let (hour, minute) = s.split_once(':').ok_or_else(bad)?;
+        let hour: u16 = hour.parse().map_err(|_| bad())?;
+        let minute: u16 = minute.parse().map_err(|_| bad())?;
+        if hour > 23 || minute > 59 {
+            return Err(bad());
+        }
+        Ok(TimeOfDay::new(hour, minute))
+    }
+}
+
+impl TryFrom<String> for TimeOfDay {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<TimeOfDay, String> {
+        s.parse()
+    }
+}
+
+impl From<TimeOfDay> for String {
+    fn from(time: TimeOfDay) -> String {
+        time.to_string()
+    }
+}
+
+// From the first time up to (but not including) the second
+//
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Window {
+    pub from: TimeOfDay,
+    pub until: TimeOfDay,
+}
+
+impl Window {
+    pub fn contains(&self, time: TimeOfDay) -> bool {
+        if self.from <= self.until {
+            self.from <= time && time < self.until
+        } else {
+            time >= self.from || time < self.until
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Variant {
+    pub season: Season,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub price: Option<Price>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Item {
+    pub name: String,
+    pub category: Category,
+    pub price: Price,
+    #[serde(default)]
+    pub allergens: Vec<Allergen>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub available: Option<Window>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<Variant>,
+}
+
+impl Item {
+    pub fn variant(&self, season: Season) -> Option<&Variant> {
+        self.variants.iter().find(|variant| variant.season == season)
+    }
+
+    pub fn name_in(&self, season: Season) -> &str {
+        self.variant(season).map_or(&self.name, |variant| &variant.name)
+    }
+
+    pub fn price_in(&self, season: Season) -> Price {
+        self.variant(season).and_then(|variant| variant.price).unwrap_or(self.price)
+    }
+
+    pub fn contains(&self, allergen: Allergen) -> bool {
+        self.allergens.contains(&allergen)
+    }
+}
+
+#[derive(Debug)]
+pub enum MenuError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+    UnknownFormat(PathBuf),
+    DuplicateItem(String),
+    DuplicateVariant { item: String, season: Season },
+}
+
+impl fmt::Display for MenuError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MenuError::Io(e) => write!(f, "{}", e),
+            MenuError::Toml(e) => write!(f, "{}", e),
+            MenuError::Json(e) => write!(f, "{}", e),
+            MenuError::UnknownFormat(path) => {
+                write!(f, "{}: a menu has to be a .toml or .json file", path.display())
+            }
+            MenuError::DuplicateItem(name) => write!(f, "'{}' is on the menu twice", name),
+            MenuError::DuplicateVariant { item, season } => {
+                write!(f, "'{}' has two {:?} variants", item, season)
+            }
+        }
+    }
+}
+
+impl Error for MenuError {}
+
+impl From<io::Error> for MenuError {
+    fn from(e: io::Error) -> MenuError {
+        MenuError::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for MenuError {
+    fn from(e: toml::de::Error) -> MenuError {
+        MenuError::Toml(e)
+    }
+}
+
+impl From<serde_json::Error> for MenuError {
+    fn from(e: serde_json::Error) -> MenuError {
+        MenuError::Json(e)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Menu {
+    #[serde(default)]
+    windows: HashMap<Category, Window>,
+    items: Vec<Item>,
+}
+
+impl Menu {
+    pub fn house() -> Menu {
+        Menu::from_toml(HOUSE_MENU).expect("the house menu is valid")
+    }
+
+    pub fn from_toml(text: &str) -> Result<Menu, MenuError> {
+        let menu: Menu = toml::from_str(text)?;
+        menu.checked()
+    }
+
+    pub fn from_json(text: &str) -> Result<Menu, MenuError> {
+        let menu: Menu = serde_json::from_str(text)?;
+        menu.checked()
+    }
+
+    pub fn load(path: &Path) -> Result<Menu, MenuError> {
+        let read = || {
+            fs::read_to_string(path).map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+        };
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Menu::from_toml(&read()?),
+            Some("json") => Menu::from_json(&read()?),
+            _ => Err(MenuError::UnknownFormat(path.to_path_buf())),
+        }
+    }
+
+    fn checked(self) -> Result<Menu, MenuError> {
+        for (i, item) in self.items.iter().enumerate() {
+            if self.items[..i].iter().any(|earlier| earlier.name == item.name) {
+                return Err(MenuError::DuplicateItem(item.name.clone()));
+            }
+            for (j, variant) in item.variants.iter().enumerate() {
+                if item.variants[..j].iter().any(|earlier| earlier.season == variant.season) {
+                    return Err(MenuError::DuplicateVariant {
+                        item: item.name.clone(),
+                        season: variant.season,
+                    });
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Item> {
+        self.items.iter().find(|item| item.name == name)
+    }
+
+    pub fn window(&self, category: Category) -> Option<Window> {
+        self.windows.get(&category).copied()
+    }
+
+    pub fn in_category(&self, category: Category) -> impl Iterator<Item = &Item> + '_ {
+        self.items.iter().filter(move |item| item.category == category)
+    }
+
+    pub fn is_available(&self, item: &Item, time: TimeOfDay) -> bool {
+        let in_window = |window: Option<Window>| window.is_none_or(|window| window.contains(time));
+        in_window(self.window(item.category)) && in_window(item.available)
+    }
+
+    pub fn available_at(&self, time: TimeOfDay) -> impl Iterator<Item = &Item> + '_ {
+        self.items.iter().filter(move |item| self.is_available(item, time))
+    }
+
+    pub fn free_of<'m>(&'m self, allergens: &'m [Allergen]) -> impl Iterator<Item = &'m Item> + 'm {
+        self.items.iter().filter(move |item| !allergens.iter().any(|&allergen| item.contains(allergen)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names<'a, I: Iterator<Item = &'a Item>>(items: I) -> Vec<&'a str> {
+        items.map(|item| item.name.as_str()).collect()
+    }
+
+    #[test]
+    fn the_house_menu() {
+        let menu = Menu::house();
+        assert_eq!(menu.items().len(), 7);
+        assert_eq!(names(menu.in_category(Category::Appetizer)), ["Soup", "Salad"]);
+        assert_eq!(menu.get("Toast").unwrap().price, Price(250));
+        assert_eq!(Price(1805).to_string(), "$18.05");
+    }
+
+    #[test]
+    fn breakfast_is_only_served_in_the_morning() {
+        let menu = Menu::house();
+        let toast = menu.get("Toast").unwrap();
+        assert!(menu.is_available(toast, TimeOfDay::new(6, 0)));
+        assert!(menu.is_available(toast, TimeOfDay::new(11, 29)));
+        assert!(!menu.is_available(toast, TimeOfDay::new(11, 30)));
+        assert_eq!(
+            names(menu.available_at(TimeOfDay::new(18, 0))),
+            ["Soup", "Salad", "Fish of the day", "Lemonade"]
+        );
+    }
+
+    #[test]
+    fn windows_can_run_past_midnight() {
+        let late = Window {
+            from: TimeOfDay::new(22, 0),
+            until: TimeOfDay::new(2, 0),
+        };
+        assert!(late.contains(TimeOfDay::new(23, 59)));
+        assert!(late.contains(TimeOfDay::new(1, 0)));
+        assert!(!late.contains(TimeOfDay::new(2, 0)));
+        assert!(!late.contains(TimeOfDay::new(12, 0)));
+    }
+
+    #[test]
+    fn seasonal_variants() {
+        let menu = Menu::house();
+        let fruit = menu.get("Seasonal fruit").unwrap();
+        assert_eq!(fruit.name_in(Season::Summer), "peaches");
+        assert_eq!(fruit.price_in(Season::Summer), Price(400));
+        assert_eq!(fruit.price_in(Season::Winter), Price(350));
+
+        let salad = menu.get("Salad").unwrap();
+        assert_eq!(salad.name_in(Season::Summer), "Salad");
+        assert_eq!(Season::of_month(7), Season::Summer);
+        assert_eq!(Season::of_month(12), Season::Winter);
+    }
+
+    #[test]
+    fn leaving_out_allergens() {
+        let menu = Menu::house();
+        assert_eq!(
+            names(menu.free_of(&[Allergen::Dairy, Allergen::Gluten])),
+            ["Seasonal fruit", "Salad", "Fish of the day", "Lemonade"]
+        );
+    }
+
+    #[test]
+    fn json_menus_read_the_same() {
+        let menu = Menu::house();
+        let json = serde_json::to_string(&menu).unwrap();
+        assert_eq!(Menu::from_json(&json).unwrap(), menu);
+    }
+
+    #[test]
+    fn bad_menus() {
+        let twice = r#"
+            [[items]]
+            name = "Toast"
+            category = "breakfast"
+            price = 250
+
+            [[items]]
+            name = "Toast"
+            category = "breakfast"
+            price = 300
+        "#;
+        assert_eq!(Menu::from_toml(twice).unwrap_err().to_string(), "'Toast' is on the menu twice");
+
+        let late = r#"{"windows": {"breakfast": {"from": "25:00", "until": "11:00"}}, "items": []}"#;
+        let err = Menu::from_json(late).unwrap_err().to_string();
+        assert!(err.contains("'25:00' is not a time like 07:30"), "{}", err);
+
+        assert!(matches!(Menu::load(Path::new("menu.yaml")), Err(MenuError::UnknownFormat(_))));
+        assert!(matches!(Menu::load(Path::new("no/such/menu.toml")), Err(MenuError::Io(_))));
+    }
+}
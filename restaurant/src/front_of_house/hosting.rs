@@ -0,0 +1,421 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - A Waitlist is the host stand: the tables in the
+//   restaurant, which of them are taken, and the parties
+//   waiting, in the order they arrived.
+// - Seating is first come, first served, except that a
+//   party that no free table fits is passed over for the
+//   next one that does. Each party gets the smallest free
+//   table it fits, so big tables are kept for big
+//   parties.
+// - Wait estimates assume a table turns over in the
+//   average time the last few tables took (45 minutes
+//   until there are any), and that every party ahead
+//   will take one of the tables this party could use. It
+//   is a rough guide for quoting, not a promise.
+// - Times come from a Clock, which tests replace with a
+//   ManualClock.
+//
+
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use crate::clock::{Clock, SystemClock};
+
+// How long a table is assumed to take before any have
+// been cleared
+//
+const DEFAULT_TURN: Duration = Duration::from_secs(45 * 60);
+
+// How many recent table turns the estimate averages
+//
+const TURNS_REMEMBERED: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PartyId(pub u32);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Party {
+    pub id: PartyId,
+    pub name: String,
+    pub size: u32,
+    pub arrived: Duration,
+    pub phone: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Table {
+    pub number: u32,
+    pub seats: u32,
+}
+
+impl Table {
+    pub fn new(number: u32, seats: u32) -> Table {
+        Table { number, seats }
+    }
+}
+
+// A party that has just been sat
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Seated {
+    pub party: Party,
+    pub table: u32,
+    pub waited: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeatingError {
+    EmptyParty,
+    TooBig { size: u32, largest: u32 },
+    NobodyWaiting,
+    NoTableFree,
+    UnknownParty(PartyId),
+    UnknownTable(u32),
+    TableNotTaken(u32),
+}
+
+impl fmt::Display for SeatingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SeatingError::EmptyParty => write!(f, "a party needs at least one person"),
+            SeatingError::TooBig { size, largest } => {
+                write!(f, "a party of {} is too big; the largest table seats {}", size, largest)
+            }
+            SeatingError::NobodyWaiting => write!(f, "nobody is waiting"),
+            SeatingError::NoTableFree => write!(f, "no free table fits anyone waiting"),
+            SeatingError::UnknownParty(id) => write!(f, "party {} isn't waiting", id.0),
+            SeatingError::UnknownTable(number) => write!(f, "there is no table {}", number),
+            SeatingError::TableNotTaken(number) => write!(f, "table {} isn't taken", number),
+        }
+    }
+}
+
+impl Error for SeatingError {}
+
+#[derive(Debug, Clone, Copy)]
+struct Taken {
+    party: PartyId,
+    since: Duration,
+}
+
+pub struct Waitlist<'a> {
+    clock: &'a dyn Clock,
+    tables: Vec<Table>,
+    taken: HashMap<u32, Taken>,
+    waiting: VecDeque<Party>,
+    no_shows: Vec<Party>,
+    turns: VecDeque<Duration>,
+    next_id: u32,
+}
+
+impl<'a> Waitlist<'a> {
+    pub fn new(mut tables: Vec<Table>) -> Waitlist<'a> {
+        // Smallest first, so the first free table that fits
+        // a party is the best one for it
+        //
+        tables.sort_by_key(|t| (t.seats, t.number));
+        let mut numbers: Vec<u32> = tables.iter().map(|t| t.number).collect();
+        numbers.sort_unstable();
+        if let Some(pair) = numbers.windows(2).find(|pair| pair[0] == pair[1]) {
+            panic!("Table {} is in the list twice.", pair[0]);
+        }
+        Waitlist {
+            clock: &SystemClock,
+            tables,
+            taken: HashMap::new(),
+            waiting: VecDeque::new(),
+            no_shows: Vec::new(),
+            turns: VecDeque::new(),
+            next_id: 1,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: &'a dyn Clock) -> Waitlist<'a> {
+        self.clock = clock;
+        self
+    }
+
+    pub fn add_to_waitlist(&mut self, name: &str, size: u32, phone: Option<&str>) -> Result<PartyId, SeatingError> {
+        self.check_size(size)?;
+        let id = PartyId(self.next_id);
+        self.next_id += 1;
+        self.waiting.push_back(Party {
+            id,
+            name: String::from(name),
+            size,
+            arrived: self.clock.now(),
+            phone: phone.map(String::from),
+        });
+        Ok(id)
+    }
+
+    pub fn waiting(&self) -> impl Iterator<Item = &Party> {
+        self.waiting.iter()
+    }
+
+    pub fn free_tables(&self) -> impl Iterator<Item = &Table> {
+        self.tables.iter().filter(move |t| !self.taken.contains_key(&t.number))
+    }
+
+    // Seats the first party that a free table fits
+    //
+    pub fn seat_at_table(&mut self) -> Result<Seated, SeatingError> {
+        if self.waiting.is_empty() {
+            return Err(SeatingError::NobodyWaiting);
+        }
+        let (position, table) = self
+            .waiting
+            .iter()
+            .enumerate()
+            .find_map(|(i, party)| self.best_free_table(party.size).map(|table| (i, table)))
+            .ok_or(SeatingError::NoTableFree)?;
+
+        let party = self.waiting.remove(position).unwrap();
+        let now = self.clock.now();
+        self.taken.insert(table, Taken { party: party.id, since: now });
+        Ok(Seated {
+            waited: now.saturating_sub(party.arrived),
+            party,
+            table,
+        })
+    }
+
+    // The party at a table has left. How long they were there
+    // goes towards future wait estimates.
+    //
+    pub fn clear_table(&mut self, number: u32) -> Result<PartyId, SeatingError> {
+        if !self.tables.iter().any(|t| t.number == number) {
+            return Err(SeatingError::UnknownTable(number));
+        }
+        let taken = self.taken.remove(&number).ok_or(SeatingError::TableNotTaken(number))?;
+
+        if self.turns.len() == TURNS_REMEMBERED {
+            self.turns.pop_front();
+        }
+        self.turns.push_back(self.clock.now().saturating_sub(taken.since));
+        Ok(taken.party)
+    }
+
+    // A party that gave up waiting
+    //
+    pub fn remove(&mut self, id: PartyId) -> Result<Party, SeatingError> {
+        let position = self.position(id)?;
+        Ok(self.waiting.remove(position).unwrap())
+    }
+
+    // A party that wasn't there when called. They come off the
+    // list, and are remembered (with their phone number, if
+    // they left one) in no_shows().
+    //
+    pub fn no_show(&mut self, id: PartyId) -> Result<Party, SeatingError> {
+        let party = self.remove(id)?;
+        self.no_shows.push(party.clone());
+        Ok(party)
+    }
+
+    pub fn no_shows(&self) -> &[Party] {
+        &self.no_shows
+    }
+
+    pub fn estimated_wait(&self, id: PartyId) -> Result<Duration, SeatingError> {
+        let position = self.position(id)?;
+        Ok(self.wait_for(self.waiting[position].size, position))
+    }
+
+    // What to tell a party of `size` that has just walked in
+    //
+    pub fn quote(&self, size: u32) -> Result<Duration, SeatingError> {
+        self.check_size(size)?;
+        Ok(self.wait_for(size, self.waiting.len()))
+    }
+
+    fn check_size(&self, size: u32) -> Result<(), SeatingError> {
+        let largest = self.tables.iter().map(|t| t.seats).max().unwrap_or(0);
+        if size == 0 {
+            Err(SeatingError::EmptyParty)
+        } else if size > largest {
+            Err(SeatingError::TooBig { size, largest })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn position(&self, id: PartyId) -> Result<usize, SeatingError> {
+        self.waiting
+            .iter()
+            .position(|party| party.id == id)
+            .ok_or(SeatingError::UnknownParty(id))
+    }
+
+    fn best_free_table(&self, size: u32) -> Option<u32> {
+        self.free_tables().find(|t| t.seats >= size).map(|t| t.number)
+    }
+
+    fn turn_time(&self) -> Duration {
+        if self.turns.is_empty() {
+            DEFAULT_TURN
+        } else {
+            self.turns.iter().sum::<Duration>() / self.turns.len() as u32
+        }
+    }
+
+    // Works out when each table a party of `size` could use
+    // comes free, lets the `ahead` parties in front take the
+    // first ones, and gives how long after now the next one
+    // is free after that
+    //
+    fn wait_for(&self, size: u32, ahead: usize) -> Duration {
+        let now = self.clock.now();
+        let turn = self.turn_time();
+        let mut free_at: Vec<Duration> = self
+            .tables
+            .iter()
+            .filter(|t| t.seats >= size)
+            .map(|t| match self.taken.get(&t.number) {
+                Some(taken) => (taken.since + turn).max(now),
+                None => now,
+            })
+            .collect();
+
+        for _ in 0..ahead {
+            if let Some(first) = free_at.iter_mut().min() {
+                *first += turn;
+            }
+        }
+        free_at.into_iter().min().unwrap_or(now) - now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    fn minutes(n: u64) -> Duration {
+        Duration::from_secs(n * 60)
+    }
+
+    fn tables() -> Vec<Table> {
+        vec![Table::new(1, 2), Table::new(2, 4), Table::new(3, 2), Table::new(4, 8)]
+    }
+
+    #[test]
+    fn parties_are_seated_in_order_at_the_smallest_table() {
+        let clock = ManualClock::new(minutes(0));
+        let mut waitlist = Waitlist::new(tables()).with_clock(&clock);
+        let ann = waitlist.add_to_waitlist("Ann", 2, Some("555-0100")).unwrap();
+        clock.advance(minutes(5));
+        waitlist.add_to_waitlist("Bo", 3, None).unwrap();
+
+        let seated = waitlist.seat_at_table().unwrap();
+        assert_eq!((seated.party.id, seated.table, seated.waited), (ann, 1, minutes(5)));
+        assert_eq!(seated.party.phone.as_deref(), Some("555-0100"));
+        assert_eq!(waitlist.seat_at_table().unwrap().table, 2);
+        assert_eq!(waitlist.seat_at_table(), Err(SeatingError::NobodyWaiting));
+    }
+
+    #[test]
+    fn smaller_parties_go_ahead_when_only_a_small_table_is_free() {
+        let mut waitlist = Waitlist::new(vec![Table::new(1, 2), Table::new(2, 6)]);
+        waitlist.add_to_waitlist("Big", 6, None).unwrap();
+        waitlist.seat_at_table().unwrap();
+
+        waitlist.add_to_waitlist("Also big", 5, None).unwrap();
+        let pair = waitlist.add_to_waitlist("Pair", 2, None).unwrap();
+        assert_eq!(waitlist.seat_at_table().map(|s| s.party.id), Ok(pair));
+        assert_eq!(waitlist.seat_at_table(), Err(SeatingError::NoTableFree));
+
+        waitlist.clear_table(2).unwrap();
+        assert_eq!(waitlist.seat_at_table().unwrap().party.name, "Also big");
+    }
+
+    #[test]
+    fn party_sizes_are_checked() {
+        let mut waitlist = Waitlist::new(tables());
+        assert_eq!(waitlist.add_to_waitlist("Nobody", 0, None), Err(SeatingError::EmptyParty));
+        assert_eq!(
+            waitlist.add_to_waitlist("Crowd", 9, None),
+            Err(SeatingError::TooBig { size: 9, largest: 8 })
+        );
+        assert_eq!(waitlist.quote(9), Err(SeatingError::TooBig { size: 9, largest: 8 }));
+    }
+
+    #[test]
+    fn clearing_tables() {
+        let mut waitlist = Waitlist::new(tables());
+        let id = waitlist.add_to_waitlist("Ann", 2, None).unwrap();
+        waitlist.seat_at_table().unwrap();
+        assert_eq!(waitlist.free_tables().count(), 3);
+        assert_eq!(waitlist.clear_table(1), Ok(id));
+        assert_eq!(waitlist.clear_table(1), Err(SeatingError::TableNotTaken(1)));
+        assert_eq!(waitlist.clear_table(9), Err(SeatingError::UnknownTable(9)));
+    }
+
+    #[test]
+    fn removals_and_no_shows() {
+        let mut waitlist = Waitlist::new(tables());
+        let ann = waitlist.add_to_waitlist("Ann", 2, Some("555-0100")).unwrap();
+        let bo = waitlist.add_to_waitlist("Bo", 2, None).unwrap();
+        let cy = waitlist.add_to_waitlist("Cy", 2, None).unwrap();
+
+        assert_eq!(waitlist.remove(bo).unwrap().name, "Bo");
+        assert_eq!(waitlist.remove(bo), Err(SeatingError::UnknownParty(bo)));
+        waitlist.no_show(ann).unwrap();
+        assert_eq!(waitlist.no_shows()[0].phone.as_deref(), Some("555-0100"));
+
+        let waiting: Vec<PartyId> = waitlist.waiting().map(|party| party.id).collect();
+        assert_eq!(waiting, [cy]);
+    }
+
+    #[test]
+    fn estimates_start_from_the_default_turn() {
+        let clock = ManualClock::new(minutes(100));
+        let mut waitlist = Waitlist::new(vec![Table::new(1, 4)]).with_clock(&clock);
+        assert_eq!(waitlist.quote(2), Ok(minutes(0)));
+
+        waitlist.add_to_waitlist("Ann", 2, None).unwrap();
+        waitlist.seat_at_table().unwrap();
+        let bo = waitlist.add_to_waitlist("Bo", 2, None).unwrap();
+        assert_eq!(waitlist.estimated_wait(bo), Ok(minutes(45)));
+        assert_eq!(waitlist.quote(4), Ok(minutes(90)));
+
+        clock.advance(minutes(30));
+        assert_eq!(waitlist.estimated_wait(bo), Ok(minutes(15)));
+
+        // Running late: the table should be free any minute
+        //
+        clock.advance(minutes(30));
+        assert_eq!(waitlist.estimated_wait(bo), Ok(minutes(0)));
+    }
+
+    #[test]
+    fn estimates_follow_how_long_tables_really_take() {
+        let clock = ManualClock::new(minutes(0));
+        let mut waitlist = Waitlist::new(vec![Table::new(1, 2), Table::new(2, 2)]).with_clock(&clock);
+        for (name, stay) in [("Ann", 20), ("Bo", 40)] {
+            waitlist.add_to_waitlist(name, 2, None).unwrap();
+            let seated = waitlist.seat_at_table().unwrap();
+            clock.advance(minutes(stay));
+            waitlist.clear_table(seated.table).unwrap();
+        }
+
+        // Both tables free, turning every 30 minutes: the
+        // first two parties sit straight away, the third
+        // waits a turn
+        //
+        assert_eq!(waitlist.quote(2), Ok(minutes(0)));
+        waitlist.add_to_waitlist("Cy", 2, None).unwrap();
+        waitlist.add_to_waitlist("Di", 2, None).unwrap();
+        assert_eq!(waitlist.quote(2), Ok(minutes(30)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Table 1 is in the list twice")]
+    fn table_numbers_are_unique() {
+        Waitlist::new(vec![Table::new(1, 2), Table::new(1, 4)]);
+    }
+}
@@ -0,0 +1,121 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - The front of house's end of an order: taking it,
+//   bringing it out once the kitchen has it ready, and
+//   being paid for it. Voiding an order (the party left,
+//   or it was put in by mistake) happens here too.
+// - Every function is one checked move of the order's
+//   state (see src/order.rs); an illegal one is an
+//   OrderError.
+//
+
+use crate::order::{OrderError, OrderId, Orders, Status};
+
+pub fn take_order(orders: &mut Orders, table: u32, items: &[&str]) -> Result<OrderId, OrderError> {
+    orders.place(table, items)
+}
+
+pub fn serve_order(orders: &mut Orders, id: OrderId) -> Result<(), OrderError> {
+    orders.transition(id, Status::Served, None)
+}
+
+pub fn take_payment(orders: &mut Orders, id: OrderId) -> Result<(), OrderError> {
+    orders.transition(id, Status::Paid, None)
+}
+
+pub fn void_order(orders: &mut Orders, id: OrderId, reason: &str) -> Result<(), OrderError> {
+    crate::move_with_reason(orders, id, Status::Voided, reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::back_of_house::{cook_order, fix_incorrect_order, plate_order};
+    use crate::clock::ManualClock;
+    use std::time::Duration;
+
+    #[test]
+    fn an_order_from_start_to_finish() {
+        let clock = ManualClock::new(Duration::from_secs(0));
+        let mut orders = Orders::new().with_clock(&clock);
+        let id = take_order(&mut orders, 3, &["soup", "salad"]).unwrap();
+
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(cook_order(&mut orders), Ok(id));
+        clock.advance(Duration::from_secs(600));
+        plate_order(&mut orders, id).unwrap();
+        serve_order(&mut orders, id).unwrap();
+        clock.advance(Duration::from_secs(1800));
+        take_payment(&mut orders, id).unwrap();
+
+        let order = orders.get(id).unwrap();
+        assert_eq!(order.status(), Status::Paid);
+        let trail: Vec<(Option<Status>, Status, u64)> =
+            order.trail().iter().map(|t| (t.from, t.to, t.at.as_secs())).collect();
+        assert_eq!(
+            trail,
+            [
+                (None, Status::Placed, 0),
+                (Some(Status::Placed), Status::Cooking, 60),
+                (Some(Status::Cooking), Status::Ready, 660),
+                (Some(Status::Ready), Status::Served, 660),
+                (Some(Status::Served), Status::Paid, 2460),
+            ]
+        );
+    }
+
+    #[test]
+    fn nothing_is_served_before_it_is_cooked() {
+        let mut orders = Orders::new();
+        let id = take_order(&mut orders, 1, &["toast"]).unwrap();
+        assert_eq!(
+            serve_order(&mut orders, id),
+            Err(OrderError::IllegalTransition {
+                order: id,
+                from: Status::Placed,
+                to: Status::Served,
+            })
+        );
+        assert!(take_payment(&mut orders, id).is_err());
+    }
+
+    #[test]
+    fn a_corrected_order_is_cooked_again_first() {
+        let mut orders = Orders::new();
+        let wrong = take_order(&mut orders, 1, &["soup"]).unwrap();
+        cook_order(&mut orders).unwrap();
+        let waiting = take_order(&mut orders, 2, &["salad"]).unwrap();
+        plate_order(&mut orders, wrong).unwrap();
+        serve_order(&mut orders, wrong).unwrap();
+
+        fix_incorrect_order(&mut orders, wrong, "asked for no onions").unwrap();
+        assert_eq!(orders.kitchen_queue().collect::<Vec<_>>(), [wrong, waiting]);
+        assert_eq!(cook_order(&mut orders), Ok(wrong));
+
+        let order = orders.get(wrong).unwrap();
+        let correction = &order.trail()[4];
+        assert_eq!(correction.to, Status::Corrected);
+        assert_eq!(correction.reason.as_deref(), Some("asked for no onions"));
+        assert_eq!(order.status(), Status::Cooking);
+    }
+
+    #[test]
+    fn paid_and_voided_orders_are_finished() {
+        let mut orders = Orders::new();
+        let id = take_order(&mut orders, 1, &["soup"]).unwrap();
+        void_order(&mut orders, id, "left before it came").unwrap();
+        assert_eq!(cook_order(&mut orders), Err(OrderError::NothingToCook));
+        assert!(fix_incorrect_order(&mut orders, id, "cold").is_err());
+        assert!(void_order(&mut orders, id, "again").is_err());
+        assert_eq!(orders.get(id).unwrap().trail().len(), 2);
+    }
+
+    #[test]
+    fn blank_reasons_are_not_kept() {
+        let mut orders = Orders::new();
+        let id = take_order(&mut orders, 1, &["soup"]).unwrap();
+        void_order(&mut orders, id, "  ").unwrap();
+        assert_eq!(orders.get(id).unwrap().trail()[1].reason, None);
+    }
+}
@@ -9,25 +9,39 @@
 //    Paths:           A way of naming an item, such as a struct, function, or module
 //
 
-mod front_of_house {
-    pub mod hosting {
-        pub fn add_to_waitlist() {}
+pub mod clock;
+pub mod order;
 
-        fn seat_at_table() {}
-    }
+// Separating modules into different files: hosting and
+// serving grew too big to live here, so their bodies are
+// in src/front_of_house/. The module tree stays the same;
+// only where the code is kept changes.
+//
+mod front_of_house {
+    pub mod hosting;
+    pub mod serving;
+}
 
-    mod serving {
-        fn take_order() {}
+// Corrections and voids come from both ends of the house,
+// and both keep the reason, if there is one, with the order
+//
+fn move_with_reason(
+    orders: &mut order::Orders,
+    id: order::OrderId,
+    to: order::Status,
+    reason: &str,
+) -> Result<(), order::OrderError> {
+    let reason = reason.trim();
+    orders.transition(id, to, if reason.is_empty() { None } else { Some(reason) })
+}
 
-        fn serve_order() {}
+mod back_of_house {
+    use crate::order::{OrderError, OrderId, Orders, Status};
 
-        fn take_payment() {}
-    }
-}
+    pub mod menu;
 
-fn serve_order() {}
+    use menu::{Item, Menu, Season};
 
-mod back_of_house {
     pub struct Breakfast {
         pub toast: String,
         seasonal_fruit: String,
@@ -40,10 +54,24 @@ mod back_of_house {
         // private
         //
         pub fn summer(toast: &str) -> Breakfast {
-            Breakfast {
+            Breakfast::in_season(&Menu::house(), Season::Summer, toast)
+                .expect("the house menu has seasonal fruit")
+        }
+
+        // The fruit is whatever the menu's seasonal fruit is
+        // in that season. A menu without any gives no
+        // breakfast.
+        //
+        pub fn in_season(menu: &Menu, season: Season, toast: &str) -> Option<Breakfast> {
+            let fruit = menu.get("Seasonal fruit")?;
+            Some(Breakfast {
                 toast: String::from(toast),
-                seasonal_fruit: String::from("peaches"),
-            }
+                seasonal_fruit: String::from(fruit.name_in(season)),
+            })
+        }
+
+        pub fn seasonal_fruit(&self) -> &str {
+            &self.seasonal_fruit
         }
     }
 
@@ -55,12 +83,41 @@ mod back_of_house {
         Salad,
     }
 
-    fn fix_incorrect_order() {
-        cook_order();
-        super::serve_order();
+    impl Appetizer {
+        pub fn name(&self) -> &'static str {
+            match self {
+                Appetizer::Soup => "Soup",
+                Appetizer::Salad => "Salad",
+            }
+        }
+
+        pub fn on<'m>(&self, menu: &'m Menu) -> Option<&'m Item> {
+            menu.get(self.name())
+        }
+    }
+
+    // Starts cooking whatever is at the front of the
+    // kitchen's queue
+    //
+    pub fn cook_order(orders: &mut Orders) -> Result<OrderId, OrderError> {
+        let id = orders.next_to_cook()?;
+        orders.transition(id, Status::Cooking, None)?;
+        Ok(id)
+    }
+
+    pub fn plate_order(orders: &mut Orders, id: OrderId) -> Result<(), OrderError> {
+        orders.transition(id, Status::Ready, None)
     }
 
-    fn cook_order() {}
+    // Sends an order back to the kitchen, at the front of the
+    // queue, with what was wrong with it
+    //
+    pub fn fix_incorrect_order(orders: &mut Orders, id: OrderId, reason: &str) -> Result<(), OrderError> {
+        // super goes up a level, to the crate root, the same
+        // as .. does in a filesystem
+        //
+        super::move_with_reason(orders, id, Status::Corrected, reason)
+    }
 }
 
 // Bringing a module into scope idiomatically
@@ -86,6 +143,14 @@ mod back_of_house {
 // code would think about the domain...
 //
 pub use crate::front_of_house::hosting;
+pub use crate::front_of_house::serving;
+
+// back_of_house stays private (so Breakfast keeps its
+// secrets); only the kitchen's functions and the menu are
+// re-exported
+//
+pub use crate::back_of_house::menu;
+pub use crate::back_of_house::{cook_order, fix_incorrect_order, plate_order};
 
 // However, when bringing in structs, enums, and other items,
 // it's idiomatic to specify the full path
@@ -144,6 +209,7 @@ fn function2() -> IoResult<()> {
 
 // Using a glob operator:
 //
+#[allow(unused_imports)]
 use std::collections::*;
 
 
@@ -161,17 +227,37 @@ pub fn eat_at_restaurant() {
     // use statement above brings hosting into scope...
     //
 
-    hosting::add_to_waitlist();
+    let mut waitlist = hosting::Waitlist::new(vec![hosting::Table::new(1, 2), hosting::Table::new(2, 4)]);
+    waitlist.add_to_waitlist("Ferris", 2, None).unwrap();
+    match waitlist.seat_at_table() {
+        Ok(seated) => println!("{} is at table {}", seated.party.name, seated.table),
+        Err(err) => println!("Couldn't seat anyone: {}", err),
+    }
+
+    let mut orders = order::Orders::new();
+    let served = serving::take_order(&mut orders, 1, &["soup"])
+        .and_then(|_| back_of_house::cook_order(&mut orders))
+        .and_then(|id| back_of_house::plate_order(&mut orders, id).map(|_| id))
+        .and_then(|id| serving::serve_order(&mut orders, id).map(|_| id));
+    match served {
+        Ok(id) => println!("Order {} is on table 1", id.0),
+        Err(err) => println!("Couldn't serve the soup: {}", err),
+    }
 
     // In the example of
     let mut meal = back_of_house::Breakfast::summer("Rye");
     meal.toast = String::from("Wheat");
     println!("meal.toast: {}", meal.toast);
+    println!("meal.seasonal_fruit(): {}", meal.seasonal_fruit());
 
     // meal.seasonal_fruit = String::from("blueberries"); // this will fail
 
-    let order1 = back_of_house::Appetizer::Soup;
-    let order2 = back_of_house::Appetizer::Salad;
+    let menu = back_of_house::menu::Menu::house();
+    for appetizer in &[back_of_house::Appetizer::Soup, back_of_house::Appetizer::Salad] {
+        if let Some(item) = appetizer.on(&menu) {
+            println!("{}: {}", item.name, item.price);
+        }
+    }
 
     let mut map = HashMap::new();
     map.insert(1, 2);
@@ -179,8 +265,19 @@ pub fn eat_at_restaurant() {
 
 #[cfg(test)]
 mod tests {
+    use super::back_of_house::menu::{Menu, Season};
+    use super::back_of_house::Breakfast;
+
     #[test]
     fn it_works() {
         assert_eq!(2 + 2, 4);
     }
+
+    #[test]
+    fn the_fruit_comes_from_the_menu() {
+        assert_eq!(Breakfast::summer("Rye").seasonal_fruit(), "peaches");
+        let winter = Breakfast::in_season(&Menu::house(), Season::Winter, "Rye").unwrap();
+        assert_eq!(winter.seasonal_fruit(), "oranges");
+        assert!(Breakfast::in_season(&Menu::default(), Season::Summer, "Rye").is_none());
+    }
 }
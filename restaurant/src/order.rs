@@ -0,0 +1,298 @@
+//////////////////////////////////////////////////
+// General Notes
+//
+// - An Order goes Placed -> Cooking -> Ready -> Served ->
+//   Paid. Anything the kitchen got wrong, from Cooking
+//   onwards, can be sent back as Corrected, which cooks
+//   it again. Anything not yet paid for can be Voided.
+//   Paid and Voided are the end.
+// - Orders only move through the functions in
+//   front_of_house::serving and back_of_house, and every
+//   move is checked against the rules above. An illegal
+//   move is an OrderError and leaves the order as it was.
+// - Each order keeps an audit trail of every move it made,
+//   when, and why (for corrections and voids).
+// - The kitchen works through a queue: new orders join
+//   the back, corrected ones go to the front.
+//
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use crate::clock::{Clock, SystemClock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OrderId(pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Status {
+    Placed,
+    Cooking,
+    Ready,
+    Served,
+    Paid,
+    Corrected,
+    Voided,
+}
+
+impl Status {
+    pub fn can_become(self, next: Status) -> bool {
+        use Status::*;
+
+        match (self, next) {
+            (Placed, Cooking) | (Cooking, Ready) | (Ready, Served) | (Served, Paid) => true,
+            (Cooking, Corrected) | (Ready, Corrected) | (Served, Corrected) => true,
+            (Corrected, Cooking) => true,
+            (Paid, Voided) | (Voided, Voided) => false,
+            (_, Voided) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+// One move in an order's audit trail. The first one, when
+// the order is taken, comes from nowhere.
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transition {
+    pub from: Option<Status>,
+    pub to: Status,
+    pub at: Duration,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Order {
+    pub id: OrderId,
+    pub table: u32,
+    pub items: Vec<String>,
+    status: Status,
+    trail: Vec<Transition>,
+}
+
+impl Order {
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn trail(&self) -> &[Transition] {
+        &self.trail
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderError {
+    EmptyOrder,
+    UnknownOrder(OrderId),
+    NothingToCook,
+    IllegalTransition { order: OrderId, from: Status, to: Status },
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderError::EmptyOrder => write!(f, "an order needs at least one item"),
+            OrderError::UnknownOrder(id) => write!(f, "there is no order {}", id.0),
+            OrderError::NothingToCook => write!(f, "the kitchen has nothing to cook"),
+            OrderError::IllegalTransition { order, from, to } => {
+                write!(f, "order {} can't go from {} to {}", order.0, from, to)
+            }
+        }
+    }
+}
+
+impl Error for OrderError {}
+
+// Every order taken, and the kitchen's queue
+//
+pub struct Orders<'a> {
+    clock: &'a dyn Clock,
+    orders: Vec<Order>,
+    kitchen: VecDeque<OrderId>,
+}
+
+impl Default for Orders<'_> {
+    fn default() -> Self {
+        Orders::new()
+    }
+}
+
+impl<'a> Orders<'a> {
+    pub fn new() -> Orders<'a> {
+        Orders {
+            clock: &SystemClock,
+            orders: Vec::new(),
+            kitchen: VecDeque::new(),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: &'a dyn Clock) -> Orders<'a> {
+        self.clock = clock;
+        self
+    }
+
+    pub fn get(&self, id: OrderId) -> Option<&Order> {
+        self.orders.iter().find(|order| order.id == id)
+    }
+
+    pub fn kitchen_queue(&self) -> impl Iterator<Item = OrderId> + '_ {
+        self.kitchen.iter().copied()
+    }
+
+    pub(crate) fn place(&mut self, table: u32, items: &[&str]) -> Result<OrderId, OrderError> {
+        if items.is_empty() {
+            return Err(OrderError::EmptyOrder);
+        }
+        let id = OrderId(self.orders.len() as u32 + 1);
+        self.orders.push(Order {
+            id,
+            table,
+            items: items.iter().map(|item| String::from(*item)).collect(),
+            status: Status::Placed,
+            trail: vec![Transition {
+                from: None,
+                to: Status::Placed,
+                at: self.clock.now(),
+                reason: None,
+            }],
+        });
+        self.kitchen.push_back(id);
+        Ok(id)
+    }
+
+    pub(crate) fn next_to_cook(&self) -> Result<OrderId, OrderError> {
+        self.kitchen.front().copied().ok_or(OrderError::NothingToCook)
+    }
+
+    // Moves an order on, if the rules allow it, and keeps the
+    // kitchen queue in step: orders leave it when they start
+    // cooking or are voided, and come back (at the front)
+    // when they're corrected
+    //
+    pub(crate) fn transition(&mut self, id: OrderId, to: Status, reason: Option<&str>) -> Result<(), OrderError> {
+        let at = self.clock.now();
+        let order = self
+            .orders
+            .iter_mut()
+            .find(|order| order.id == id)
+            .ok_or(OrderError::UnknownOrder(id))?;
+        let from = order.status;
+        if !from.can_become(to) {
+            return Err(OrderError::IllegalTransition { order: id, from, to });
+        }
+
+        order.status = to;
+        order.trail.push(Transition {
+            from: Some(from),
+            to,
+            at,
+            reason: reason.map(String::from),
+        });
+        match to {
+            Status::Cooking | Status::Voided => self.kitchen.retain(|&queued| queued != id),
+            Status::Corrected => self.kitchen.push_front(id),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    const ALL: [Status; 7] = [
+        Status::Placed,
+        Status::Cooking,
+        Status::Ready,
+        Status::Served,
+        Status::Paid,
+        Status::Corrected,
+        Status::Voided,
+    ];
+
+    #[test]
+    fn the_legal_moves() {
+        let mut legal = Vec::new();
+        for &from in &ALL {
+            for &to in &ALL {
+                if from.can_become(to) {
+                    legal.push(format!("{}->{}", from, to));
+                }
+            }
+        }
+        assert_eq!(
+            legal,
+            [
+                "Placed->Cooking",
+                "Placed->Voided",
+                "Cooking->Ready",
+                "Cooking->Corrected",
+                "Cooking->Voided",
+                "Ready->Served",
+                "Ready->Corrected",
+                "Ready->Voided",
+                "Served->Paid",
+                "Served->Corrected",
+                "Served->Voided",
+                "Corrected->Cooking",
+                "Corrected->Voided",
+            ]
+        );
+    }
+
+    #[test]
+    fn illegal_moves_change_nothing() {
+        let clock = ManualClock::new(Duration::from_secs(60));
+        let mut orders = Orders::new().with_clock(&clock);
+        let id = orders.place(4, &["soup"]).unwrap();
+
+        assert_eq!(
+            orders.transition(id, Status::Served, None),
+            Err(OrderError::IllegalTransition {
+                order: id,
+                from: Status::Placed,
+                to: Status::Served,
+            })
+        );
+        let order = orders.get(id).unwrap();
+        assert_eq!(order.status(), Status::Placed);
+        assert_eq!(order.trail().len(), 1);
+        assert_eq!(order.trail()[0].at, Duration::from_secs(60));
+    }
+
+    #[test]
+    fn unknown_and_empty_orders() {
+        let mut orders = Orders::new();
+        assert_eq!(orders.place(1, &[]), Err(OrderError::EmptyOrder));
+        assert_eq!(
+            orders.transition(OrderId(7), Status::Cooking, None),
+            Err(OrderError::UnknownOrder(OrderId(7)))
+        );
+        assert_eq!(orders.next_to_cook(), Err(OrderError::NothingToCook));
+    }
+
+    #[test]
+    fn the_kitchen_queue_follows_the_orders() {
+        let mut orders = Orders::new();
+        let first = orders.place(1, &["soup"]).unwrap();
+        let second = orders.place(2, &["salad"]).unwrap();
+        let third = orders.place(3, &["toast"]).unwrap();
+
+        orders.transition(first, Status::Cooking, None).unwrap();
+        orders.transition(third, Status::Voided, Some("walked out")).unwrap();
+        assert_eq!(orders.kitchen_queue().collect::<Vec<_>>(), [second]);
+
+        orders.transition(first, Status::Corrected, Some("too salty")).unwrap();
+        assert_eq!(orders.kitchen_queue().collect::<Vec<_>>(), [first, second]);
+    }
+}